@@ -10,7 +10,7 @@ use time::{Timespec, at_utc};
 fn main() {
   let crontab = Crontab::parse("0 * * * *").expect("unparsable"); // every hour
 
-  println!("Schedule components: {:?}\n", crontab.schedule);
+  println!("Schedule components: {:?}\n", crontab.schedule());
 
   let mut timestamp = 1500001200;
 
@@ -0,0 +1,413 @@
+//! anacrontab files, for jobs that run every so many days on machines that
+//! aren't always on.
+
+use ast::{Span, words};
+use diagnostic::{Diagnostic, Hint};
+use error::CrontabError;
+use file::{FileError, Variable, parse_variable};
+use std::ops::Range;
+use time::{self, Duration, Tm};
+use times::{adv_month, adv_year, day_of_year, days_in_month, weekday};
+
+/// A parsed anacrontab file, such as `/etc/anacrontab`.
+///
+/// ```
+/// use crontab::{AnacronPeriod, Anacrontab};
+///
+/// let anacrontab = Anacrontab::parse(
+///   "START_HOURS_RANGE=3-22\n\
+///    RANDOM_DELAY=45\n\
+///    1\t5\tcron.daily\t\tnice run-parts /etc/cron.daily\n\
+///    @monthly 45 cron.monthly nice run-parts /etc/cron.monthly\n").unwrap();
+///
+/// assert_eq!(anacrontab.start_hours_range, Some(3 .. 22));
+/// assert_eq!(anacrontab.random_delay, 45);
+/// assert_eq!(anacrontab.jobs[0].period, AnacronPeriod::Days(1));
+/// assert_eq!(anacrontab.jobs[1].identifier, "cron.monthly");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Anacrontab {
+  /// The jobs, in order.
+  pub jobs: Vec<AnacronJob>,
+  /// The environment variable assignments, in order, including
+  /// `START_HOURS_RANGE` and `RANDOM_DELAY`.
+  pub variables: Vec<Variable>,
+  /// The hours jobs may start in, from `START_HOURS_RANGE`, eg. `3 .. 22`
+  /// for `3-22`, which lets jobs start from 3:00 up to 21:59.
+  pub start_hours_range: Option<Range<u32>>,
+  /// The most minutes added at random to each job's delay, from
+  /// `RANDOM_DELAY`, or 0.
+  pub random_delay: u32,
+}
+
+/// A job in an anacrontab.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AnacronJob {
+  /// The line number of the job, counting from 1.
+  pub line: usize,
+  /// How often the job runs.
+  pub period: AnacronPeriod,
+  /// How many minutes anacron waits before running the job.
+  pub delay: u32,
+  /// The name of the job, which names its timestamp file.
+  pub identifier: String,
+  /// The command to run.
+  pub command: String,
+}
+
+/// How often an anacron job runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AnacronPeriod {
+  /// Every so many days, eg. 7 or `@weekly`.
+  Days(u32),
+  /// Once a month, for `@monthly`.
+  Monthly,
+  /// Once a year, for `@yearly` or `@annually`.
+  Yearly,
+}
+
+/// When a job next runs. anacron adds a random delay of up to
+/// `RANDOM_DELAY` minutes, so the job runs somewhere between the two.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Due {
+  /// The earliest time the job runs.
+  pub earliest: Tm,
+  /// The latest time the job runs, if anacron is running then.
+  pub latest: Tm,
+}
+
+impl Anacrontab {
+  /// Parse an anacrontab. Jobs are lines of a period, a delay in minutes, an
+  /// identifier and a command. The first line that isn't valid is reported
+  /// as an error.
+  pub fn parse(text: &str) -> Result<Anacrontab, FileError> {
+    let mut anacrontab = Anacrontab {
+      jobs: Vec::new(),
+      variables: Vec::new(),
+      start_hours_range: None,
+      random_delay: 0,
+    };
+
+    for (i, line) in text.lines().enumerate() {
+      anacrontab.parse_line(i + 1, line)
+          .map_err(|diagnostic| FileError::new(i + 1, diagnostic))?;
+    }
+
+    Ok(anacrontab)
+  }
+
+  fn parse_line(&mut self, number: usize, line: &str) -> Result<(), Diagnostic> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+      return Ok(());
+    }
+
+    let variable = match parse_variable(trimmed) {
+      Some(variable) => variable,
+      None => {
+        self.jobs.push(parse_job(number, line)?);
+        return Ok(());
+      },
+    };
+
+    let offset = line.len() - trimmed.len();
+    let span = Span::new(offset, line.len());
+    let invalid = |what: &str| {
+      let error = CrontabError::ErrCronFormat(
+        format!("Invalid {}: {}", what, variable.value));
+      Diagnostic::new(error, line, span, None)
+    };
+
+    match variable.name.as_str() {
+      "START_HOURS_RANGE" => {
+        let range = parse_hours(&variable.value)
+            .ok_or_else(|| invalid("START_HOURS_RANGE"))?;
+        self.start_hours_range = Some(range);
+      },
+      "RANDOM_DELAY" => {
+        self.random_delay = variable.value.parse()
+            .map_err(|_| invalid("RANDOM_DELAY"))?;
+      },
+      _ => {},
+    }
+
+    self.variables.push(variable);
+    Ok(())
+  }
+
+  /// When a job next runs, given when it last ran.
+  ///
+  /// anacron only records the day a job last ran, so the job becomes due at
+  /// the start of the day its period later, or at the start of
+  /// `START_HOURS_RANGE` that day. It then runs after its delay, plus up to
+  /// `RANDOM_DELAY` minutes. A monthly job is due on the same day of the
+  /// next month, or that month's last day if it is shorter.
+  ///
+  /// ```
+  /// # extern crate crontab;
+  /// # extern crate time;
+  /// # fn main() {
+  /// let anacrontab = crontab::Anacrontab::parse(
+  ///   "START_HOURS_RANGE=3-22\nRANDOM_DELAY=45\n7 25 cron.weekly run-parts /etc/cron.weekly").unwrap();
+  ///
+  /// // 2017-10-14 18:30 UTC.
+  /// let last_run = time::at_utc(time::Timespec::new(1508005800, 0));
+  /// let due = anacrontab.due(&anacrontab.jobs[0], &last_run);
+  ///
+  /// // 2017-10-21, from 3:25 to 4:10.
+  /// assert_eq!((due.earliest.tm_mday, due.earliest.tm_hour, due.earliest.tm_min), (21, 3, 25));
+  /// assert_eq!((due.latest.tm_mday, due.latest.tm_hour, due.latest.tm_min), (21, 4, 10));
+  /// # }
+  /// ```
+  pub fn due(&self, job: &AnacronJob, last_run: &Tm) -> Due {
+    let mut earliest = due_date(job.period, last_run);
+    earliest.tm_hour = self.start_hours_range.as_ref().map_or(0, |range| range.start as i32);
+
+    let earliest = add(&earliest, Duration::minutes(i64::from(job.delay)));
+    let latest = add(&earliest, Duration::minutes(i64::from(self.random_delay)));
+
+    Due {
+      earliest: normalize(earliest),
+      latest: normalize(latest),
+    }
+  }
+
+  /// Whether anacron would start a job at a time, given when it last ran:
+  /// its period has passed, and the time is within `START_HOURS_RANGE`. The
+  /// job's delay isn't included.
+  pub fn is_due(&self, job: &AnacronJob, last_run: &Tm, now: &Tm) -> bool {
+    let due = due_date(job.period, last_run);
+    let today = (now.tm_year, now.tm_mon, now.tm_mday);
+    let in_hours = match self.start_hours_range {
+      Some(ref range) => range.contains(&(now.tm_hour as u32)),
+      None => true,
+    };
+
+    today >= (due.tm_year, due.tm_mon, due.tm_mday) && in_hours
+  }
+}
+
+/// The start of the day a job is due on.
+fn due_date(period: AnacronPeriod, last_run: &Tm) -> Tm {
+  let mut date = *last_run;
+  date.tm_hour = 0;
+  date.tm_min = 0;
+  date.tm_sec = 0;
+  date.tm_nsec = 0;
+
+  match period {
+    AnacronPeriod::Days(days) => date = add(&date, Duration::days(i64::from(days))),
+    AnacronPeriod::Monthly => adv_month(&mut date),
+    AnacronPeriod::Yearly => adv_year(&mut date),
+  }
+
+  // Stay within shorter months, eg. a month after January 31st.
+  date.tm_mday = date.tm_mday.min(days_in_month(&date));
+  date
+}
+
+/// The time a duration later on the calendar, keeping the time's offset
+/// rather than converting it to UTC as `Tm + Duration` does.
+fn add(time: &Tm, duration: Duration) -> Tm {
+  let mut utc = *time;
+  utc.tm_utcoff = 0;
+
+  let mut later = time::at_utc(utc.to_timespec() + duration);
+  later.tm_utcoff = time.tm_utcoff;
+  later.tm_isdst = time.tm_isdst;
+  later
+}
+
+/// The time with its day of week and day of year made consistent with its
+/// date.
+fn normalize(mut time: Tm) -> Tm {
+  time.tm_wday = weekday(&time) as i32;
+  time.tm_yday = day_of_year(&time);
+  time
+}
+
+/// A `START_HOURS_RANGE` such as `3-22`.
+fn parse_hours(value: &str) -> Option<Range<u32>> {
+  let dash = value.find('-')?;
+  let start = value[.. dash].trim().parse::<u32>().ok()?;
+  let end = value[dash + 1 ..].trim().parse::<u32>().ok()?;
+
+  if start < end && end <= 24 {
+    Some(start .. end)
+  } else {
+    None
+  }
+}
+
+fn parse_job(number: usize, line: &str) -> Result<AnacronJob, Diagnostic> {
+  let words = words(line);
+
+  if words.len() < 4 {
+    let error = CrontabError::ErrCronFormat(format!("Invalid job: {}", line.trim()));
+    let span = Span::new(words[0].1.start, words[words.len() - 1].1.end);
+    return Err(Diagnostic::new(error, line, span, None).with_hint(Hint::AnacronJob));
+  }
+
+  let invalid = |what: &str, (word, span): (&str, Span)| {
+    let error = CrontabError::ErrCronFormat(format!("Invalid {}: {}", what, word));
+    Diagnostic::new(error, line, span, None).with_hint(Hint::AnacronJob)
+  };
+
+  let period = parse_period(words[0].0).ok_or_else(|| invalid("period", words[0]))?;
+  let delay = words[1].0.parse::<u32>().map_err(|_| invalid("delay", words[1]))?;
+
+  let identifier = words[2].0;
+  if identifier.contains('/') {
+    return Err(invalid("job identifier", words[2]));
+  }
+
+  Ok(AnacronJob {
+    line: number,
+    period,
+    delay,
+    identifier: identifier.to_string(),
+    command: line[words[2].1.end ..].trim().to_string(),
+  })
+}
+
+/// A period in days, or a name such as `@monthly`.
+fn parse_period(period: &str) -> Option<AnacronPeriod> {
+  match period {
+    "@daily" => Some(AnacronPeriod::Days(1)),
+    "@weekly" => Some(AnacronPeriod::Days(7)),
+    "@monthly" => Some(AnacronPeriod::Monthly),
+    "@yearly" | "@annually" => Some(AnacronPeriod::Yearly),
+    _ => period.parse::<u32>().ok().filter(|days| *days > 0).map(AnacronPeriod::Days),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+  use test_helpers::{get_tm, normal};
+
+  const ANACRONTAB : &str = "\
+# /etc/anacrontab: configuration file for anacron
+SHELL=/bin/sh
+PATH=/sbin:/bin:/usr/sbin:/usr/bin
+RANDOM_DELAY=45
+START_HOURS_RANGE=3-22
+
+#period in days   delay in minutes   job-identifier   command
+1\t5\tcron.daily\t\tnice run-parts /etc/cron.daily
+7\t25\tcron.weekly\t\tnice run-parts /etc/cron.weekly
+@monthly 45\tcron.monthly\t\tnice run-parts /etc/cron.monthly
+";
+
+  fn job(period: &str) -> AnacronJob {
+    let text = format!("{} 0 job true", period);
+    Anacrontab::parse(&text).unwrap().jobs.remove(0)
+  }
+
+  #[test]
+  fn parse() {
+    let anacrontab = Anacrontab::parse(ANACRONTAB).unwrap();
+
+    expect!(anacrontab.variables.len()).to(be_equal_to(4));
+    expect!(anacrontab.start_hours_range.clone()).to(be_some().value(3 .. 22));
+    expect!(anacrontab.random_delay).to(be_equal_to(45));
+    expect!(anacrontab.jobs.len()).to(be_equal_to(3));
+
+    let weekly = &anacrontab.jobs[1];
+    expect!(weekly.line).to(be_equal_to(9));
+    expect!(weekly.period).to(be_equal_to(AnacronPeriod::Days(7)));
+    expect!(weekly.delay).to(be_equal_to(25));
+    expect!(weekly.identifier.as_str()).to(be_equal_to("cron.weekly"));
+    expect!(weekly.command.as_str()).to(be_equal_to("nice run-parts /etc/cron.weekly"));
+
+    expect!(anacrontab.jobs[2].period).to(be_equal_to(AnacronPeriod::Monthly));
+  }
+
+  #[test]
+  fn periods() {
+    expect!(parse_period("3")).to(be_some().value(AnacronPeriod::Days(3)));
+    expect!(parse_period("@daily")).to(be_some().value(AnacronPeriod::Days(1)));
+    expect!(parse_period("@weekly")).to(be_some().value(AnacronPeriod::Days(7)));
+    expect!(parse_period("@annually")).to(be_some().value(AnacronPeriod::Yearly));
+    expect!(parse_period("0")).to(be_none());
+    expect!(parse_period("@hourly")).to(be_none());
+  }
+
+  #[test]
+  fn errors() {
+    let error = Anacrontab::parse("SHELL=/bin/sh\n@hourly 5 job true").unwrap_err();
+    expect!(error.line).to(be_equal_to(2));
+    expect!(error.diagnostic.span.text(&error.diagnostic.source)).to(be_equal_to("@hourly"));
+
+    let error = Anacrontab::parse("1 soon job true").unwrap_err();
+    expect!(error.diagnostic.error.to_string()).to(be_equal_to("Invalid delay: soon".to_string()));
+
+    expect!(Anacrontab::parse("1 5 cron/daily true")).to(be_err());
+    expect!(Anacrontab::parse("1 5 job")).to(be_err());
+    expect!(Anacrontab::parse("START_HOURS_RANGE=22-3")).to(be_err());
+    expect!(Anacrontab::parse("START_HOURS_RANGE=3-25")).to(be_err());
+    expect!(Anacrontab::parse("RANDOM_DELAY=lots")).to(be_err());
+  }
+
+  #[test]
+  fn due_dates() {
+    let last_run = get_tm(2017, 1, 31, 18, 30, 0);
+
+    expect!(normal(&due_date(AnacronPeriod::Days(1), &last_run)))
+        .to(be_equal_to(get_tm(2017, 2, 1, 0, 0, 0)));
+    expect!(normal(&due_date(AnacronPeriod::Days(30), &last_run)))
+        .to(be_equal_to(get_tm(2017, 3, 2, 0, 0, 0)));
+    expect!(normal(&due_date(AnacronPeriod::Monthly, &last_run)))
+        .to(be_equal_to(get_tm(2017, 2, 28, 0, 0, 0)));
+    expect!(normal(&due_date(AnacronPeriod::Yearly, &get_tm(2016, 2, 29, 9, 0, 0))))
+        .to(be_equal_to(get_tm(2017, 2, 28, 0, 0, 0)));
+  }
+
+  #[test]
+  fn due() {
+    let anacrontab = Anacrontab::parse(ANACRONTAB).unwrap();
+    let due = anacrontab.due(&anacrontab.jobs[0], &get_tm(2017, 12, 31, 23, 0, 0));
+
+    expect!(normal(&due.earliest)).to(be_equal_to(get_tm(2018, 1, 1, 3, 5, 0)));
+    expect!(normal(&due.latest)).to(be_equal_to(get_tm(2018, 1, 1, 3, 50, 0)));
+    expect!(due.earliest.tm_wday).to(be_equal_to(1)); // 2018 started on a Monday.
+
+    // Without the variables, jobs are due from midnight.
+    let anacrontab = Anacrontab::parse("").unwrap();
+    let due = anacrontab.due(&job("2"), &get_tm(2017, 10, 14, 9, 0, 0));
+    expect!(normal(&due.earliest)).to(be_equal_to(get_tm(2017, 10, 16, 0, 0, 0)));
+    expect!(normal(&due.latest)).to(be_equal_to(normal(&due.earliest)));
+  }
+
+  #[test]
+  fn long_delays_and_periods() {
+    let anacrontab = Anacrontab::parse("RANDOM_DELAY=4000000000").unwrap();
+    let due = anacrontab.due(&job("1"), &get_tm(2017, 10, 14, 9, 0, 0));
+    expect!(normal(&due.earliest)).to(be_equal_to(get_tm(2017, 10, 15, 0, 0, 0)));
+    // 4000000000 minutes is 2777777 days and 18:40.
+    expect!(normal(&due.latest)).to(be_equal_to(get_tm(9623, 1, 31, 18, 40, 0)));
+
+    let due = anacrontab.due(&job("4000000000"), &get_tm(2017, 10, 14, 9, 0, 0));
+    expect!(due.earliest.tm_year + 1900).to(be_greater_than(10_000_000));
+  }
+
+  #[test]
+  fn is_due() {
+    let anacrontab = Anacrontab::parse(ANACRONTAB).unwrap();
+    let weekly = &anacrontab.jobs[1];
+    let last_run = get_tm(2017, 10, 14, 9, 0, 0);
+
+    expect!(anacrontab.is_due(weekly, &last_run, &get_tm(2017, 10, 20, 12, 0, 0))).to(be_false());
+    expect!(anacrontab.is_due(weekly, &last_run, &get_tm(2017, 10, 21, 2, 59, 0))).to(be_false());
+    expect!(anacrontab.is_due(weekly, &last_run, &get_tm(2017, 10, 21, 3, 0, 0))).to(be_true());
+    expect!(anacrontab.is_due(weekly, &last_run, &get_tm(2017, 11, 2, 21, 59, 0))).to(be_true());
+    expect!(anacrontab.is_due(weekly, &last_run, &get_tm(2017, 11, 2, 22, 0, 0))).to(be_false());
+
+    let monthly = job("@monthly");
+    let anacrontab = Anacrontab::parse("").unwrap();
+    expect!(anacrontab.is_due(&monthly, &last_run, &get_tm(2017, 11, 13, 23, 0, 0))).to(be_false());
+    expect!(anacrontab.is_due(&monthly, &last_run, &get_tm(2017, 11, 14, 0, 0, 0))).to(be_true());
+    expect!(anacrontab.is_due(&monthly, &last_run, &get_tm(2018, 1, 1, 0, 0, 0))).to(be_true());
+  }
+}
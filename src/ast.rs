@@ -0,0 +1,918 @@
+//! A syntax tree of crontab expressions, as written.
+//!
+//! Parsing a schedule expands each field into its values, which loses how
+//! the user wrote it: `*/15` and `0,15,30,45` give the same schedule. The
+//! syntax tree keeps each part of the expression along with its `Span` in
+//! the source, for tools that work on the expression itself.
+
+use diagnostic::{Diagnostic, Hint, Located};
+use dialect::Dialect;
+use error::CrontabError;
+use std::fmt;
+use suggest::suggest;
+
+/// A byte range `[start, end)` in the source of an expression.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+  /// Byte offset of the first character.
+  pub start: usize,
+  /// Byte offset just past the last character.
+  pub end: usize,
+}
+
+impl Span {
+  /// Create a span from byte offsets.
+  pub fn new(start: usize, end: usize) -> Span {
+    Span {
+      start,
+      end,
+    }
+  }
+
+  /// The length of the span in bytes.
+  pub fn len(&self) -> usize {
+    self.end - self.start
+  }
+
+  /// Whether the span is empty.
+  pub fn is_empty(&self) -> bool {
+    self.start == self.end
+  }
+
+  /// The text of the span within the source it was parsed from.
+  pub fn text<'a>(&self, source: &'a str) -> &'a str {
+    &source[self.start .. self.end]
+  }
+
+  /// The span shifted right by `offset` bytes.
+  pub (crate) fn offset(self, offset: usize) -> Span {
+    Span::new(self.start + offset, self.end + offset)
+  }
+}
+
+/// The fields of a crontab expression, in the order of a five-field
+/// expression. Dialects such as AWS add a year, and Quartz and Spring start
+/// with the second.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FieldKind {
+  /// Seconds, [0,59], the first field of Quartz and Spring expressions.
+  Second,
+  /// Minutes, [0,59].
+  Minute,
+  /// Hours, [0,23].
+  Hour,
+  /// Days of the month, [1,31].
+  DayOfMonth,
+  /// Months, [1,12], or `JAN`-`DEC`.
+  Month,
+  /// Days of the week, [0,6] with 0 as Sunday, or `SUN`-`SAT`.
+  DayOfWeek,
+  /// Years, [1970,2199], the last field of AWS and Quartz expressions.
+  Year,
+}
+
+impl FieldKind {
+  /// The smallest value of the field, as in Vixie cron. See the dialect
+  /// for the range it accepts.
+  pub fn min(self) -> u32 {
+    match self {
+      FieldKind::Second | FieldKind::Minute | FieldKind::Hour | FieldKind::DayOfWeek => 0,
+      FieldKind::DayOfMonth | FieldKind::Month => 1,
+      FieldKind::Year => 1970,
+    }
+  }
+
+  /// The largest value of the field, as in Vixie cron.
+  pub fn max(self) -> u32 {
+    match self {
+      FieldKind::Second | FieldKind::Minute => 59,
+      FieldKind::Hour => 23,
+      FieldKind::DayOfMonth => 31,
+      FieldKind::Month => 12,
+      FieldKind::DayOfWeek => 6,
+      FieldKind::Year => 2199,
+    }
+  }
+
+  /// The name of the field, eg. "day of month".
+  pub fn name(self) -> &'static str {
+    match self {
+      FieldKind::Second => "second",
+      FieldKind::Minute => "minute",
+      FieldKind::Hour => "hour",
+      FieldKind::DayOfMonth => "day of month",
+      FieldKind::Month => "month",
+      FieldKind::DayOfWeek => "day of week",
+      FieldKind::Year => "year",
+    }
+  }
+}
+
+/// A nickname for a common schedule, such as `@daily`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Macro {
+  /// `@yearly`, once a year at midnight on January 1st.
+  Yearly,
+  /// `@annually`, the same as `@yearly`.
+  Annually,
+  /// `@monthly`, once a month at midnight on the 1st.
+  Monthly,
+  /// `@weekly`, once a week at midnight on Sunday.
+  Weekly,
+  /// `@daily`, once a day at midnight.
+  Daily,
+  /// `@midnight`, the same as `@daily`.
+  Midnight,
+  /// `@hourly`, once an hour at minute 0.
+  Hourly,
+}
+
+const MACROS : [Macro; 7] = [
+  Macro::Yearly,
+  Macro::Annually,
+  Macro::Monthly,
+  Macro::Weekly,
+  Macro::Daily,
+  Macro::Midnight,
+  Macro::Hourly,
+];
+
+impl Macro {
+  /// The macro as written, eg. "@daily".
+  pub fn name(self) -> &'static str {
+    match self {
+      Macro::Yearly => "@yearly",
+      Macro::Annually => "@annually",
+      Macro::Monthly => "@monthly",
+      Macro::Weekly => "@weekly",
+      Macro::Daily => "@daily",
+      Macro::Midnight => "@midnight",
+      Macro::Hourly => "@hourly",
+    }
+  }
+
+  /// The five-field expression the macro stands for, eg. "0 0 * * *".
+  pub fn expansion(self) -> &'static str {
+    match self {
+      Macro::Yearly | Macro::Annually => "0 0 1 1 *",
+      Macro::Monthly => "0 0 1 * *",
+      Macro::Weekly => "0 0 * * 0",
+      Macro::Daily | Macro::Midnight => "0 0 * * *",
+      Macro::Hourly => "0 * * * *",
+    }
+  }
+
+  /// The macro for a name such as "@daily".
+  pub fn from_name(name: &str) -> Option<Macro> {
+    MACROS.iter().find(|m| m.name() == name).cloned()
+  }
+}
+
+/// A parsed crontab expression.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Expression {
+  /// The time zone prefix, if any, eg. `CRON_TZ=Europe/London`.
+  pub zone: Option<Zone>,
+  /// The fields or macro of the expression.
+  pub kind: ExpressionKind,
+  /// The fields or macro, without the zone prefix or surrounding
+  /// whitespace.
+  pub span: Span,
+}
+
+/// A time zone prefix on an expression, as accepted by cronie and
+/// Kubernetes, eg. `CRON_TZ=Europe/London 0 9 * * *`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Zone {
+  /// The variable used to set the zone.
+  pub variable: ZoneVariable,
+  /// The name of the time zone as written, eg. "Europe/London".
+  pub name: String,
+  /// The whole prefix, eg. `CRON_TZ=Europe/London`.
+  pub span: Span,
+}
+
+/// The variable of a time zone prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ZoneVariable {
+  /// `CRON_TZ=`
+  CronTz,
+  /// `TZ=`
+  Tz,
+}
+
+impl ZoneVariable {
+  /// The variable as written, eg. "CRON_TZ".
+  pub fn name(self) -> &'static str {
+    match self {
+      ZoneVariable::CronTz => "CRON_TZ",
+      ZoneVariable::Tz => "TZ",
+    }
+  }
+}
+
+/// What an expression is made of.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ExpressionKind {
+  /// Five whitespace-separated fields, eg. `*/15 9-17 * * MON-FRI`.
+  Fields(Vec<Field>),
+  /// A macro, eg. `@daily`.
+  Macro(Macro),
+}
+
+/// A single field of an expression.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Field {
+  /// Which field this is.
+  pub kind: FieldKind,
+  /// The comma-separated items of the field.
+  pub items: Vec<Item>,
+  /// The whole field.
+  pub span: Span,
+}
+
+/// One comma-separated item of a field.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Item {
+  /// What the item is.
+  pub kind: ItemKind,
+  /// The whole item.
+  pub span: Span,
+}
+
+/// What an item is.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+  /// A special character, eg. `*`.
+  Special(Special),
+  /// A single number or name, eg. `5` or `MON`.
+  Value(Atom),
+  /// An inclusive range, eg. `1-5` or `MON-FRI`.
+  Range(Atom, Atom),
+  /// An item with a step, eg. `*/15`, `0-30/5` or `5/15`. The stepped item
+  /// is a special character, a range, or a value, which ranges up to the
+  /// end of the field.
+  Step(Box<Item>, Atom),
+  /// A number of days before the last day of the month, eg. `L-3`.
+  LastDayOffset(Atom),
+  /// The weekday, Monday to Friday, nearest a day of the month, eg. `15W`.
+  NearestBusinessDay(Atom),
+  /// The last of a day of the week in the month, eg. `5L` or `FRIL`.
+  Last(Atom),
+  /// The nth of a day of the week in the month, eg. `MON#2`.
+  Nth(Atom, Atom),
+}
+
+/// A special character standing in for values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Special {
+  /// `*`, every value of the field.
+  Any,
+  /// `?`, no specific value, for the day field that the other one
+  /// overrides. In the Kubernetes dialect, every value, as `*`.
+  NoSpecificValue,
+  /// `L`, the last day of the month, or Saturday as a day of the week.
+  Last,
+  /// `LW`, the last weekday, Monday to Friday, of the month.
+  LastBusinessDay,
+}
+
+/// A number or name within an item.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Atom {
+  /// The number or name.
+  pub kind: AtomKind,
+  /// The number or name as written.
+  pub span: Span,
+}
+
+/// What an atom is.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AtomKind {
+  /// A number, eg. `5`.
+  Number(u32),
+  /// A name as written, eg. `MON` or `jan`. Names aren't checked until the
+  /// expression is evaluated.
+  Name(String),
+}
+
+impl Expression {
+  /// Parse the syntax of an expression. This checks the structure of the
+  /// expression and its numbers, but not whether names are known or values
+  /// are within range.
+  ///
+  /// ```
+  /// use crontab::{Expression, ExpressionKind, ItemKind, Special};
+  ///
+  /// let expression = Expression::parse("*/15 9-17 * * MON-FRI").unwrap();
+  /// if let ExpressionKind::Fields(ref fields) = expression.kind {
+  ///   let item = &fields[0].items[0];
+  ///   assert_eq!(item.span.text("*/15 9-17 * * MON-FRI"), "*/15");
+  ///   if let ItemKind::Step(ref base, _) = item.kind {
+  ///     assert_eq!(base.kind, ItemKind::Special(Special::Any));
+  ///   }
+  /// }
+  /// ```
+  pub fn parse(source: &str) -> Result<Expression, CrontabError> {
+    Expression::parse_dialect(source, Dialect::Vixie)
+  }
+
+  /// Parse the syntax of an expression in a dialect.
+  ///
+  /// ```
+  /// use crontab::{Dialect, Expression};
+  ///
+  /// let source = "cron(0 12 ? * MON-FRI *)";
+  /// let expression = Expression::parse_dialect(source, Dialect::Aws).unwrap();
+  /// assert_eq!(expression.fields().unwrap().len(), 6);
+  /// assert_eq!(expression.span.text(source), "0 12 ? * MON-FRI *");
+  /// ```
+  pub fn parse_dialect(source: &str, dialect: Dialect)
+      -> Result<Expression, CrontabError> {
+    parse_expression(source, dialect).map_err(|diagnostic| diagnostic.error)
+  }
+
+  /// The fields of the expression, or `None` for a macro.
+  pub fn fields(&self) -> Option<&[Field]> {
+    match self.kind {
+      ExpressionKind::Fields(ref fields) => Some(fields),
+      ExpressionKind::Macro(_) => None,
+    }
+  }
+}
+
+/// Parse the syntax of an expression in a dialect, locating any error in
+/// the source.
+pub (crate) fn parse_expression(source: &str, dialect: Dialect)
+    -> Result<Expression, Diagnostic> {
+  let body = body(source, dialect)?;
+  let mut words : Vec<(&str, Span)> = words(body.text(source)).into_iter()
+      .map(|(word, span)| (word, span.offset(body.start)))
+      .collect();
+
+  let zone = match words.first() {
+    Some(&(word, span)) => {
+      parse_zone(word, span).map_err(|error| {
+        Diagnostic::new(error, source, span, None)
+            .with_hint(Hint::Zone)
+      })?
+    },
+    None => None,
+  };
+
+  if let Some(ref zone) = zone {
+    if !dialect.zones() {
+      let error = CrontabError::ErrCronFormat(
+        format!("The {} dialect has no time zone prefix: {}", dialect, words[0].0));
+      return Err(Diagnostic::new(error, source, zone.span, None)
+          .with_hint(Hint::ZonePrefix)
+          .in_dialect(dialect));
+    }
+  }
+
+  if zone.is_some() {
+    words.remove(0);
+  }
+
+  if words.len() == 1 && words[0].0.starts_with('@') && dialect.macros() {
+    let (word, span) = words[0];
+    return match Macro::from_name(word) {
+      Some(m) => Ok(Expression { zone, kind: ExpressionKind::Macro(m), span }),
+      None => {
+        let names : Vec<&str> = MACROS.iter().map(|m| m.name()).collect();
+        let error = CrontabError::UnknownName {
+          name: word.to_string(),
+          field: None,
+          span,
+          suggestion: suggest(word, &names).map(|name| name.to_string()),
+        };
+        Err(Diagnostic::new(error, source, span, None))
+      },
+    };
+  }
+
+  let mut kinds = dialect.fields();
+  if dialect.optional_year() && words.len() + 1 == kinds.len() {
+    kinds = &kinds[.. kinds.len() - 1];
+  }
+  if words.len() != kinds.len() {
+    let span = match (words.first(), words.last()) {
+      (Some(first), Some(last)) => Span::new(first.1.start, last.1.end),
+      _ => Span::new(body.end, body.end),
+    };
+    let error = CrontabError::ErrCronFormat(
+      format!("Invalid format: {}", source));
+    return Err(Diagnostic::new(error, source, span, None)
+        .with_hint(Hint::FieldCount)
+        .in_dialect(dialect));
+  }
+
+  let fields = words.iter()
+      .zip(kinds.iter())
+      .map(|(&(word, span), kind)| {
+        parse_field(word, span, *kind, dialect).map_err(|(error, span)| {
+          Diagnostic::new(error, source, span, Some(*kind)).in_dialect(dialect)
+        })
+      })
+      .collect::<Result<Vec<Field>, Diagnostic>>()?;
+
+  check_no_specific_values(&fields, dialect).map_err(|(error, span)| {
+    Diagnostic::new(error, source, span, None)
+        .with_hint(Hint::NoSpecificValue)
+  })?;
+
+  let span = Span::new(fields[0].span.start, fields[fields.len() - 1].span.end);
+
+  Ok(Expression {
+    zone,
+    kind: ExpressionKind::Fields(fields),
+    span,
+  })
+}
+
+/// The part of the source holding the expression: all of it, or what is
+/// inside an AWS `cron(...)` wrapper.
+fn body(source: &str, dialect: Dialect) -> Result<Span, Diagnostic> {
+  let trimmed = source.trim();
+  let start = source.len() - source.trim_start().len();
+  let span = Span::new(start, start + trimmed.len());
+
+  if dialect != Dialect::Aws || !trimmed.starts_with("cron(") {
+    return Ok(Span::new(0, source.len()));
+  }
+
+  if !trimmed.ends_with(')') {
+    let error = CrontabError::ErrCronFormat(
+      format!("Missing `)` after the expression: {}", source));
+    return Err(Diagnostic::new(error, source, span, None));
+  }
+
+  Ok(Span::new(span.start + "cron(".len(), span.end - 1))
+}
+
+/// Check that `?` is used in at most one day field, and that exactly one of
+/// them is `?` in dialects that require it. Where `?` means `*`, anything
+/// goes.
+fn check_no_specific_values(fields: &[Field], dialect: Dialect) -> Located<()> {
+  if dialect.any_value_question_mark() {
+    return Ok(());
+  }
+
+  let no_specific_value = |field: &Field| {
+    field.items.iter().any(|item| item.kind == ItemKind::Special(Special::NoSpecificValue))
+  };
+
+  for field in fields {
+    if no_specific_value(field) && field.items.len() > 1 {
+      let error = CrontabError::ErrCronFormat(
+        format!("`?` can't be combined with other values: {}", field));
+      return Err((error, field.span));
+    }
+  }
+
+  let days : Vec<&Field> = fields.iter()
+      .filter(|field| field.kind == FieldKind::DayOfMonth || field.kind == FieldKind::DayOfWeek)
+      .collect();
+  let count = days.iter().filter(|field| no_specific_value(field)).count();
+
+  if count > 1 || (count == 0 && dialect.requires_no_specific_day()) {
+    let span = Span::new(days[0].span.start, days[days.len() - 1].span.end);
+    let error = CrontabError::ErrCronFormat(if count > 1 {
+      "Only one of the day of month and day of week can be `?`".to_string()
+    } else {
+      "One of the day of month and day of week must be `?`".to_string()
+    });
+    return Err((error, span));
+  }
+
+  Ok(())
+}
+
+/// The whitespace-separated words of the source, with their spans.
+pub (crate) fn words(source: &str) -> Vec<(&str, Span)> {
+  let mut words = Vec::new();
+  let mut start = None;
+
+  for (i, c) in source.char_indices() {
+    match (c.is_whitespace(), start) {
+      (true, Some(s)) => {
+        words.push((&source[s .. i], Span::new(s, i)));
+        start = None;
+      },
+      (false, None) => start = Some(i),
+      _ => {},
+    }
+  }
+
+  if let Some(s) = start {
+    words.push((&source[s ..], Span::new(s, source.len())));
+  }
+
+  words
+}
+
+/// A `CRON_TZ=` or `TZ=` prefix, or `None` if the word isn't one.
+fn parse_zone(word: &str, span: Span) -> Result<Option<Zone>, CrontabError> {
+  let variables = [ZoneVariable::CronTz, ZoneVariable::Tz];
+
+  for variable in variables.iter() {
+    let prefix = format!("{}=", variable.name());
+    if !word.starts_with(&prefix) {
+      continue;
+    }
+
+    let name = &word[prefix.len() ..];
+    if name.is_empty() {
+      return Err(CrontabError::ErrCronFormat(
+        format!("Missing time zone: {}", word)));
+    }
+
+    return Ok(Some(Zone {
+      variable: *variable,
+      name: name.to_string(),
+      span,
+    }));
+  }
+
+  Ok(None)
+}
+
+fn parse_field(field: &str, span: Span, kind: FieldKind, dialect: Dialect)
+    -> Located<Field> {
+  let mut items = Vec::new();
+  let mut start = 0;
+  let extensions = dialect.day_extensions();
+
+  for part in field.split(',') {
+    items.push(parse_item(part, span.start + start, kind, dialect, extensions)?);
+    start += part.len() + 1;
+  }
+
+  Ok(Field {
+    kind,
+    items,
+    span,
+  })
+}
+
+fn parse_item(item: &str, offset: usize, kind: FieldKind, dialect: Dialect, extensions: bool)
+    -> Located<Item> {
+  let span = Span::new(0, item.len()).offset(offset);
+
+  if item == "?" && dialect.any_value_question_mark() {
+    return Ok(Item {
+      kind: ItemKind::Special(Special::NoSpecificValue),
+      span,
+    });
+  }
+
+  if extensions {
+    if let Some(kind) = parse_day_extension(item, offset, kind)? {
+      return Ok(Item {
+        kind,
+        span,
+      });
+    }
+  }
+
+  // stepped, eg. */2 or 1-45/3
+  if let Some(slash) = item.find('/') {
+    let base = parse_item(&item[.. slash], offset, kind, dialect, false)?;
+    let step = parse_number(&item[slash + 1 ..], offset + slash + 1)?;
+
+    return Ok(Item {
+      kind: ItemKind::Step(Box::new(base), step),
+      span,
+    });
+  }
+
+  if item == "*" {
+    return Ok(Item {
+      kind: ItemKind::Special(Special::Any),
+      span,
+    });
+  }
+
+  // ranges, eg. 1-30
+  if let Some(dash) = item.find('-') {
+    let start = parse_atom(&item[.. dash], offset)?;
+    let end = parse_atom(&item[dash + 1 ..], offset + dash + 1)?;
+
+    return Ok(Item {
+      kind: ItemKind::Range(start, end),
+      span,
+    });
+  }
+
+  Ok(Item {
+    kind: ItemKind::Value(parse_atom(item, offset)?),
+    span,
+  })
+}
+
+/// The `?`, `L`, `W` and `#` items of the day fields, or `None` if the item
+/// isn't one.
+fn parse_day_extension(item: &str, offset: usize, kind: FieldKind)
+    -> Located<Option<ItemKind>> {
+  let upper = item.to_ascii_uppercase();
+  let before_last = item.len().saturating_sub(1);
+  let day_of_month = kind == FieldKind::DayOfMonth;
+  let day_of_week = kind == FieldKind::DayOfWeek;
+
+  let kind = match (upper.as_str(), item.find('#')) {
+    ("?", _) if day_of_month || day_of_week => {
+      ItemKind::Special(Special::NoSpecificValue)
+    },
+    ("L", _) if day_of_month || day_of_week => ItemKind::Special(Special::Last),
+    ("LW", _) if day_of_month => ItemKind::Special(Special::LastBusinessDay),
+    (_, _) if day_of_month && upper.starts_with("L-") => {
+      ItemKind::LastDayOffset(parse_number(&item[2 ..], offset + 2)?)
+    },
+    (_, _) if day_of_month && upper.len() > 1 && upper.ends_with('W') => {
+      ItemKind::NearestBusinessDay(parse_number(&item[.. before_last], offset)?)
+    },
+    (_, Some(hash)) if day_of_week => {
+      ItemKind::Nth(parse_atom(&item[.. hash], offset)?,
+                    parse_number(&item[hash + 1 ..], offset + hash + 1)?)
+    },
+    (_, _) if day_of_week && upper.len() > 1 && upper.ends_with('L') => {
+      ItemKind::Last(parse_atom(&item[.. before_last], offset)?)
+    },
+    _ => return Ok(None),
+  };
+
+  Ok(Some(kind))
+}
+
+/// A number, such as a step, where a name isn't allowed.
+fn parse_number(number: &str, offset: usize) -> Located<Atom> {
+  let span = Span::new(0, number.len()).offset(offset);
+  let value = number.parse::<u32>()
+      .map_err(|err| (CrontabError::from(err), span))?;
+
+  Ok(Atom {
+    kind: AtomKind::Number(value),
+    span,
+  })
+}
+
+fn parse_atom(atom: &str, offset: usize) -> Located<Atom> {
+  let span = Span::new(0, atom.len()).offset(offset);
+  let kind = if !atom.is_empty() && atom.chars().all(|c| c.is_ascii_alphabetic()) {
+    AtomKind::Name(atom.to_string())
+  } else {
+    let number = atom.parse::<u32>()
+        .map_err(|err| (CrontabError::from(err), span))?;
+    AtomKind::Number(number)
+  };
+
+  Ok(Atom {
+    kind,
+    span,
+  })
+}
+
+/// Renders the expression with single spaces between fields, keeping names
+/// and items as written.
+impl fmt::Display for Expression {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if let Some(ref zone) = self.zone {
+      write!(f, "{} ", zone)?;
+    }
+
+    match self.kind {
+      ExpressionKind::Macro(m) => write!(f, "{}", m.name()),
+      ExpressionKind::Fields(ref fields) => {
+        for (i, field) in fields.iter().enumerate() {
+          if i > 0 {
+            write!(f, " ")?;
+          }
+          write!(f, "{}", field)?;
+        }
+        Ok(())
+      },
+    }
+  }
+}
+
+impl fmt::Display for Zone {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}={}", self.variable.name(), self.name)
+  }
+}
+
+impl fmt::Display for Field {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (i, item) in self.items.iter().enumerate() {
+      if i > 0 {
+        write!(f, ",")?;
+      }
+      write!(f, "{}", item)?;
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for Item {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.kind {
+      ItemKind::Special(Special::Any) => write!(f, "*"),
+      ItemKind::Special(Special::NoSpecificValue) => write!(f, "?"),
+      ItemKind::Special(Special::Last) => write!(f, "L"),
+      ItemKind::Special(Special::LastBusinessDay) => write!(f, "LW"),
+      ItemKind::Value(ref atom) => write!(f, "{}", atom),
+      ItemKind::Range(ref start, ref end) => write!(f, "{}-{}", start, end),
+      ItemKind::Step(ref base, ref step) => write!(f, "{}/{}", base, step),
+      ItemKind::LastDayOffset(ref offset) => write!(f, "L-{}", offset),
+      ItemKind::NearestBusinessDay(ref day) => write!(f, "{}W", day),
+      ItemKind::Last(ref weekday) => write!(f, "{}L", weekday),
+      ItemKind::Nth(ref weekday, ref n) => write!(f, "{}#{}", weekday, n),
+    }
+  }
+}
+
+impl fmt::Display for Atom {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.kind {
+      AtomKind::Number(number) => write!(f, "{}", number),
+      AtomKind::Name(ref name) => write!(f, "{}", name),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+
+  fn fields(source: &str) -> Vec<Field> {
+    Expression::parse(source).unwrap().fields().unwrap().to_vec()
+  }
+
+  #[test]
+  fn field_spans() {
+    let source = "  */15  9-17 * *\tMON-FRI ";
+    let expression = Expression::parse(source).unwrap();
+    expect!(expression.span.text(source)).to(be_equal_to("*/15  9-17 * *\tMON-FRI"));
+
+    let fields = expression.fields().unwrap();
+    let text : Vec<&str> = fields.iter().map(|f| f.span.text(source)).collect();
+    expect!(text).to(be_equal_to(vec!["*/15", "9-17", "*", "*", "MON-FRI"]));
+
+    let kinds : Vec<FieldKind> = fields.iter().map(|f| f.kind).collect();
+    expect!(kinds).to(be_equal_to(Dialect::Vixie.fields().to_vec()));
+  }
+
+  #[test]
+  fn items() {
+    let source = "0,*/15,1-5,0-30/5 * * jan-Mar MON";
+    let fields = fields(source);
+    let items = &fields[0].items;
+
+    let text : Vec<&str> = items.iter().map(|i| i.span.text(source)).collect();
+    expect!(text).to(be_equal_to(vec!["0", "*/15", "1-5", "0-30/5"]));
+
+    match items[0].kind {
+      ItemKind::Value(ref atom) => {
+        expect!(atom.kind.clone()).to(be_equal_to(AtomKind::Number(0)));
+      },
+      ref kind => panic!("Unexpected item: {:?}", kind),
+    }
+
+    match items[3].kind {
+      ItemKind::Step(ref base, ref step) => {
+        expect!(base.span.text(source)).to(be_equal_to("0-30"));
+        expect!(step.kind.clone()).to(be_equal_to(AtomKind::Number(5)));
+        expect!(step.span.text(source)).to(be_equal_to("5"));
+      },
+      ref kind => panic!("Unexpected item: {:?}", kind),
+    }
+
+    match fields[3].items[0].kind {
+      ItemKind::Range(ref start, ref end) => {
+        expect!(start.kind.clone()).to(be_equal_to(AtomKind::Name("jan".to_string())));
+        expect!(end.span.text(source)).to(be_equal_to("Mar"));
+      },
+      ref kind => panic!("Unexpected item: {:?}", kind),
+    }
+
+    match fields[4].items[0].kind {
+      ItemKind::Value(ref atom) => {
+        expect!(atom.span.text(source)).to(be_equal_to("MON"));
+      },
+      ref kind => panic!("Unexpected item: {:?}", kind),
+    }
+  }
+
+  #[test]
+  fn macros() {
+    let expression = Expression::parse(" @daily ").unwrap();
+    expect!(expression.fields()).to(be_none());
+    expect!(expression.span).to(be_equal_to(Span::new(1, 7)));
+    expect!(expression.kind).to(be_equal_to(ExpressionKind::Macro(Macro::Daily)));
+
+    for m in MACROS.iter() {
+      expect!(Macro::from_name(m.name())).to(be_some().value(*m));
+      expect!(Expression::parse(m.expansion())).to(be_ok());
+    }
+
+    expect!(Expression::parse("@dialy")).to(be_err());
+    expect!(Expression::parse("@daily *")).to(be_err());
+  }
+
+  #[test]
+  fn zones() {
+    let source = " CRON_TZ=Europe/London  0 9 * * *";
+    let expression = Expression::parse(source).unwrap();
+    let zone = expression.zone.clone().unwrap();
+    expect!(zone.variable).to(be_equal_to(ZoneVariable::CronTz));
+    expect!(zone.name.as_str()).to(be_equal_to("Europe/London"));
+    expect!(zone.span.text(source)).to(be_equal_to("CRON_TZ=Europe/London"));
+    expect!(expression.span.text(source)).to(be_equal_to("0 9 * * *"));
+    expect!(expression.fields().unwrap()[0].span.text(source)).to(be_equal_to("0"));
+
+    let expression = Expression::parse("TZ=UTC @daily").unwrap();
+    expect!(expression.zone.clone().unwrap().variable).to(be_equal_to(ZoneVariable::Tz));
+    expect!(expression.to_string()).to(be_equal_to("TZ=UTC @daily".to_string()));
+
+    expect!(Expression::parse("0 9 * * *").unwrap().zone).to(be_none());
+    expect!(Expression::parse("CRON_TZ= 0 9 * * *")).to(be_err());
+    expect!(Expression::parse("CRON_TZ=UTC")).to(be_err());
+    expect!(Expression::parse("0 9 * * * CRON_TZ=UTC")).to(be_err());
+  }
+
+  #[test]
+  fn syntax_errors() {
+    expect!(Expression::parse("* * * *")).to(be_err());
+    expect!(Expression::parse("*/*/2 * * * *")).to(be_err());
+    expect!(Expression::parse("*/MON * * * *")).to(be_err());
+    expect!(Expression::parse("1- * * * *")).to(be_err());
+    expect!(Expression::parse("*A * * * *")).to(be_err());
+    expect!(Expression::parse("1,,2 * * * *")).to(be_err());
+  }
+
+  #[test]
+  fn display_as_written() {
+    let expression = Expression::parse("  */15\t9-17 * jan MON-FRI ").unwrap();
+    expect!(expression.to_string()).to(be_equal_to("*/15 9-17 * jan MON-FRI".to_string()));
+    expect!(Expression::parse("@hourly").unwrap().to_string())
+        .to(be_equal_to("@hourly".to_string()));
+  }
+
+  #[test]
+  fn aws() {
+    let source = "cron(0 12 ? * MON#2 2030)";
+    let expression = Expression::parse_dialect(source, Dialect::Aws).unwrap();
+    let fields = expression.fields().unwrap();
+    expect!(fields.len()).to(be_equal_to(6));
+    expect!(fields[5].kind).to(be_equal_to(FieldKind::Year));
+    expect!(fields[5].span.text(source)).to(be_equal_to("2030"));
+    expect!(fields[2].items[0].kind.clone()).to(be_equal_to(ItemKind::Special(Special::NoSpecificValue)));
+    expect!(fields[4].items[0].to_string()).to(be_equal_to("MON#2".to_string()));
+
+    for source in &["0 12 L * ? *", "0 12 L-3 * ? *", "0 12 LW * ? *", "0 12 15W * ? *",
+                    "0 12 ? * 6L *", "0 12 ? * FRIL *", "0/5 * ? * * *"] {
+      expect!(Expression::parse_dialect(source, Dialect::Aws)).to(be_ok());
+    }
+
+    // Exactly one of the day fields is `?`.
+    expect!(Expression::parse_dialect("0 12 * * * *", Dialect::Aws)).to(be_err());
+    expect!(Expression::parse_dialect("0 12 ? * ? *", Dialect::Aws)).to(be_err());
+    expect!(Expression::parse_dialect("0 12 ?,1 * * *", Dialect::Aws)).to(be_err());
+    expect!(Expression::parse_dialect("cron(0 12 ? * * *", Dialect::Aws)).to(be_err());
+    expect!(Expression::parse_dialect("0 12 ? * * ", Dialect::Aws)).to(be_err());
+    expect!(Expression::parse_dialect("@daily", Dialect::Aws)).to(be_err());
+    expect!(Expression::parse_dialect("TZ=UTC 0 12 ? * * *", Dialect::Aws)).to(be_err());
+
+    // Vixie cron has none of it.
+    expect!(Expression::parse("0 12 ? * *")).to(be_err());
+    expect!(Expression::parse("0 12 * * MON#2")).to(be_err());
+  }
+
+  #[test]
+  fn quartz_and_spring() {
+    let kinds = |source: &str, dialect: Dialect| {
+      Expression::parse_dialect(source, dialect).unwrap().fields().unwrap().iter()
+          .map(|field| field.kind)
+          .collect::<Vec<FieldKind>>()
+    };
+
+    // Quartz starts with the second, and may end with the year.
+    expect!(kinds("0 0/5 14,18 * * ?", Dialect::Quartz))
+        .to(be_equal_to(Dialect::Quartz.fields()[.. 6].to_vec()));
+    expect!(kinds("0 0 12 ? * MON#2 2030", Dialect::Quartz))
+        .to(be_equal_to(Dialect::Quartz.fields().to_vec()));
+    expect!(Expression::parse_dialect("0 0 12 * * *", Dialect::Quartz)).to(be_err());
+    expect!(Expression::parse_dialect("0 12 * * ?", Dialect::Quartz)).to(be_err());
+    expect!(Expression::parse_dialect("@daily", Dialect::Quartz)).to(be_err());
+
+    // Spring has six fields, macros and `?` for `*`.
+    expect!(kinds("0 */15 9-17 * * MON-FRI", Dialect::Spring))
+        .to(be_equal_to(Dialect::Spring.fields().to_vec()));
+    for source in &["@daily", "0 0 12 ? * ?", "0 0 12 L * *", "0 0 12 * * FRI#3"] {
+      expect!(Expression::parse_dialect(source, Dialect::Spring)).to(be_ok());
+    }
+    expect!(Expression::parse_dialect("0 12 * * *", Dialect::Spring)).to(be_err());
+    expect!(Expression::parse_dialect("0 0 12 * * * 2030", Dialect::Spring)).to(be_err());
+  }
+}
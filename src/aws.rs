@@ -0,0 +1,214 @@
+//! Amazon EventBridge schedule expressions: `cron(...)` and `rate(...)`.
+
+use crontab::Crontab;
+use dialect::Dialect;
+use error::CrontabError;
+use std::fmt;
+use time::{Duration, Tm};
+
+/// An EventBridge schedule expression, such as `cron(0 12 ? * MON-FRI *)`
+/// or `rate(5 minutes)`.
+///
+/// ```
+/// # extern crate crontab;
+/// # extern crate time;
+/// # fn main() {
+/// use crontab::{AwsSchedule, RateUnit};
+///
+/// // The rule was created on 2017-10-14 at 09:00 UTC.
+/// let created = time::at_utc(time::Timespec::new(1507971600, 0));
+/// let now = time::at_utc(time::Timespec::new(1507972000, 0));
+///
+/// let rate = AwsSchedule::parse("rate(5 minutes)").unwrap();
+/// let next = rate.find_event_after(&created, &now).unwrap();
+/// assert_eq!((next.tm_hour, next.tm_min), (9, 10));
+///
+/// let cron = AwsSchedule::parse("cron(0 12 ? * MON-FRI *)").unwrap();
+/// let next = cron.find_event_after(&created, &now).unwrap();
+/// assert_eq!((next.tm_mday, next.tm_hour), (16, 12));
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AwsSchedule {
+  /// A `cron(...)` expression, parsed in the AWS dialect.
+  Cron(Box<Crontab>),
+  /// A `rate(...)` expression.
+  Rate(Rate),
+}
+
+/// A fixed interval from when a rule was created, eg. `rate(5 minutes)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rate {
+  /// How many units between runs, at least 1.
+  pub value: u32,
+  /// The unit of the interval.
+  pub unit: RateUnit,
+}
+
+/// The unit of a rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RateUnit {
+  /// `minute` or `minutes`.
+  Minutes,
+  /// `hour` or `hours`.
+  Hours,
+  /// `day` or `days`.
+  Days,
+}
+
+impl AwsSchedule {
+  /// Parse a `rate(...)` expression, or a `cron(...)` expression as
+  /// `Crontab::parse_dialect` does with `Dialect::Aws`.
+  pub fn parse(source: &str) -> Result<AwsSchedule, CrontabError> {
+    if source.trim_start().starts_with("rate(") {
+      Rate::parse(source).map(AwsSchedule::Rate)
+    } else {
+      Crontab::parse_dialect(source, Dialect::Aws)
+          .map(|crontab| AwsSchedule::Cron(Box::new(crontab)))
+    }
+  }
+
+  /// The next time the schedule runs after `time`. Rates count from
+  /// `created`, when the rule was created; cron expressions ignore it.
+  /// Returns `None` if a cron expression's years are over.
+  pub fn find_event_after(&self, created: &Tm, time: &Tm) -> Option<Tm> {
+    match *self {
+      AwsSchedule::Cron(ref crontab) => crontab.find_event_after(time),
+      AwsSchedule::Rate(ref rate) => Some(rate.find_event_after(created, time)),
+    }
+  }
+}
+
+impl Rate {
+  /// Parse a rate such as `rate(5 minutes)`. As in EventBridge, the unit is
+  /// singular for a value of 1 and plural otherwise.
+  pub fn parse(source: &str) -> Result<Rate, CrontabError> {
+    let invalid = || CrontabError::ErrCronFormat(format!("Invalid rate: {}", source));
+
+    let body = source.trim()
+        .strip_prefix("rate(")
+        .and_then(|rest| rest.strip_suffix(')'))
+        .ok_or_else(invalid)?;
+
+    let mut words = body.split_whitespace();
+    let (value, unit) = match (words.next(), words.next(), words.next()) {
+      (Some(value), Some(unit), None) => (value, unit),
+      _ => return Err(invalid()),
+    };
+
+    let value = value.parse::<u32>()?;
+    let unit = match (unit, value) {
+      (_, 0) => {
+        return Err(CrontabError::FieldOutsideRange {
+          description: "Rate must be at least 1".to_string(),
+        });
+      },
+      ("minute", 1) | ("minutes", 2 ..) => RateUnit::Minutes,
+      ("hour", 1) | ("hours", 2 ..) => RateUnit::Hours,
+      ("day", 1) | ("days", 2 ..) => RateUnit::Days,
+      _ => return Err(invalid()),
+    };
+
+    Ok(Rate { value, unit })
+  }
+
+  /// The time between runs.
+  pub fn period(&self) -> Duration {
+    let value = i64::from(self.value);
+    match self.unit {
+      RateUnit::Minutes => Duration::minutes(value),
+      RateUnit::Hours => Duration::hours(value),
+      RateUnit::Days => Duration::days(value),
+    }
+  }
+
+  /// The first run after `time`, counting whole periods from `created`. The
+  /// result is in UTC.
+  pub fn find_event_after(&self, created: &Tm, time: &Tm) -> Tm {
+    let period = self.period().num_seconds();
+    let elapsed = (time.to_timespec() - created.to_timespec()).num_seconds();
+    let periods = if elapsed < 0 { 0 } else { elapsed / period + 1 };
+
+    *created + Duration::seconds(periods * period)
+  }
+}
+
+impl RateUnit {
+  /// The unit as written for a value, eg. "minute" for 1 and "minutes"
+  /// otherwise.
+  pub fn name(self, value: u32) -> &'static str {
+    match (self, value) {
+      (RateUnit::Minutes, 1) => "minute",
+      (RateUnit::Minutes, _) => "minutes",
+      (RateUnit::Hours, 1) => "hour",
+      (RateUnit::Hours, _) => "hours",
+      (RateUnit::Days, 1) => "day",
+      (RateUnit::Days, _) => "days",
+    }
+  }
+}
+
+/// Prints the rate as EventBridge writes it, eg. `rate(1 hour)`.
+impl fmt::Display for Rate {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "rate({} {})", self.value, self.unit.name(self.value))
+  }
+}
+
+impl fmt::Display for AwsSchedule {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      AwsSchedule::Cron(ref crontab) => write!(f, "{}", crontab),
+      AwsSchedule::Rate(ref rate) => write!(f, "{}", rate),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+  use test_helpers::{get_tm, normal};
+
+  #[test]
+  fn parse_rates() {
+    expect!(Rate::parse("rate(5 minutes)").unwrap())
+        .to(be_equal_to(Rate { value: 5, unit: RateUnit::Minutes }));
+    expect!(Rate::parse(" rate( 1 hour ) ").unwrap())
+        .to(be_equal_to(Rate { value: 1, unit: RateUnit::Hours }));
+    expect!(Rate::parse("rate(7 days)").unwrap().to_string())
+        .to(be_equal_to("rate(7 days)".to_string()));
+
+    expect!(Rate::parse("rate(1 minutes)")).to(be_err());
+    expect!(Rate::parse("rate(5 minute)")).to(be_err());
+    expect!(Rate::parse("rate(0 minutes)")).to(be_err());
+    expect!(Rate::parse("rate(-1 minutes)")).to(be_err());
+    expect!(Rate::parse("rate(5 weeks)")).to(be_err());
+    expect!(Rate::parse("rate(5 minutes")).to(be_err());
+    expect!(Rate::parse("rate(5)")).to(be_err());
+  }
+
+  #[test]
+  fn parse_schedules() {
+    expect!(AwsSchedule::parse("rate(1 day)").unwrap().to_string())
+        .to(be_equal_to("rate(1 day)".to_string()));
+    expect!(AwsSchedule::parse("cron(0/15 * * * ? *)").unwrap().to_string())
+        .to(be_equal_to("cron(*/15 * * * ? *)".to_string()));
+    expect!(AwsSchedule::parse("0 12 * * * *")).to(be_err());
+  }
+
+  #[test]
+  fn rate_events() {
+    let rate = Rate::parse("rate(2 hours)").unwrap();
+    let created = get_tm(2017, 10, 14, 9, 30, 0);
+
+    let next = |time: Tm| normal(&rate.find_event_after(&created, &time));
+
+    expect!(next(get_tm(2017, 10, 14, 9, 30, 0))).to(be_equal_to(get_tm(2017, 10, 14, 11, 30, 0)));
+    expect!(next(get_tm(2017, 10, 14, 12, 0, 0))).to(be_equal_to(get_tm(2017, 10, 14, 13, 30, 0)));
+    expect!(next(get_tm(2017, 10, 14, 23, 45, 0))).to(be_equal_to(get_tm(2017, 10, 15, 1, 30, 0)));
+
+    // Before the rule exists, it first runs when it's created.
+    expect!(next(get_tm(2017, 10, 1, 0, 0, 0))).to(be_equal_to(created));
+  }
+}
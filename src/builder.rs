@@ -0,0 +1,292 @@
+//! A typed builder for constructing schedules in code.
+
+use calendar::{Month, Weekday};
+use crontab::Crontab;
+use error::CrontabError;
+use parsing::ScheduleComponents;
+use std::ops::{Range, RangeInclusive};
+
+/// Values that can be added to a schedule field, such as a single value, a
+/// range or a list of values. Weekdays and months are converted to their cron
+/// numbers.
+pub trait FieldValues {
+  /// The values, in any order.
+  fn field_values(self) -> Vec<u32>;
+}
+
+impl FieldValues for u32 {
+  fn field_values(self) -> Vec<u32> {
+    vec![self]
+  }
+}
+
+impl FieldValues for Range<u32> {
+  fn field_values(self) -> Vec<u32> {
+    self.collect()
+  }
+}
+
+impl FieldValues for RangeInclusive<u32> {
+  fn field_values(self) -> Vec<u32> {
+    self.collect()
+  }
+}
+
+impl FieldValues for Vec<u32> {
+  fn field_values(self) -> Vec<u32> {
+    self
+  }
+}
+
+impl FieldValues for &[u32] {
+  fn field_values(self) -> Vec<u32> {
+    self.to_vec()
+  }
+}
+
+impl FieldValues for Weekday {
+  fn field_values(self) -> Vec<u32> {
+    vec![self.number()]
+  }
+}
+
+impl FieldValues for RangeInclusive<Weekday> {
+  fn field_values(self) -> Vec<u32> {
+    Weekday::range(self).map(Weekday::number).collect()
+  }
+}
+
+impl FieldValues for &[Weekday] {
+  fn field_values(self) -> Vec<u32> {
+    self.iter().map(|weekday| weekday.number()).collect()
+  }
+}
+
+impl FieldValues for Month {
+  fn field_values(self) -> Vec<u32> {
+    vec![self.number()]
+  }
+}
+
+impl FieldValues for RangeInclusive<Month> {
+  fn field_values(self) -> Vec<u32> {
+    Month::range(self).map(Month::number).collect()
+  }
+}
+
+impl FieldValues for &[Month] {
+  fn field_values(self) -> Vec<u32> {
+    self.iter().map(|month| month.number()).collect()
+  }
+}
+
+/// Builds a `Crontab` field by field. Fields that are never set include
+/// every value, like `*`. Values are checked against the same ranges that
+/// `Crontab::parse` uses when the schedule is built.
+///
+/// ```
+/// use crontab::{Crontab, Weekday};
+///
+/// let crontab = Crontab::builder()
+///     .minute(0)
+///     .hours(9..=17)
+///     .weekdays(Weekday::Mon..=Weekday::Fri)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(crontab.to_string(), "0 9-17 * * 1-5");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CrontabBuilder {
+  minutes: Option<Vec<u32>>,
+  hours: Option<Vec<u32>>,
+  days: Option<Vec<u32>>,
+  months: Option<Vec<u32>>,
+  weekdays: Option<Vec<u32>>,
+}
+
+impl CrontabBuilder {
+  /// Create a builder for a schedule that runs every minute.
+  pub fn new() -> CrontabBuilder {
+    CrontabBuilder::default()
+  }
+
+  /// Add a minute. Range [0,59] inclusive.
+  pub fn minute(self, minute: u32) -> CrontabBuilder {
+    self.minutes(minute)
+  }
+
+  /// Add minutes. Range [0,59] inclusive.
+  pub fn minutes<V: FieldValues>(mut self, minutes: V) -> CrontabBuilder {
+    add(&mut self.minutes, minutes);
+    self
+  }
+
+  /// Add an hour. Range [0,23] inclusive.
+  pub fn hour(self, hour: u32) -> CrontabBuilder {
+    self.hours(hour)
+  }
+
+  /// Add hours. Range [0,23] inclusive.
+  pub fn hours<V: FieldValues>(mut self, hours: V) -> CrontabBuilder {
+    add(&mut self.hours, hours);
+    self
+  }
+
+  /// Add a day of the month. Range [1,31] inclusive.
+  pub fn day(self, day: u32) -> CrontabBuilder {
+    self.days(day)
+  }
+
+  /// Add days of the month. Range [1,31] inclusive.
+  pub fn days<V: FieldValues>(mut self, days: V) -> CrontabBuilder {
+    add(&mut self.days, days);
+    self
+  }
+
+  /// Add a month. Range [1,12] inclusive.
+  pub fn month(self, month: u32) -> CrontabBuilder {
+    self.months(month)
+  }
+
+  /// Add months, as numbers in range [1,12] inclusive or as `Month`s.
+  pub fn months<V: FieldValues>(mut self, months: V) -> CrontabBuilder {
+    add(&mut self.months, months);
+    self
+  }
+
+  /// Add a day of the week. Range [0,6] inclusive, with 0 as Sunday.
+  pub fn weekday(self, weekday: u32) -> CrontabBuilder {
+    self.weekdays(weekday)
+  }
+
+  /// Add days of the week, as numbers in range [0,6] inclusive with 0 as
+  /// Sunday, or as `Weekday`s.
+  pub fn weekdays<V: FieldValues>(mut self, weekdays: V) -> CrontabBuilder {
+    add(&mut self.weekdays, weekdays);
+    self
+  }
+
+  /// Validate the fields and build the `Crontab`.
+  pub fn build(&self) -> Result<Crontab, CrontabError> {
+    let schedule = ScheduleComponents::from_unordered(
+      field(&self.minutes, 0, 59),
+      field(&self.hours, 0, 23),
+      field(&self.days, 1, 31),
+      field(&self.months, 1, 12),
+      field(&self.weekdays, 0, 6))?;
+
+    Ok(Crontab::from_schedule(schedule))
+  }
+}
+
+fn add<V: FieldValues>(field: &mut Option<Vec<u32>>, values: V) {
+  field.get_or_insert_with(Vec::new).extend(values.field_values());
+}
+
+/// The values added to a field, or all of them if the field was never set.
+fn field(values: &Option<Vec<u32>>, field_min: u32, field_max: u32)
+    -> Vec<u32> {
+  match *values {
+    Some(ref values) => values.clone(),
+    None => (field_min .. field_max + 1).collect(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+
+  #[test]
+  fn empty_builder_is_every_minute() {
+    let crontab = CrontabBuilder::new().build().unwrap();
+    expect!(crontab).to(be_equal_to(Crontab::parse("* * * * *").unwrap()));
+  }
+
+  #[test]
+  fn values_and_ranges() {
+    let crontab = Crontab::builder()
+        .minute(0)
+        .hours(9..=17)
+        .days(1..8)
+        .months(vec![1, 7])
+        .weekdays(&[5, 1, 3][..])
+        .build()
+        .unwrap();
+
+    expect!(crontab.schedule().minutes()).to(be_equal_to(&[0][..]));
+    expect!(crontab.schedule().hours().to_vec()).to(be_equal_to((9..18).collect::<Vec<u32>>()));
+    expect!(crontab.schedule().days().to_vec()).to(be_equal_to((1..8).collect::<Vec<u32>>()));
+    expect!(crontab.schedule().months()).to(be_equal_to(&[1, 7][..]));
+    expect!(crontab.schedule().weekdays()).to(be_equal_to(&[1, 3, 5][..]));
+  }
+
+  #[test]
+  fn weekdays_and_months() {
+    let crontab = Crontab::builder()
+        .weekdays(Weekday::Mon..=Weekday::Fri)
+        .weekdays(Weekday::Sun)
+        .months(&[Month::Jan, Month::Jul][..])
+        .months(Month::Dec)
+        .months(Month::Mar..=Month::Apr)
+        .build()
+        .unwrap();
+
+    expect!(crontab.schedule().weekdays()).to(be_equal_to(&[0, 1, 2, 3, 4, 5][..]));
+    expect!(crontab.schedule().months()).to(be_equal_to(&[1, 3, 4, 7, 12][..]));
+
+    let crontab = Crontab::builder()
+        .weekdays(&[Weekday::Sat, Weekday::Sun][..])
+        .months(&[Month::Feb][..])
+        .build()
+        .unwrap();
+
+    expect!(crontab.schedule().weekdays()).to(be_equal_to(&[0, 6][..]));
+    expect!(crontab.schedule().months()).to(be_equal_to(&[2][..]));
+
+    // Ranges wrap around the end of the week and year.
+    let crontab = Crontab::builder()
+        .weekdays(Weekday::Sat..=Weekday::Sun)
+        .months(Month::Nov..=Month::Feb)
+        .build()
+        .unwrap();
+
+    expect!(crontab.schedule().typed_weekdays()).to(be_equal_to(vec![Weekday::Sun, Weekday::Sat]));
+    expect!(crontab.schedule().typed_months())
+        .to(be_equal_to(vec![Month::Jan, Month::Feb, Month::Nov, Month::Dec]));
+
+    // As they do when parsed.
+    let parsed = Crontab::parse("* * * NOV-FEB SAT-SUN").unwrap();
+    expect!(crontab.schedule()).to(be_equal_to(parsed.schedule()));
+  }
+
+  #[test]
+  fn values_accumulate_unique_and_ordered() {
+    let crontab = Crontab::builder()
+        .minute(30)
+        .minute(0)
+        .minutes(vec![30, 15])
+        .build()
+        .unwrap();
+
+    expect!(crontab.schedule().minutes()).to(be_equal_to(&[0, 15, 30][..]));
+  }
+
+  #[test]
+  fn values_outside_range() {
+    expect!(Crontab::builder().minute(60).build()).to(be_err());
+    expect!(Crontab::builder().hour(24).build()).to(be_err());
+    expect!(Crontab::builder().day(0).build()).to(be_err());
+    expect!(Crontab::builder().days(30..=32).build()).to(be_err());
+    expect!(Crontab::builder().month(0).build()).to(be_err());
+    expect!(Crontab::builder().month(13).build()).to(be_err());
+    expect!(Crontab::builder().weekday(7).build()).to(be_err());
+  }
+
+  #[test]
+  fn empty_values() {
+    expect!(Crontab::builder().minutes(Vec::new()).build()).to(be_err());
+    expect!(Crontab::builder().hours(5..5).build()).to(be_err());
+  }
+}
@@ -0,0 +1,406 @@
+//! Days of the week and months, numbered as cron numbers them.
+
+use error::CrontabError;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use time::Tm;
+
+/// A day of the week. Cron numbers these [0,6] with 0 as Sunday, the same
+/// as `Tm.tm_wday`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+  /// Sunday, 0.
+  Sun = 0,
+  /// Monday, 1.
+  Mon = 1,
+  /// Tuesday, 2.
+  Tue = 2,
+  /// Wednesday, 3.
+  Wed = 3,
+  /// Thursday, 4.
+  Thu = 4,
+  /// Friday, 5.
+  Fri = 5,
+  /// Saturday, 6.
+  Sat = 6,
+}
+
+/// A month. Cron numbers these [1,12] with 1 as January, whereas
+/// `Tm.tm_mon` numbers them [0,11].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Month {
+  /// January, 1.
+  Jan = 1,
+  /// February, 2.
+  Feb = 2,
+  /// March, 3.
+  Mar = 3,
+  /// April, 4.
+  Apr = 4,
+  /// May, 5.
+  May = 5,
+  /// June, 6.
+  Jun = 6,
+  /// July, 7.
+  Jul = 7,
+  /// August, 8.
+  Aug = 8,
+  /// September, 9.
+  Sep = 9,
+  /// October, 10.
+  Oct = 10,
+  /// November, 11.
+  Nov = 11,
+  /// December, 12.
+  Dec = 12,
+}
+
+const WEEKDAYS : [Weekday; 7] = [
+  Weekday::Sun, Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu,
+  Weekday::Fri, Weekday::Sat,
+];
+
+const WEEKDAY_NAMES : [&str; 7] = [
+  "Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday",
+];
+
+const MONTHS : [Month; 12] = [
+  Month::Jan, Month::Feb, Month::Mar, Month::Apr, Month::May, Month::Jun,
+  Month::Jul, Month::Aug, Month::Sep, Month::Oct, Month::Nov, Month::Dec,
+];
+
+const MONTH_NAMES : [&str; 12] = [
+  "January", "February", "March", "April", "May", "June", "July", "August",
+  "September", "October", "November", "December",
+];
+
+impl Weekday {
+  /// The weekday for a cron number in [0,6], with 0 as Sunday.
+  pub fn from_number(number: u32) -> Option<Weekday> {
+    WEEKDAYS.get(number as usize).cloned()
+  }
+
+  /// The weekday of a `Tm`, from its `tm_wday` field.
+  pub fn from_tm(time: &Tm) -> Weekday {
+    WEEKDAYS[time.tm_wday.rem_euclid(7) as usize]
+  }
+
+  /// The weekday for a three letter abbreviation as used in crontabs, eg.
+  /// "MON". Case insensitive.
+  pub fn from_abbreviation(abbreviation: &str) -> Option<Weekday> {
+    WEEKDAYS.iter()
+        .find(|weekday| weekday.abbreviation().eq_ignore_ascii_case(abbreviation))
+        .cloned()
+  }
+
+  /// The cron number of the weekday, [0,6] with 0 as Sunday.
+  pub fn number(self) -> u32 {
+    self as u32
+  }
+
+  /// The full English name, eg. "Monday".
+  pub fn name(self) -> &'static str {
+    WEEKDAY_NAMES[self as usize]
+  }
+
+  /// The three letter abbreviation used in crontabs, eg. "MON".
+  pub fn abbreviation(self) -> &'static str {
+    ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"][self as usize]
+  }
+
+  /// The following day, wrapping from Saturday to Sunday.
+  pub fn succ(self) -> Weekday {
+    WEEKDAYS[(self as usize + 1) % 7]
+  }
+
+  /// The preceding day, wrapping from Sunday to Saturday.
+  pub fn pred(self) -> Weekday {
+    WEEKDAYS[(self as usize + 6) % 7]
+  }
+
+  /// Iterate over every day of the week, from Sunday to Saturday.
+  pub fn iter() -> Weekdays {
+    Weekdays { next: Weekday::Sun, remaining: 7 }
+  }
+
+  /// Iterate over an inclusive range of weekdays, eg. Monday to Friday.
+  /// Like `succ`, the range wraps from Saturday to Sunday, so Saturday to
+  /// Sunday is the weekend.
+  pub fn range(range: RangeInclusive<Weekday>) -> Weekdays {
+    let (start, end) = range.into_inner();
+    Weekdays { next: start, remaining: (end.number() + 7 - start.number()) % 7 + 1 }
+  }
+}
+
+impl Month {
+  /// The month for a cron number in [1,12], with 1 as January.
+  pub fn from_number(number: u32) -> Option<Month> {
+    match number {
+      0 => None,
+      n => MONTHS.get(n as usize - 1).cloned(),
+    }
+  }
+
+  /// The month of a `Tm`, from its zero-indexed `tm_mon` field.
+  pub fn from_tm(time: &Tm) -> Month {
+    MONTHS[time.tm_mon.rem_euclid(12) as usize]
+  }
+
+  /// The month for a three letter abbreviation as used in crontabs, eg.
+  /// "JAN". Case insensitive.
+  pub fn from_abbreviation(abbreviation: &str) -> Option<Month> {
+    MONTHS.iter()
+        .find(|month| month.abbreviation().eq_ignore_ascii_case(abbreviation))
+        .cloned()
+  }
+
+  /// The cron number of the month, [1,12] with 1 as January.
+  pub fn number(self) -> u32 {
+    self as u32
+  }
+
+  /// The zero-indexed month number used by `Tm.tm_mon`, [0,11].
+  pub fn tm_mon(self) -> i32 {
+    self as i32 - 1
+  }
+
+  /// The full English name, eg. "January".
+  pub fn name(self) -> &'static str {
+    MONTH_NAMES[self as usize - 1]
+  }
+
+  /// The three letter abbreviation used in crontabs, eg. "JAN".
+  pub fn abbreviation(self) -> &'static str {
+    ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT",
+     "NOV", "DEC"][self as usize - 1]
+  }
+
+  /// The following month, wrapping from December to January.
+  pub fn succ(self) -> Month {
+    MONTHS[self as usize % 12]
+  }
+
+  /// The preceding month, wrapping from January to December.
+  pub fn pred(self) -> Month {
+    MONTHS[(self as usize + 10) % 12]
+  }
+
+  /// Iterate over every month, from January to December.
+  pub fn iter() -> Months {
+    Months { next: Month::Jan, remaining: 12 }
+  }
+
+  /// Iterate over an inclusive range of months, eg. April to June. Like
+  /// `succ`, the range wraps from December to January, so November to
+  /// February is the winter.
+  pub fn range(range: RangeInclusive<Month>) -> Months {
+    let (start, end) = range.into_inner();
+    Months { next: start, remaining: (end.number() + 12 - start.number()) % 12 + 1 }
+  }
+}
+
+/// An iterator over days of the week. See `Weekday::iter`.
+#[derive(Clone, Debug)]
+pub struct Weekdays {
+  next: Weekday,
+  remaining: u32,
+}
+
+impl Iterator for Weekdays {
+  type Item = Weekday;
+
+  fn next(&mut self) -> Option<Weekday> {
+    if self.remaining == 0 {
+      return None;
+    }
+    let weekday = self.next;
+    self.next = weekday.succ();
+    self.remaining -= 1;
+    Some(weekday)
+  }
+}
+
+/// An iterator over months. See `Month::iter`.
+#[derive(Clone, Debug)]
+pub struct Months {
+  next: Month,
+  remaining: u32,
+}
+
+impl Iterator for Months {
+  type Item = Month;
+
+  fn next(&mut self) -> Option<Month> {
+    if self.remaining == 0 {
+      return None;
+    }
+    let month = self.next;
+    self.next = month.succ();
+    self.remaining -= 1;
+    Some(month)
+  }
+}
+
+impl From<Weekday> for u32 {
+  fn from(weekday: Weekday) -> u32 {
+    weekday.number()
+  }
+}
+
+impl From<Month> for u32 {
+  fn from(month: Month) -> u32 {
+    month.number()
+  }
+}
+
+impl TryFrom<u32> for Weekday {
+  type Error = CrontabError;
+
+  fn try_from(number: u32) -> Result<Weekday, CrontabError> {
+    Weekday::from_number(number).ok_or_else(|| CrontabError::FieldOutsideRange {
+      description: format!("Value {} is greater than maximum: 6", number)
+    })
+  }
+}
+
+impl TryFrom<u32> for Month {
+  type Error = CrontabError;
+
+  fn try_from(number: u32) -> Result<Month, CrontabError> {
+    Month::from_number(number).ok_or_else(|| CrontabError::FieldOutsideRange {
+      description: if number == 0 {
+        "Value 0 is less than minimum: 1".to_string()
+      } else {
+        format!("Value {} is greater than maximum: 12", number)
+      }
+    })
+  }
+}
+
+/// Parses full names or three letter abbreviations, case insensitive.
+impl FromStr for Weekday {
+  type Err = CrontabError;
+
+  fn from_str(s: &str) -> Result<Weekday, CrontabError> {
+    WEEKDAYS.iter()
+        .find(|weekday| weekday.name().eq_ignore_ascii_case(s))
+        .cloned()
+        .or_else(|| Weekday::from_abbreviation(s))
+        .ok_or_else(|| CrontabError::ErrCronFormat(format!("Unknown weekday: {}", s)))
+  }
+}
+
+/// Parses full names or three letter abbreviations, case insensitive.
+impl FromStr for Month {
+  type Err = CrontabError;
+
+  fn from_str(s: &str) -> Result<Month, CrontabError> {
+    MONTHS.iter()
+        .find(|month| month.name().eq_ignore_ascii_case(s))
+        .cloned()
+        .or_else(|| Month::from_abbreviation(s))
+        .ok_or_else(|| CrontabError::ErrCronFormat(format!("Unknown month: {}", s)))
+  }
+}
+
+impl fmt::Display for Weekday {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+impl fmt::Display for Month {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+  use test_helpers::get_tm;
+  use time::{Timespec, at_utc};
+
+  #[test]
+  fn weekday_numbers() {
+    expect!(Weekday::Sun.number()).to(be_equal_to(0));
+    expect!(Weekday::Sat.number()).to(be_equal_to(6));
+    expect!(Weekday::from_number(1)).to(be_equal_to(Some(Weekday::Mon)));
+    expect!(Weekday::from_number(7)).to(be_equal_to(None));
+    expect!(Weekday::try_from(3).ok()).to(be_equal_to(Some(Weekday::Wed)));
+    expect!(Weekday::try_from(7)).to(be_err());
+    expect!(u32::from(Weekday::Fri)).to(be_equal_to(5));
+  }
+
+  #[test]
+  fn month_numbers() {
+    expect!(Month::Jan.number()).to(be_equal_to(1));
+    expect!(Month::Jan.tm_mon()).to(be_equal_to(0));
+    expect!(Month::Dec.number()).to(be_equal_to(12));
+    expect!(Month::Dec.tm_mon()).to(be_equal_to(11));
+    expect!(Month::from_number(0)).to(be_equal_to(None));
+    expect!(Month::from_number(12)).to(be_equal_to(Some(Month::Dec)));
+    expect!(Month::from_number(13)).to(be_equal_to(None));
+    expect!(Month::try_from(0)).to(be_err());
+    expect!(Month::try_from(13)).to(be_err());
+    expect!(u32::from(Month::Jul)).to(be_equal_to(7));
+  }
+
+  #[test]
+  fn from_tm() {
+    // 2017-01-01 00:00 UTC was a Sunday.
+    let tm = at_utc(Timespec::new(1483228800, 0));
+    expect!(Weekday::from_tm(&tm)).to(be_equal_to(Weekday::Sun));
+    expect!(Month::from_tm(&tm)).to(be_equal_to(Month::Jan));
+
+    let tm = get_tm(2017, 12, 31, 0, 0, 0);
+    expect!(Month::from_tm(&tm)).to(be_equal_to(Month::Dec));
+  }
+
+  #[test]
+  fn succ_and_pred() {
+    expect!(Weekday::Sat.succ()).to(be_equal_to(Weekday::Sun));
+    expect!(Weekday::Sun.pred()).to(be_equal_to(Weekday::Sat));
+    expect!(Weekday::Mon.succ()).to(be_equal_to(Weekday::Tue));
+    expect!(Month::Dec.succ()).to(be_equal_to(Month::Jan));
+    expect!(Month::Jan.pred()).to(be_equal_to(Month::Dec));
+    expect!(Month::Jun.succ()).to(be_equal_to(Month::Jul));
+  }
+
+  #[test]
+  fn iterators() {
+    expect!(Weekday::iter().count()).to(be_equal_to(7));
+    expect!(Weekday::iter().next()).to(be_equal_to(Some(Weekday::Sun)));
+    expect!(Weekday::range(Weekday::Mon..=Weekday::Fri).collect::<Vec<Weekday>>())
+        .to(be_equal_to(vec![Weekday::Mon, Weekday::Tue, Weekday::Wed,
+                             Weekday::Thu, Weekday::Fri]));
+    expect!(Month::iter().count()).to(be_equal_to(12));
+    expect!(Month::iter().last()).to(be_equal_to(Some(Month::Dec)));
+    expect!(Month::range(Month::Apr..=Month::Jun).collect::<Vec<Month>>())
+        .to(be_equal_to(vec![Month::Apr, Month::May, Month::Jun]));
+
+    // Ranges wrap, like `succ`.
+    expect!(Weekday::range(Weekday::Sat..=Weekday::Sun).collect::<Vec<Weekday>>())
+        .to(be_equal_to(vec![Weekday::Sat, Weekday::Sun]));
+    expect!(Weekday::range(Weekday::Wed..=Weekday::Wed).count()).to(be_equal_to(1));
+    expect!(Month::range(Month::Nov..=Month::Feb).collect::<Vec<Month>>())
+        .to(be_equal_to(vec![Month::Nov, Month::Dec, Month::Jan, Month::Feb]));
+    expect!(Month::range(Month::Feb..=Month::Jan).count()).to(be_equal_to(12));
+  }
+
+  #[test]
+  fn names() {
+    expect!("mon".parse::<Weekday>().ok()).to(be_equal_to(Some(Weekday::Mon)));
+    expect!("Monday".parse::<Weekday>().ok()).to(be_equal_to(Some(Weekday::Mon)));
+    expect!("MNO".parse::<Weekday>()).to(be_err());
+    expect!("JUN".parse::<Month>().ok()).to(be_equal_to(Some(Month::Jun)));
+    expect!("september".parse::<Month>().ok()).to(be_equal_to(Some(Month::Sep)));
+    expect!("Jnu".parse::<Month>()).to(be_err());
+    expect!(Weekday::Thu.to_string()).to(be_equal_to("Thursday".to_string()));
+    expect!(Month::Mar.to_string()).to(be_equal_to("March".to_string()));
+    expect!(Weekday::from_abbreviation("Monday")).to(be_equal_to(None));
+  }
+}
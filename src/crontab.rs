@@ -1,29 +1,234 @@
+use ast::{Expression, FieldKind, Zone, parse_expression};
+use builder::CrontabBuilder;
+use calendar::Month;
+use description::{Description, Locale};
+use diagnostic::Diagnostic;
+use dialect::Dialect;
 use error::CrontabError;
-use parsing::{ScheduleComponents, parse_cron};
+use explain::{DayRule, Explanation};
+use formatting::{format_cron, format_dialect};
+use lint::{Lint, lint};
+use locales::English;
+use parsing::{ScheduleComponents, evaluate};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 use time::{Tm, now, now_utc};
-use times::{adv_month, adv_day, adv_hour, adv_minute};
+use times::{adv_day, adv_hour, adv_minute, day_of_year, weekday};
 
 /// Represents a crontab schedule.
-#[derive(Clone)]
+///
+/// Equality and hashing are semantic: two crontabs are equal when their
+/// expressions expand to the same schedule components in the same time zone,
+/// so `"*/30 * * * *"` and `"0,30 * * * *"` compare equal.
+#[derive(Clone, Debug)]
 pub struct Crontab {
-  /// The components parsed from a crontab schedule.
-  pub schedule: ScheduleComponents,
+  schedule: ScheduleComponents,
+  source: String,
+  expression: Expression,
+  dialect: Dialect,
 }
 
 impl Crontab {
 
   /// Parse a crontab schedule into a Crontab instance.
   pub fn parse(crontab_schedule: &str) -> Result<Crontab, CrontabError> {
-    let schedule = parse_cron(crontab_schedule)?;
+    Crontab::diagnose(crontab_schedule).map_err(|diagnostic| diagnostic.error)
+  }
+
+  /// Parse a crontab schedule like `parse`, but report an error with where
+  /// it is in the schedule and a hint at how to fix it, for showing to
+  /// whoever wrote the schedule.
+  ///
+  /// ```
+  /// use crontab::Crontab;
+  ///
+  /// let diagnostic = Crontab::diagnose("60 * * * *").unwrap_err();
+  /// println!("{}", diagnostic);
+  /// // error: Value 60 is greater than maximum: 59
+  /// //   60 * * * *
+  /// //   ^^ minute
+  /// //   = help: the minute field accepts 0-59
+  /// ```
+  pub fn diagnose(crontab_schedule: &str) -> Result<Crontab, Diagnostic> {
+    Crontab::diagnose_dialect(crontab_schedule, Dialect::Vixie)
+  }
+
+  /// Parse a schedule written in a dialect, such as an AWS EventBridge
+  /// `cron(...)` expression.
+  ///
+  /// ```
+  /// extern crate crontab;
+  /// extern crate time;
+  ///
+  /// use crontab::{Crontab, Dialect};
+  ///
+  /// # fn main() {
+  /// let crontab = Crontab::parse_dialect("cron(0 12 ? * MON-FRI *)", Dialect::Aws).unwrap();
+  /// assert_eq!(crontab.schedule().weekdays(), &[1, 2, 3, 4, 5]);
+  /// assert_eq!(crontab.to_string(), "cron(0 12 ? * 2-6 *)");
+  ///
+  /// // Friday 2017-10-13 12:00 UTC.
+  /// let friday = time::at_utc(time::Timespec::new(1507896000, 0));
+  /// let monday = crontab.find_event_after(&friday).unwrap();
+  /// assert_eq!(monday.tm_mday, 16);
+  /// # }
+  /// ```
+  pub fn parse_dialect(crontab_schedule: &str, dialect: Dialect)
+      -> Result<Crontab, CrontabError> {
+    Crontab::diagnose_dialect(crontab_schedule, dialect).map_err(|diagnostic| diagnostic.error)
+  }
+
+  /// Parse a schedule written in a dialect like `parse_dialect`, reporting
+  /// errors like `diagnose`.
+  pub fn diagnose_dialect(crontab_schedule: &str, dialect: Dialect)
+      -> Result<Crontab, Diagnostic> {
+    let expression = parse_expression(crontab_schedule, dialect)?;
+    let schedule = evaluate(&expression, dialect).map_err(|(error, span, field)| {
+      Diagnostic::new(error, crontab_schedule, span, field).in_dialect(dialect)
+    })?;
     Ok(Crontab {
-      schedule: schedule,
+      schedule,
+      source: crontab_schedule.to_string(),
+      expression,
+      dialect,
     })
   }
 
+  /// Create a crontab from schedule components. Its source is the canonical
+  /// expression of the schedule.
+  pub (crate) fn from_schedule(schedule: ScheduleComponents) -> Crontab {
+    let source = format_cron(&schedule);
+    let expression = Expression::parse(&source)
+        .expect("canonical expressions always parse");
+
+    Crontab {
+      schedule,
+      source,
+      expression,
+      dialect: Dialect::Vixie,
+    }
+  }
+
+  /// The components parsed from the schedule. They can't be changed in
+  /// place, which would leave the source expression describing another
+  /// schedule; convert changed components into a new crontab instead.
+  ///
+  /// ```
+  /// use crontab::{Crontab, ScheduleComponents};
+  ///
+  /// let crontab = Crontab::parse("0 9 * * 1-5").unwrap();
+  /// assert_eq!(crontab.schedule().hours(), &[9]);
+  ///
+  /// let schedule = ScheduleComponents::from_unordered(
+  ///     vec![30], vec![6], (1..32).collect(), (1..13).collect(), vec![1]).unwrap();
+  /// assert_eq!(Crontab::from(schedule).source(), "30 6 * * 1");
+  /// ```
+  pub fn schedule(&self) -> &ScheduleComponents {
+    &self.schedule
+  }
+
+  /// The expression the crontab was parsed from, exactly as given.
+  ///
+  /// ```
+  /// let crontab = crontab::Crontab::parse(" CRON_TZ=UTC */30 * * * *").unwrap();
+  /// assert_eq!(crontab.source(), " CRON_TZ=UTC */30 * * * *");
+  /// assert_eq!(crontab.to_string(), "CRON_TZ=UTC 0,30 * * * *");
+  /// ```
+  pub fn source(&self) -> &str {
+    &self.source
+  }
+
+  /// The syntax tree of the source expression.
+  pub fn expression(&self) -> &Expression {
+    &self.expression
+  }
+
+  /// The dialect the expression was parsed as.
+  pub fn dialect(&self) -> Dialect {
+    self.dialect
+  }
+
+  /// The time zone prefix of the expression, eg. `CRON_TZ=Europe/London`.
+  /// The zone is informational: `find_event_after` still works in the time
+  /// zone of the time it is given.
+  pub fn zone(&self) -> Option<&Zone> {
+    self.expression.zone.as_ref()
+  }
+
+  /// The same schedule written in another dialect, eg. a Quartz expression
+  /// as Unix cron, or the other way around. Fails if the dialect can't
+  /// express it exactly: seconds other than 0, years, days such as `L` or
+  /// `MON#2`, a zone prefix, or how the day fields combine when both are
+  /// restricted.
+  ///
+  /// ```
+  /// use crontab::{Crontab, Dialect};
+  ///
+  /// let quartz = Crontab::parse_dialect("0 0/5 14,18 * * ?", Dialect::Quartz).unwrap();
+  /// let unix = quartz.to_dialect(Dialect::Vixie).unwrap();
+  /// assert_eq!(unix.to_string(), "*/5 14,18 * * *");
+  /// assert_eq!(unix, quartz);
+  ///
+  /// let spring = Crontab::parse("0 9 * * 1-5").unwrap().to_dialect(Dialect::Spring).unwrap();
+  /// assert_eq!(spring.to_string(), "0 0 9 * * 1-5");
+  ///
+  /// let seconds = Crontab::parse_dialect("*/10 * * * * ?", Dialect::Quartz).unwrap();
+  /// assert!(seconds.to_dialect(Dialect::Vixie).is_err());
+  /// ```
+  pub fn to_dialect(&self, dialect: Dialect) -> Result<Crontab, CrontabError> {
+    let unsupported = if self.zone().is_some() && !dialect.zones() {
+      Some("a time zone prefix")
+    } else {
+      unexpressible(&self.schedule, dialect)
+    };
+    if let Some(unsupported) = unsupported {
+      return Err(self.unexpressible_error(dialect, unsupported));
+    }
+
+    in_dialect(&self.schedule, self.zone(), dialect)
+  }
+
+  /// The error for a schedule the dialect can't express.
+  pub (crate) fn unexpressible_error(&self, dialect: Dialect, unsupported: &str)
+      -> CrontabError {
+    CrontabError::ErrCronFormat(
+      format!("The {} dialect can't express {}: {}", dialect, unsupported, self))
+  }
+
+  /// Start building a schedule in code. See `CrontabBuilder`.
+  pub fn builder() -> CrontabBuilder {
+    CrontabBuilder::new()
+  }
+
+  /// Describe the schedule in English, in the style of crontab.guru.
+  ///
+  /// ```
+  /// let crontab = crontab::Crontab::parse("0 9 * * 1-5").unwrap();
+  /// assert_eq!(crontab.describe(),
+  ///   "At 09:00 on every day-of-week from Monday through Friday.");
+  /// ```
+  pub fn describe(&self) -> String {
+    self.describe_in(&English::default())
+  }
+
+  /// Describe the schedule in the language of the given locale.
+  ///
+  /// ```
+  /// use crontab::{Crontab, German};
+  ///
+  /// let crontab = Crontab::parse("0 9 * * 1-5").unwrap();
+  /// assert_eq!(crontab.describe_in(&German), "Um 09:00 Uhr von Montag bis Freitag.");
+  /// ```
+  pub fn describe_in<L: Locale + ?Sized>(&self, locale: &L) -> String {
+    locale.describe(&Description::new(&self.schedule))
+  }
+
   // TODO/FIXME: Optional API is a bit strange. Get rid of the Option wrapper.
   /// Given a start time, calculate the next time this event will take place.
   /// It will always return a time after the time provided, even if the time
-  /// provided happens to be a time specified by the cron schedule.
+  /// provided happens to be a time specified by the cron schedule. Returns
+  /// `None` for schedules that never fire, such as `0 0 30 2 *`.
   ///
   /// Usage:
   ///
@@ -39,6 +244,49 @@ impl Crontab {
     calculate_next_event(&self.schedule, start_time)
   }
 
+  /// Explain, field by field, why a time does or doesn't match the schedule,
+  /// including how the day of month and day of week were combined. Seconds
+  /// are ignored.
+  ///
+  /// ```
+  /// # extern crate crontab;
+  /// # extern crate time;
+  /// # fn main() {
+  /// // 2017-10-14 09:00 UTC, a Saturday.
+  /// let saturday = time::at_utc(time::Timespec::new(1507971600, 0));
+  /// let crontab = crontab::Crontab::parse("0 9 * * MON-FRI").unwrap();
+  ///
+  /// let explanation = crontab.explain(&saturday);
+  /// assert!(!explanation.matches);
+  /// println!("{}", explanation);
+  /// // minute 0 ✓
+  /// // hour 9 ✓
+  /// // day of month 14 ✓ (unrestricted)
+  /// // month October ✓ (unrestricted)
+  /// // day of week Saturday ✗ (allowed: Mon–Fri)
+  /// // day rule: only the day of week is restricted, so it must match
+  /// // does not match
+  /// # }
+  /// ```
+  pub fn explain(&self, time: &Tm) -> Explanation {
+    Explanation::new(&self.schedule, time)
+  }
+
+  /// Check the schedule for expressions that are valid, but probably not
+  /// what was meant, such as `* 2 * * *` running every minute from 2:00 to
+  /// 2:59. Spans refer to `source`.
+  ///
+  /// ```
+  /// use crontab::{Crontab, LintCode};
+  ///
+  /// let lints = Crontab::parse("*/45 * * * *").unwrap().lint();
+  /// assert_eq!(lints[0].code, LintCode::UnevenStep);
+  /// assert!(Crontab::parse("0 2 * * *").unwrap().lint().is_empty());
+  /// ```
+  pub fn lint(&self) -> Vec<Lint> {
+    lint(self)
+  }
+
   /// Find the next occurring event in current local timezone. Keep in mind that
   /// Crontabs do not specify a timezone, so the schedule will be relative to
   /// the local time when this function is used.
@@ -54,40 +302,121 @@ impl Crontab {
   }
 }
 
+impl FromStr for Crontab {
+  type Err = CrontabError;
+
+  fn from_str(s: &str) -> Result<Crontab, CrontabError> {
+    Crontab::parse(s)
+  }
+}
+
+/// A Unix cron crontab of the components, with their canonical expression
+/// as its source.
+impl From<ScheduleComponents> for Crontab {
+  fn from(schedule: ScheduleComponents) -> Crontab {
+    Crontab::from_schedule(schedule)
+  }
+}
+
+/// Prints the schedule as a canonical expression in its dialect, after its
+/// time zone prefix if it has one. AWS expressions are wrapped in
+/// `cron(...)`. Use `source` for the expression as written.
+impl fmt::Display for Crontab {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if let Some(zone) = self.zone() {
+      write!(f, "{} ", zone)?;
+    }
+
+    let expression = format_dialect(&self.schedule, self.dialect);
+    match self.dialect {
+      Dialect::Aws => write!(f, "cron({})", expression),
+      _ => write!(f, "{}", expression),
+    }
+  }
+}
+
+impl PartialEq for Crontab {
+  fn eq(&self, other: &Crontab) -> bool {
+    self.schedule == other.schedule
+        && self.zone().map(|zone| &zone.name) == other.zone().map(|zone| &zone.name)
+  }
+}
+
+impl Eq for Crontab {}
+
+impl Hash for Crontab {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.schedule.hash(state);
+    self.zone().map(|zone| &zone.name).hash(state);
+  }
+}
+
 // TODO: Stop testing this. Test the Crontab method instead.
 pub (crate) fn calculate_next_event(times: &ScheduleComponents, time: &Tm)
     -> Option<Tm> {
-  let mut next_time = time.clone();
+  // A later second of the same minute.
+  if let Some(seconds) = times.seconds() {
+    let later = seconds.iter().find(|&&second| second as i32 > time.tm_sec);
+    if let Some(&second) = later {
+      if minute_matches(times, time) {
+        let mut upcoming = *time;
+        upcoming.tm_sec = second as i32;
+        return Some(upcoming);
+      }
+    }
+  }
+
+  let mut next_time = *time;
 
   // Minute-resolution. We're always going to round up to the next minute.
   next_time.tm_sec = 0;
   adv_minute(&mut next_time);
 
-  loop {
+  // Weekdays repeat on the same dates every 400 years, so a schedule that
+  // hasn't fired by then never will, eg. "0 0 30 2 *".
+  let last_year = next_time.tm_year + 400;
+
+  let mut upcoming = loop {
+    if next_time.tm_year > last_year {
+      return None;
+    }
+
+    match try_year(times, &mut next_time) {
+      Some(DateTimeMatch::Missed) => continue, // Retry
+      Some(_) => {}, // Continue
+      None => return None, // No years left
+    }
+
     match try_month(times, &mut next_time) {
       DateTimeMatch::Missed => continue, // Retry
       DateTimeMatch::ContinueMatching => {}, // Continue
-      DateTimeMatch::AnswerFound(upcoming) => return Some(upcoming),
+      DateTimeMatch::AnswerFound(upcoming) => break upcoming,
     }
 
     match try_day(times, &mut next_time) {
       DateTimeMatch::Missed => continue, // Retry
       DateTimeMatch::ContinueMatching => {}, // Continue
-      DateTimeMatch::AnswerFound(upcoming) => return Some(upcoming),
+      DateTimeMatch::AnswerFound(upcoming) => break upcoming,
     }
 
     match try_hour(times, &mut next_time) {
       DateTimeMatch::Missed => continue, // Retry
       DateTimeMatch::ContinueMatching => {}, // Continue
-      DateTimeMatch::AnswerFound(upcoming) => return Some(upcoming),
+      DateTimeMatch::AnswerFound(upcoming) => break upcoming,
     }
 
     match try_minute(times, &mut next_time) {
       DateTimeMatch::Missed => continue, // Retry
-      DateTimeMatch::ContinueMatching => return Some(next_time), // Uhh...
-      DateTimeMatch::AnswerFound(upcoming) => return Some(upcoming),
+      DateTimeMatch::ContinueMatching => break next_time, // Uhh...
+      DateTimeMatch::AnswerFound(upcoming) => break upcoming,
     }
-  }
+  };
+
+  upcoming.tm_sec = times.seconds().map_or(0, |seconds| seconds[0] as i32);
+  // Months are skipped without tracking these.
+  upcoming.tm_wday = weekday(&upcoming) as i32;
+  upcoming.tm_yday = day_of_year(&upcoming);
+  Some(upcoming)
 }
 
 enum DateTimeMatch {
@@ -96,96 +425,159 @@ enum DateTimeMatch {
   AnswerFound(Tm),
 }
 
-fn try_month(times: &ScheduleComponents, time: &mut Tm) -> DateTimeMatch {
-  // Tm month range is [0, 11]
-  // Cron months are [1, 12]
-  let test_month = (time.tm_mon + 1) as u32;
+/// Whether a value is one of a field's values.
+pub (crate) fn field_matches(values: &[u32], value: u32) -> bool {
+  values.binary_search(&value).is_ok()
+}
 
-  match times.months.binary_search(&test_month) {
-    Ok(_) => {
-      // Precise month... must keep matching
-      DateTimeMatch::ContinueMatching
+/// How the day of month and day of week fields combine. When both leave out
+/// some days, a day matches if either of them does, unless the schedule
+/// needs both to match. See `DayRule` for how this differs from Vixie cron.
+pub (crate) fn day_rule(times: &ScheduleComponents) -> DayRule {
+  let days = times.restricts_days(true);
+  let weekdays = times.restricts_days(false);
+
+  match (days, weekdays) {
+    (false, false) => DayRule::Any,
+    (true, false) => DayRule::DayOfMonth,
+    (false, true) => DayRule::DayOfWeek,
+    (true, true) if times.both_days() => DayRule::Both,
+    (true, true) => DayRule::Either,
+  }
+}
+
+/// What about a schedule a dialect can't express, apart from a time zone
+/// prefix, if anything.
+pub (crate) fn unexpressible(schedule: &ScheduleComponents, dialect: Dialect)
+    -> Option<&'static str> {
+  match day_rule(schedule) {
+    _ if schedule.seconds().is_some() && !dialect.seconds() => Some("seconds"),
+    _ if schedule.years().is_some() && !dialect.fields().contains(&FieldKind::Year) => {
+      Some("years")
     },
-    Err(pos) => {
-      if let Some(month) = times.months.get(pos) {
-        // Next month. We're done.
-        let mut use_time = time.clone();
-        use_time.tm_mon = (month - 1) as i32;
-        // Tm day range is [1, 31]
-        use_time.tm_mday = times.days.get(0).unwrap().clone() as i32;
-        // Tm hour range is [0, 23]
-        use_time.tm_hour = times.hours.get(0).unwrap().clone() as i32;
-        // Tm minute range is [0, 59]
-        use_time.tm_min = times.minutes.get(0).unwrap().clone() as i32;
-        use_time.tm_sec = 0; // Second resolution
+    _ if !schedule.day_specials().is_empty() && !dialect.day_extensions() => {
+      Some("days picked by their place in the month")
+    },
+    DayRule::Either if dialect.both_days() || dialect.requires_no_specific_day() => {
+      Some("days matching either day field")
+    },
+    DayRule::Both if !dialect.both_days() => Some("days matching both day fields"),
+    _ => None,
+  }
+}
 
-        DateTimeMatch::AnswerFound(use_time)
+/// A schedule the dialect can express, written in it with a time zone
+/// prefix.
+pub (crate) fn in_dialect(schedule: &ScheduleComponents, zone: Option<&Zone>, dialect: Dialect)
+    -> Result<Crontab, CrontabError> {
+  let expression = format_dialect(schedule, dialect);
+  let source = match zone {
+    Some(zone) => format!("{} {}", zone, expression),
+    None => expression,
+  };
+  Crontab::parse_dialect(&source, dialect)
+}
 
-      } else {
-        // Skipped beyond. Pop to last unit and use next value.
-        time.tm_year = time.tm_year + 1;
-        // Tm month range is [0, 11], Cron months are [1, 12]
-        time.tm_mon = (times.months.get(0).unwrap().clone() - 1) as i32;
-        // Tm day range is [1, 31]
-        time.tm_mday = times.days.get(0).unwrap().clone() as i32;
-        // Tm hour range is [0, 23]
-        time.tm_hour = times.hours.get(0).unwrap().clone() as i32;
-        // Tm minute range is [0, 59]
-        time.tm_min = times.minutes.get(0).unwrap().clone() as i32;
-        time.tm_sec = 0; // Second resolution
+/// Whether the minute of a time is one the schedule fires in.
+fn minute_matches(times: &ScheduleComponents, time: &Tm) -> bool {
+  let year = (time.tm_year + 1900) as u32;
+  let year_matches = match times.years() {
+    Some(years) => field_matches(years, year),
+    None => true,
+  };
+  year_matches
+      && field_matches(times.months(), Month::from_tm(time).number())
+      && day_rule(times).matches(times.day_of_month_matches(time),
+                                 times.day_of_week_matches(time))
+      && field_matches(times.hours(), time.tm_hour as u32)
+      && field_matches(times.minutes(), time.tm_min as u32)
+}
 
-        DateTimeMatch::Missed
-      }
-    }
+/// Whether the year matches, or `None` when the schedule's years are over.
+fn try_year(times: &ScheduleComponents, time: &mut Tm) -> Option<DateTimeMatch> {
+  let years = match times.years() {
+    Some(years) => years,
+    None => return Some(DateTimeMatch::ContinueMatching),
+  };
+
+  match years.binary_search(&((time.tm_year + 1900) as u32)) {
+    Ok(_) => Some(DateTimeMatch::ContinueMatching),
+    Err(pos) => {
+      // Skip to the start of the next year.
+      time.tm_year = *years.get(pos)? as i32 - 1900;
+      time.tm_mon = 0;
+      time.tm_mday = 1;
+      time.tm_hour = 0;
+      time.tm_min = 0;
+      time.tm_sec = 0;
+      Some(DateTimeMatch::Missed)
+    },
   }
 }
 
-fn try_day(times: &ScheduleComponents, time: &mut Tm) -> DateTimeMatch {
-  match times.days.binary_search(&(time.tm_mday as u32)) {
+fn try_month(times: &ScheduleComponents, time: &mut Tm) -> DateTimeMatch {
+  let test_month = Month::from_tm(time).number();
+
+  match times.months().binary_search(&test_month) {
     Ok(_) => {
       // Precise month... must keep matching
       DateTimeMatch::ContinueMatching
     },
     Err(pos) => {
-      if let Some(day) = times.days.get(pos) {
-        // Next day. We're done.
-        let mut use_time = time.clone();
-        // Tm day range is [1, 31]
-        use_time.tm_mday = day.clone() as i32;
-        // Tm hour range is [0, 23]
-        use_time.tm_hour = times.hours.get(0).unwrap().clone() as i32;
-        // Tm minute range is [0, 59]
-        use_time.tm_min = times.minutes.get(0).unwrap().clone() as i32;
-        use_time.tm_sec = 0; // Second resolution
-
-        DateTimeMatch::AnswerFound(use_time)
-
+      if let Some(month) = times.months().get(pos) {
+        // Next month. Its days still need matching.
+        time.tm_mon = month_to_tm(*month);
       } else {
-        time.tm_mday = 1; // Reset day (1-indexed)
-        time.tm_hour = 0; // Reset hour
-        time.tm_min = 0; // Reset minute
-        time.tm_sec = 0; // Reset second
-        adv_month(time);
-        DateTimeMatch::Missed
+        // Skipped beyond. Pop to last unit and use next value.
+        time.tm_year += 1;
+        time.tm_mon = month_to_tm(times.months()[0]);
       }
+
+      time.tm_mday = 1; // Reset day (1-indexed)
+      time.tm_hour = 0; // Reset hour
+      time.tm_min = 0; // Reset minute
+      time.tm_sec = 0; // Reset second
+      DateTimeMatch::Missed
     }
   }
 }
 
+/// The zero-indexed `tm_mon` of a cron month in [1, 12].
+fn month_to_tm(month: u32) -> i32 {
+  Month::from_number(month).expect("month within range").tm_mon()
+}
+
+fn try_day(times: &ScheduleComponents, time: &mut Tm) -> DateTimeMatch {
+  let day = times.day_of_month_matches(time);
+  let weekday = times.day_of_week_matches(time);
+
+  if day_rule(times).matches(day, weekday) {
+    // Precise day... must keep matching
+    return DateTimeMatch::ContinueMatching;
+  }
+
+  // Try the next day, which may be in the next month.
+  time.tm_hour = 0; // Reset hour
+  time.tm_min = 0; // Reset minute
+  time.tm_sec = 0; // Reset second
+  adv_day(time);
+  DateTimeMatch::Missed
+}
+
 fn try_hour(times: &ScheduleComponents, time: &mut Tm) -> DateTimeMatch {
-  match times.hours.binary_search(&(time.tm_hour as u32)) {
+  match times.hours().binary_search(&(time.tm_hour as u32)) {
     Ok(_) => {
-      // Precise month... must keep matching
+      // Precise hour... must keep matching
       DateTimeMatch::ContinueMatching
     },
     Err(pos) => {
-      if let Some(hour) = times.hours.get(pos) {
+      if let Some(hour) = times.hours().get(pos) {
         // Next hour. We're done.
-        let mut use_time = time.clone();
+        let mut use_time = *time;
         // Tm hour range is [0, 23]
-        use_time.tm_hour = hour.clone() as i32;
+        use_time.tm_hour = *hour as i32;
         // Tm minute range is [0, 59]
-        use_time.tm_min = times.minutes.get(0).unwrap().clone() as i32;
+        use_time.tm_min = times.minutes()[0] as i32;
         use_time.tm_sec = 0; // Second resolution
 
         DateTimeMatch::AnswerFound(use_time)
@@ -202,20 +594,20 @@ fn try_hour(times: &ScheduleComponents, time: &mut Tm) -> DateTimeMatch {
 }
 
 fn try_minute(times: &ScheduleComponents, time: &mut Tm) -> DateTimeMatch {
-  match times.minutes.binary_search(&(time.tm_min as u32)) {
+  match times.minutes().binary_search(&(time.tm_min as u32)) {
     Ok(_) => {
       // DONE
-      let mut use_time = time.clone();
-      //use_time.tm_min = minute.clone() as i32;
+      let mut use_time = *time;
+      //use_time.tm_min = *minute as i32;
       use_time.tm_sec = 0; // Second resolution
       DateTimeMatch::AnswerFound(use_time)
     },
     Err(pos) => {
-      if let Some(minute) = times.minutes.get(pos) {
+      if let Some(minute) = times.minutes().get(pos) {
         // Next minute. We're done.
-        let mut use_time = time.clone();
+        let mut use_time = *time;
         // Tm minute range is [0, 59]
-        use_time.tm_min = minute.clone() as i32;
+        use_time.tm_min = *minute as i32;
         use_time.tm_sec = 0; // Second resolution
 
         DateTimeMatch::AnswerFound(use_time)
@@ -237,6 +629,7 @@ mod tests {
   use expectest::prelude::*;
   use test_helpers::get_tm;
   use test_helpers::normal;
+  use std::collections::HashSet;
   use time::{Timespec, at_utc};
 
   fn parse_times(schedule: &str) -> ScheduleComponents {
@@ -456,6 +849,74 @@ mod tests {
     expect!(normal(&next)).to(be_equal_to(get_tm(2008, 1, 1, 0, 0, 0)));
   }
 
+  #[test]
+  fn weekdays() {
+    let times = parse_times("0 9 * * MON-FRI");
+
+    // Friday 2017-10-13 09:00 to Monday 2017-10-16 09:00.
+    let tm = get_tm(2017, 10, 13, 9, 0, 0);
+    let next = calculate_next_event(&times, &tm).unwrap();
+    expect!(normal(&next)).to(be_equal_to(get_tm(2017, 10, 16, 9, 0, 0)));
+    expect!(next.tm_wday).to(be_equal_to(1));
+
+    // Sundays, across a month and a year.
+    let times = parse_times("30 12 * * 0");
+    let tm = get_tm(2017, 12, 25, 0, 0, 0);
+    let next = calculate_next_event(&times, &tm).unwrap();
+    expect!(normal(&next)).to(be_equal_to(get_tm(2017, 12, 31, 12, 30, 0)));
+    let next = calculate_next_event(&times, &next).unwrap();
+    expect!(normal(&next)).to(be_equal_to(get_tm(2018, 1, 7, 12, 30, 0)));
+  }
+
+  #[test]
+  fn either_day_of_month_or_week() {
+    // The 15th, or any Monday.
+    let times = parse_times("0 0 15 * 1");
+
+    // Saturday 2017-10-14 to Sunday the 15th.
+    let tm = get_tm(2017, 10, 14, 0, 0, 0);
+    let next = calculate_next_event(&times, &tm).unwrap();
+    expect!(normal(&next)).to(be_equal_to(get_tm(2017, 10, 15, 0, 0, 0)));
+
+    // Then Monday the 16th.
+    let next = calculate_next_event(&times, &next).unwrap();
+    expect!(normal(&next)).to(be_equal_to(get_tm(2017, 10, 16, 0, 0, 0)));
+  }
+
+  #[test]
+  fn days_missing_from_months() {
+    // Only months with a 31st.
+    let times = parse_times("0 0 31 * *");
+    let tm = get_tm(2017, 1, 31, 0, 0, 0);
+    let next = calculate_next_event(&times, &tm).unwrap();
+    expect!(normal(&next)).to(be_equal_to(get_tm(2017, 3, 31, 0, 0, 0)));
+
+    // Leap days.
+    let times = parse_times("0 0 29 2 *");
+    let tm = get_tm(2017, 1, 1, 0, 0, 0);
+    let next = calculate_next_event(&times, &tm).unwrap();
+    expect!(normal(&next)).to(be_equal_to(get_tm(2020, 2, 29, 0, 0, 0)));
+    expect!(next.tm_yday).to(be_equal_to(59));
+
+    // Never.
+    let times = parse_times("0 0 30 2 *");
+    expect!(calculate_next_event(&times, &tm)).to(be_none());
+  }
+
+  #[test]
+  fn crontab_explain() {
+    let crontab = Crontab::parse("0 9 * * MON-FRI").unwrap();
+    let saturday = get_tm(2017, 10, 14, 9, 0, 0);
+    let monday = get_tm(2017, 10, 16, 9, 0, 0);
+
+    expect!(crontab.explain(&saturday).matches).to(be_false());
+    expect!(crontab.explain(&monday).matches).to(be_true());
+
+    // Explanations agree with the scheduler.
+    let next = crontab.find_event_after(&saturday).unwrap();
+    expect!(crontab.explain(&next).matches).to(be_true());
+  }
+
   #[test]
   fn spot_check_fields_every_day() {
     // Every single day at midnight.
@@ -464,8 +925,8 @@ mod tests {
     // 2017-01-01 00:00 UTC, a non-leap year starting on a Sunday (tm_wday=0).
     let timespec = Timespec::new(1483228800, 0);
     let mut last = at_utc(timespec);
-    let mut next = last.clone();
-    let mut expected = last.clone();
+    let mut next = last;
+    let mut expected = last;
 
     // First day of 2017. (tm_year=117)
     expect!(last.tm_year).to(be_equal_to(117));
@@ -621,4 +1082,170 @@ mod tests {
     expect!(delta.num_hours()).to(be_greater_or_equal_to(0));
     expect!(delta.num_hours()).to(be_less_than(24));
   }
+
+  #[test]
+  fn crontab_from_str() {
+    let crontab = "0 * * * *".parse::<Crontab>().unwrap();
+    expect!(crontab.schedule.minutes()).to(be_equal_to(&[0][..]));
+    expect!("0 * * *".parse::<Crontab>()).to(be_err());
+  }
+
+  #[test]
+  fn crontab_display() {
+    let crontab = Crontab::parse("  0  */12 * * 1-3 ").unwrap();
+    expect!(crontab.to_string()).to(be_equal_to("0 0,12 * * 1-3".to_string()));
+
+    let reparsed = Crontab::parse(&crontab.to_string()).unwrap();
+    expect!(reparsed).to(be_equal_to(crontab));
+  }
+
+  #[test]
+  fn crontab_semantic_equality() {
+    let a = Crontab::parse("*/30 * * * *").unwrap();
+    let b = Crontab::parse("0,30 * * * *").unwrap();
+    let c = Crontab::parse("0-59/30 0-23 1-31 * 0-6").unwrap();
+    let d = Crontab::parse("0 * * * *").unwrap();
+
+    expect!(a.clone()).to(be_equal_to(b.clone()));
+    expect!(a.clone()).to(be_equal_to(c.clone()));
+    expect!(a.clone()).not_to(be_equal_to(d.clone()));
+
+    let set : HashSet<Crontab> = vec![a, b, c, d].into_iter().collect();
+    expect!(set.len()).to(be_equal_to(2));
+
+    // The same schedule in different zones.
+    let utc = Crontab::parse("CRON_TZ=UTC 0 * * * *").unwrap();
+    let tz = Crontab::parse("TZ=UTC 0 * * * *").unwrap();
+    let london = Crontab::parse("CRON_TZ=Europe/London 0 * * * *").unwrap();
+    expect!(utc.clone()).to(be_equal_to(tz));
+    expect!(utc.clone()).not_to(be_equal_to(london));
+    expect!(utc).not_to(be_equal_to(Crontab::parse("0 * * * *").unwrap()));
+  }
+
+  #[test]
+  fn crontab_keeps_source() {
+    let crontab = Crontab::parse("  CRON_TZ=America/New_York 0 9 * * MON-FRI ").unwrap();
+    expect!(crontab.source()).to(be_equal_to("  CRON_TZ=America/New_York 0 9 * * MON-FRI "));
+    expect!(crontab.dialect()).to(be_equal_to(Dialect::Vixie));
+    expect!(crontab.zone().unwrap().name.as_str()).to(be_equal_to("America/New_York"));
+    expect!(crontab.expression().to_string())
+        .to(be_equal_to("CRON_TZ=America/New_York 0 9 * * MON-FRI".to_string()));
+    expect!(crontab.to_string())
+        .to(be_equal_to("CRON_TZ=America/New_York 0 9 * * 1-5".to_string()));
+
+    let crontab = Crontab::parse("@daily").unwrap();
+    expect!(crontab.source()).to(be_equal_to("@daily"));
+    expect!(crontab.zone()).to(be_none());
+
+    let built = Crontab::builder().minute(0).build().unwrap();
+    expect!(built.source()).to(be_equal_to("0 * * * *"));
+  }
+
+  #[test]
+  fn aws_find_event_after() {
+    let next = |schedule: &str, time: Tm| {
+      Crontab::parse_dialect(schedule, Dialect::Aws).unwrap()
+          .find_event_after(&time)
+          .map(|next| normal(&next))
+    };
+    let start = get_tm(2017, 10, 14, 9, 0, 0); // A Saturday.
+
+    expect!(next("0 12 ? * MON-FRI *", start)).to(be_some().value(get_tm(2017, 10, 16, 12, 0, 0)));
+    expect!(next("0 0 L * ? *", start)).to(be_some().value(get_tm(2017, 10, 31, 0, 0, 0)));
+    expect!(next("0 0 L-1 * ? *", start)).to(be_some().value(get_tm(2017, 10, 30, 0, 0, 0)));
+    expect!(next("0 0 LW 12 ? *", start)).to(be_some().value(get_tm(2017, 12, 29, 0, 0, 0)));
+    expect!(next("0 0 15W * ? *", start)).to(be_some().value(get_tm(2017, 10, 16, 0, 0, 0)));
+    expect!(next("0 0 ? * 6L *", start)).to(be_some().value(get_tm(2017, 10, 27, 0, 0, 0)));
+    expect!(next("0 0 ? * MON#2 *", start)).to(be_some().value(get_tm(2017, 11, 13, 0, 0, 0)));
+    expect!(next("0 0 29 2 ? 2018-2021", start)).to(be_some().value(get_tm(2020, 2, 29, 0, 0, 0)));
+
+    // Once the years are over, the schedule never runs again.
+    expect!(next("0 0 1 1 ? 2017", start)).to(be_none());
+    expect!(next("0 0 30 2 ? *", start)).to(be_none());
+  }
+
+  #[test]
+  fn seconds_find_event_after() {
+    let next = |schedule: &str, time: Tm| {
+      normal(&Crontab::parse_dialect(schedule, Dialect::Quartz).unwrap()
+          .find_event_after(&time)
+          .unwrap())
+    };
+
+    // Later seconds of the same minute.
+    expect!(next("*/15 * * * * ?", get_tm(2017, 10, 14, 12, 0, 20)))
+        .to(be_equal_to(get_tm(2017, 10, 14, 12, 0, 30)));
+    expect!(next("*/15 * * * * ?", get_tm(2017, 10, 14, 12, 0, 50)))
+        .to(be_equal_to(get_tm(2017, 10, 14, 12, 1, 0)));
+    expect!(next("30 0 12 * * ?", get_tm(2017, 10, 14, 11, 0, 0)))
+        .to(be_equal_to(get_tm(2017, 10, 14, 12, 0, 30)));
+    expect!(next("30 0 12 * * ?", get_tm(2017, 10, 14, 12, 0, 30)))
+        .to(be_equal_to(get_tm(2017, 10, 15, 12, 0, 30)));
+    expect!(next("0 0 12 * * ?", get_tm(2017, 10, 14, 11, 0, 0)))
+        .to(be_equal_to(get_tm(2017, 10, 14, 12, 0, 0)));
+  }
+
+  #[test]
+  fn wrapping_ranges_find_event_after() {
+    let crontab = Crontab::parse("0 0 1 NOV-FEB *").unwrap();
+    expect!(crontab.schedule.to_expression()).to(be_equal_to("0 0 1 1,2,11,12 *".to_string()));
+    expect!(normal(&crontab.find_event_after(&get_tm(2017, 3, 1, 0, 0, 0)).unwrap()))
+        .to(be_equal_to(get_tm(2017, 11, 1, 0, 0, 0)));
+
+    // Wednesday 2017-10-11, to Saturday.
+    let crontab = Crontab::parse("0 0 * * SAT-SUN").unwrap();
+    expect!(crontab.schedule.to_expression()).to(be_equal_to("0 0 * * 0,6".to_string()));
+    expect!(normal(&crontab.find_event_after(&get_tm(2017, 10, 11, 0, 0, 0)).unwrap()))
+        .to(be_equal_to(get_tm(2017, 10, 14, 0, 0, 0)));
+  }
+
+  #[test]
+  fn spring_both_days() {
+    let crontab = Crontab::parse_dialect("0 0 0 13 * FRI", Dialect::Spring).unwrap();
+    let next = |time: Tm| normal(&crontab.find_event_after(&time).unwrap());
+
+    expect!(next(get_tm(2017, 1, 1, 0, 0, 0))).to(be_equal_to(get_tm(2017, 1, 13, 0, 0, 0)));
+    expect!(next(get_tm(2017, 1, 14, 0, 0, 0))).to(be_equal_to(get_tm(2017, 10, 13, 0, 0, 0)));
+    expect!(crontab.explain(&get_tm(2017, 2, 13, 0, 0, 0)).day_rule)
+        .to(be_equal_to(DayRule::Both));
+  }
+
+  #[test]
+  fn to_dialect() {
+    let convert = |schedule: &str, from: Dialect, to: Dialect| {
+      Crontab::parse_dialect(schedule, from).unwrap().to_dialect(to).map(|c| c.to_string())
+    };
+
+    expect!(convert("0 0/5 14,18 * * ?", Dialect::Quartz, Dialect::Vixie))
+        .to(be_ok().value("*/5 14,18 * * *".to_string()));
+    expect!(convert("0 0 12 ? * 2-6", Dialect::Quartz, Dialect::Spring))
+        .to(be_ok().value("0 0 12 * * 1-5".to_string()));
+    expect!(convert("0 0 12 * * 0,7", Dialect::Spring, Dialect::Quartz))
+        .to(be_ok().value("0 0 12 ? * 1".to_string()));
+    expect!(convert("0 9 * * MON-FRI", Dialect::Vixie, Dialect::Quartz))
+        .to(be_ok().value("0 0 9 ? * 2-6".to_string()));
+    expect!(convert("cron(0 12 L * ? 2030)", Dialect::Aws, Dialect::Quartz))
+        .to(be_ok().value("0 0 12 L * ? 2030".to_string()));
+    expect!(convert("0 0 12 ? * 6#3", Dialect::Quartz, Dialect::Aws))
+        .to(be_ok().value("cron(0 12 ? * 6#3 *)".to_string()));
+
+    // What the other dialect can't say.
+    expect!(convert("CRON_TZ=UTC 0 9 * * *", Dialect::Vixie, Dialect::Quartz)).to(be_err());
+    expect!(convert("30 0 9 * * ?", Dialect::Quartz, Dialect::Vixie)).to(be_err());
+    expect!(convert("0 0 9 * * ? 2030", Dialect::Quartz, Dialect::Spring)).to(be_err());
+    expect!(convert("0 0 9 L * ?", Dialect::Quartz, Dialect::Vixie)).to(be_err());
+    expect!(convert("0 0 1 * MON", Dialect::Vixie, Dialect::Quartz)).to(be_err());
+    expect!(convert("0 0 1 * MON", Dialect::Vixie, Dialect::Spring)).to(be_err());
+    expect!(convert("0 0 0 13 * FRI", Dialect::Spring, Dialect::Vixie)).to(be_err());
+  }
+
+  #[test]
+  fn aws_display() {
+    let crontab = Crontab::parse_dialect("cron(0/20 8-17 ? * MON-FRI 2030)", Dialect::Aws).unwrap();
+    expect!(crontab.dialect()).to(be_equal_to(Dialect::Aws));
+    expect!(crontab.to_string()).to(be_equal_to("cron(*/20 8-17 ? * 2-6 2030)".to_string()));
+
+    let crontab = Crontab::parse_dialect("15 10 L-2,LW * ? *", Dialect::Aws).unwrap();
+    expect!(crontab.to_string()).to(be_equal_to("cron(15 10 L-2,LW * ? *)".to_string()));
+  }
 }
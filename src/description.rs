@@ -0,0 +1,220 @@
+//! Human-readable descriptions of crontab schedules.
+//!
+//! A schedule is first broken down into a language-neutral `Description`,
+//! which a `Locale` then renders as a sentence in its own grammar.
+
+use ast::FieldKind;
+use formatting::{FieldItem, field_items};
+use parsing::{DaySpecial, ScheduleComponents};
+
+/// Renders schedule descriptions in a natural language.
+///
+/// Implement this to add a language. The built-in locales are `English`,
+/// `German`, `French`, `Spanish` and `Japanese`.
+pub trait Locale {
+  /// Render a description of a schedule as a sentence.
+  fn describe(&self, description: &Description) -> String;
+}
+
+/// The clock used to write times of day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Clock {
+  /// 24-hour clock, eg. "17:30".
+  TwentyFourHour,
+  /// 12-hour clock, eg. "5:30 PM".
+  TwelveHour,
+}
+
+/// Part of the description of a single schedule field. Values are given in
+/// the field's own range, eg. [0,6] for days of the week with 0 as Sunday.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Segment {
+  /// Every value of the field.
+  Every,
+  /// A list of single values.
+  Values(Vec<u32>),
+  /// Every value within an inclusive range.
+  Range(u32, u32),
+  /// Every multiple of the step within the field.
+  Step(u32),
+  /// Every multiple of the step within an inclusive range.
+  SteppedRange(u32, u32, u32),
+}
+
+/// A language-neutral description of a schedule, for a `Locale` to render.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Description {
+  /// The hour and minute, when the schedule fires at a single time of day.
+  pub time: Option<(u32, u32)>,
+  /// The seconds of the schedule, or `None` when it fires at second 0.
+  pub seconds: Option<Vec<Segment>>,
+  /// The minutes of the schedule. Always present.
+  pub minutes: Vec<Segment>,
+  /// The hours of the schedule, or `None` when every hour is included.
+  pub hours: Option<Vec<Segment>>,
+  /// The days of the month, or `None` when every day is included.
+  pub days: Option<Vec<Segment>>,
+  /// The months, or `None` when every month is included.
+  pub months: Option<Vec<Segment>>,
+  /// The days of the week, or `None` when every day is included.
+  pub weekdays: Option<Vec<Segment>>,
+  /// Days picked by their place in the month, such as the last Friday.
+  pub day_specials: Vec<DaySpecial>,
+  /// The years, or `None` when every year is included.
+  pub years: Option<Vec<Segment>>,
+  /// Whether a day must match both day fields, as in Spring, rather than
+  /// either of them.
+  pub both_days: bool,
+}
+
+impl Description {
+  /// Break schedule components down into a description.
+  pub fn new(schedule: &ScheduleComponents) -> Description {
+    let time = if schedule.minutes().len() == 1 && schedule.hours().len() == 1 {
+      Some((schedule.hours()[0], schedule.minutes()[0]))
+    } else {
+      None
+    };
+
+    Description {
+      time,
+      seconds: schedule.seconds().map(|seconds| segments(seconds, 0, 59)),
+      minutes: segments(schedule.minutes(), 0, 59),
+      hours: restricted_segments(schedule.hours(), 0, 23),
+      days: restricted_segments(schedule.days(), 1, 31),
+      months: restricted_segments(schedule.months(), 1, 12),
+      weekdays: restricted_segments(schedule.weekdays(), 0, 6),
+      day_specials: schedule.day_specials().to_vec(),
+      years: schedule.years()
+          .map(|years| segments(years, FieldKind::Year.min(), FieldKind::Year.max())),
+      both_days: schedule.both_days(),
+    }
+  }
+
+  /// Whether both day fields are restricted and either of them is enough.
+  /// Cron then runs the job when *either* of them matches, which
+  /// descriptions should say.
+  pub fn either_day(&self) -> bool {
+    !self.both_days
+        && (self.days.is_some() || self.day_specials(true).next().is_some())
+        && (self.weekdays.is_some() || self.day_specials(false).next().is_some())
+  }
+
+  /// The day specials of the day of month, or of the day of week.
+  pub (crate) fn day_specials(&self, day_of_month: bool)
+      -> impl Iterator<Item = DaySpecial> + '_ {
+    self.day_specials.iter()
+        .filter(move |special| special.is_day_of_month() == day_of_month)
+        .cloned()
+  }
+}
+
+/// The segments of a field, or `None` when it has every value, or none
+/// besides its day specials.
+fn restricted_segments(values: &[u32], field_min: u32, field_max: u32)
+    -> Option<Vec<Segment>> {
+  if values.is_empty() || values.len() as u32 == field_max - field_min + 1 {
+    None
+  } else {
+    Some(segments(values, field_min, field_max))
+  }
+}
+
+/// Group consecutive single values of the canonical field items.
+fn segments(values: &[u32], field_min: u32, field_max: u32) -> Vec<Segment> {
+  let mut segments = Vec::new();
+  let mut singles = Vec::new();
+
+  for item in field_items(values, field_min, field_max) {
+    let segment = match item {
+      FieldItem::Value(value) => {
+        singles.push(value);
+        continue;
+      },
+      FieldItem::All => Segment::Every,
+      FieldItem::Step(step) => Segment::Step(step),
+      FieldItem::Range(start, end) => Segment::Range(start, end),
+      FieldItem::SteppedRange(start, end, step) => {
+        Segment::SteppedRange(start, end, step)
+      },
+    };
+
+    if !singles.is_empty() {
+      segments.push(Segment::Values(singles.clone()));
+      singles.clear();
+    }
+    segments.push(segment);
+  }
+
+  if !singles.is_empty() {
+    segments.push(Segment::Values(singles));
+  }
+
+  segments
+}
+
+/// Join a list of phrases, with `last` before the final phrase. With
+/// `serial`, a comma precedes `last` when there are three or more phrases.
+pub (crate) fn join_list(items: &[String], separator: &str, last: &str,
+                         serial: bool) -> String {
+  match items.len() {
+    0 => String::new(),
+    1 => items[0].clone(),
+    2 => format!("{}{}{}", items[0], last, items[1]),
+    n => {
+      let head = items[.. n - 1].join(separator);
+      let comma = if serial { separator.trim_end() } else { "" };
+      format!("{}{}{}{}", head, comma, last, items[n - 1])
+    },
+  }
+}
+
+/// Uppercase the first character of a sentence.
+pub (crate) fn capitalize(sentence: &str) -> String {
+  let mut chars = sentence.chars();
+  match chars.next() {
+    Some(first) => first.to_uppercase().chain(chars).collect(),
+    None => String::new(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+  use parsing::parse_cron;
+
+  #[test]
+  fn description_of_schedule() {
+    let description = Description::new(&parse_cron("0 9 * * 1-5").unwrap());
+    expect!(description.either_day()).to(be_false());
+    expect!(description.time).to(be_equal_to(Some((9, 0))));
+    expect!(description.hours).to(be_equal_to(Some(vec![Segment::Values(vec![9])])));
+    expect!(description.days).to(be_equal_to(None));
+    expect!(description.months).to(be_equal_to(None));
+    expect!(description.weekdays).to(be_equal_to(Some(vec![Segment::Range(1, 5)])));
+  }
+
+  #[test]
+  fn description_segments() {
+    let description = Description::new(&parse_cron("*/15,7 * 1,15 * 1-5").unwrap());
+    expect!(description.either_day()).to(be_true());
+    expect!(description.time).to(be_equal_to(None));
+    expect!(description.minutes)
+        .to(be_equal_to(vec![Segment::Step(15), Segment::Values(vec![7])]));
+    expect!(description.hours).to(be_equal_to(None));
+    expect!(description.days).to(be_equal_to(Some(vec![Segment::Values(vec![1, 15])])));
+
+    let description = Description::new(&parse_cron("* * * * *").unwrap());
+    expect!(description.minutes).to(be_equal_to(vec![Segment::Every]));
+  }
+
+  #[test]
+  fn lists() {
+    let items : Vec<String> = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    expect!(join_list(&items[.. 1], ", ", " and ", true)).to(be_equal_to("a".to_string()));
+    expect!(join_list(&items[.. 2], ", ", " and ", true)).to(be_equal_to("a and b".to_string()));
+    expect!(join_list(&items, ", ", " and ", true)).to(be_equal_to("a, b, and c".to_string()));
+    expect!(join_list(&items, ", ", " und ", false)).to(be_equal_to("a, b und c".to_string()));
+  }
+}
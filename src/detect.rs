@@ -0,0 +1,185 @@
+//! Guessing the dialect of an expression from how it's written.
+
+use ast::{ExpressionKind, FieldKind, ItemKind, Special};
+use crontab::Crontab;
+use dialect::Dialect;
+use error::CrontabError;
+
+/// The dialects, most common first, so that equally likely ones keep this
+/// order.
+const DIALECTS : [Dialect; 5] = [
+  Dialect::Vixie,
+  Dialect::Quartz,
+  Dialect::Aws,
+  Dialect::Kubernetes,
+  Dialect::Spring,
+];
+
+/// The dialects an expression could be written in. See `Dialect::detect`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Detection {
+  /// The most likely dialect.
+  pub best: Candidate,
+  /// The other dialects that accept the expression, most likely first.
+  /// Empty unless the expression is ambiguous.
+  pub alternatives: Vec<Candidate>,
+}
+
+/// A dialect that accepts an expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+  /// The dialect.
+  pub dialect: Dialect,
+  /// How likely the expression is written in the dialect, from 0 to 1. The
+  /// confidences of a detection's candidates add up to 1.
+  pub confidence: f64,
+  /// The expression parsed in the dialect.
+  pub crontab: Crontab,
+}
+
+impl Dialect {
+  /// Guess the dialect of an expression, eg. one pasted from elsewhere.
+  ///
+  /// Only dialects that accept the expression are candidates, which
+  /// settles most expressions: the field count, a `cron(...)` wrapper, a
+  /// year, a zone prefix and a macro each rule some out. The rest are told
+  /// apart by how likely each is to be written that way. Vixie cron is the
+  /// most common, and `?` is usual in a day field of the AWS and Quartz
+  /// dialects, which need it, but not in Kubernetes and Spring, where it's
+  /// another `*`. So six fields such as `0 12 * * ? *` are more likely the
+  /// minute and hour with a year than a second and minute.
+  ///
+  /// Fails for expressions no dialect accepts, including Jenkins-style
+  /// hashed values such as `H/15`.
+  ///
+  /// ```
+  /// use crontab::Dialect;
+  ///
+  /// let detection = Dialect::detect("0 0/5 14,18 * * ?").unwrap();
+  /// assert_eq!(detection.best.dialect, Dialect::Quartz);
+  /// assert_eq!(detection.alternatives[0].dialect, Dialect::Spring);
+  ///
+  /// let detection = Dialect::detect("cron(0 12 ? * MON-FRI *)").unwrap();
+  /// assert_eq!(detection.best.dialect, Dialect::Aws);
+  /// assert_eq!(detection.best.confidence, 1.0);
+  ///
+  /// assert!(Dialect::detect("H/15 * * * *").is_err());
+  /// ```
+  pub fn detect(source: &str) -> Result<Detection, CrontabError> {
+    let hashed = source.split_whitespace()
+        .flat_map(|word| word.split(','))
+        .any(|item| item == "H" || item.starts_with("H(") || item.starts_with("H/"));
+    if hashed {
+      return Err(CrontabError::ErrCronFormat(format!(
+        "Hashed `H` values, as in Jenkins, aren't accepted by any dialect: {}", source)));
+    }
+
+    let mut candidates : Vec<(Crontab, f64)> = DIALECTS.iter()
+        .filter_map(|&dialect| Crontab::parse_dialect(source, dialect).ok())
+        .map(|crontab| {
+          let likelihood = likelihood(&crontab);
+          (crontab, likelihood)
+        })
+        .collect();
+    if candidates.is_empty() {
+      return Err(CrontabError::ErrCronFormat(
+        format!("No dialect accepts the expression: {}", source)));
+    }
+
+    // Stable, so equally likely dialects stay in the order of `DIALECTS`.
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let total : f64 = candidates.iter().map(|&(_, likelihood)| likelihood).sum();
+    let mut candidates = candidates.into_iter().map(|(crontab, likelihood)| Candidate {
+      dialect: crontab.dialect(),
+      confidence: likelihood / total,
+      crontab,
+    });
+
+    Ok(Detection {
+      best: candidates.next().unwrap(),
+      alternatives: candidates.collect(),
+    })
+  }
+}
+
+/// How likely an expression that a dialect accepts is written in it,
+/// relative to the other dialects.
+fn likelihood(crontab: &Crontab) -> f64 {
+  let dialect = crontab.dialect();
+  let mut likelihood = match dialect {
+    Dialect::Vixie => 4.0,
+    Dialect::Aws | Dialect::Quartz | Dialect::Kubernetes => 2.0,
+    Dialect::Spring => 1.0,
+  };
+
+  if let ExpressionKind::Fields(ref fields) = crontab.expression().kind {
+    for field in fields {
+      let no_specific_value = field.items.iter().any(|item| {
+        item.kind == ItemKind::Special(Special::NoSpecificValue)
+      });
+      if !no_specific_value {
+        continue;
+      }
+
+      let day = field.kind == FieldKind::DayOfMonth || field.kind == FieldKind::DayOfWeek;
+      likelihood *= match (day, dialect.requires_no_specific_day()) {
+        // The dialect's way of leaving a day field to the other.
+        (true, true) => 4.0,
+        (true, false) => 1.0,
+        // `?` for `*` outside the day fields is unusual.
+        (false, _) => 0.25,
+      };
+    }
+  }
+
+  likelihood
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+
+  /// The candidates' dialects and confidences, as percentages.
+  fn detect(source: &str) -> Vec<(Dialect, u32)> {
+    let detection = Dialect::detect(source).unwrap();
+    Some(detection.best).into_iter()
+        .chain(detection.alternatives)
+        .map(|candidate| (candidate.dialect, (candidate.confidence * 100.0).round() as u32))
+        .collect()
+  }
+
+  #[test]
+  fn unambiguous() {
+    expect!(detect("CRON_TZ=UTC 0 9 * * *")).to(be_equal_to(vec![(Dialect::Vixie, 100)]));
+    expect!(detect("*/5 * ? * *")).to(be_equal_to(vec![(Dialect::Kubernetes, 100)]));
+    expect!(detect("cron(0 12 ? * MON-FRI *)")).to(be_equal_to(vec![(Dialect::Aws, 100)]));
+    expect!(detect("15 10 L * ? 2030")).to(be_equal_to(vec![(Dialect::Aws, 100)]));
+    expect!(detect("0 0 12 ? * 6#3 2030")).to(be_equal_to(vec![(Dialect::Quartz, 100)]));
+    expect!(detect("0 */15 9-17 * * MON-FRI")).to(be_equal_to(vec![(Dialect::Spring, 100)]));
+  }
+
+  #[test]
+  fn ambiguous() {
+    expect!(detect("0 9 * * 1-5"))
+        .to(be_equal_to(vec![(Dialect::Vixie, 67), (Dialect::Kubernetes, 33)]));
+    expect!(detect("@daily"))
+        .to(be_equal_to(vec![(Dialect::Vixie, 57), (Dialect::Kubernetes, 29),
+                             (Dialect::Spring, 14)]));
+
+    // Seconds first, or a year last.
+    expect!(detect("0 0 12 * * ?"))
+        .to(be_equal_to(vec![(Dialect::Quartz, 89), (Dialect::Spring, 11)]));
+    expect!(detect("0 12 * * ? *"))
+        .to(be_equal_to(vec![(Dialect::Aws, 97), (Dialect::Spring, 3)]));
+  }
+
+  #[test]
+  fn nothing_accepts() {
+    expect!(Dialect::detect("H H(0-7) * * *")).to(be_err());
+    expect!(Dialect::detect("0 12 * *")).to(be_err());
+    expect!(Dialect::detect("THU * * * * * * *")).to(be_err());
+    expect!(detect("0 0 * * THU")).to(be_equal_to(vec![(Dialect::Vixie, 67),
+                                                      (Dialect::Kubernetes, 33)]));
+  }
+}
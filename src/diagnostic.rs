@@ -0,0 +1,380 @@
+//! Compiler-style reports of errors in an expression.
+
+use ast::{FieldKind, Span};
+use calendar::{Month, Weekday};
+use description::join_list;
+use dialect::Dialect;
+use error::CrontabError;
+use std::fmt;
+
+/// An error along with where it is in the source, before the rest of the
+/// context is known.
+pub (crate) type Located<T> = Result<T, (CrontabError, Span)>;
+
+/// How to render a diagnostic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Style {
+  /// Plain text.
+  Plain,
+  /// Text with ANSI colors, for terminals.
+  Ansi,
+}
+
+/// An error in an expression, with enough context to point at it. See
+/// `Crontab::diagnose`.
+///
+/// ```
+/// use crontab::{Crontab, Style};
+///
+/// let diagnostic = Crontab::diagnose("0 9 * * MNO-FRI").unwrap_err();
+/// let rendered = diagnostic.render(Style::Plain);
+/// let lines : Vec<&str> = rendered.lines().collect();
+///
+/// assert_eq!(lines, [
+///   "error: Unknown day of week name: MNO. Did you mean MON?",
+///   "  0 9 * * MNO-FRI",
+///   "          ^^^ day of week",
+///   "  = help: did you mean `MON`?",
+/// ]);
+/// ```
+#[derive(Debug)]
+pub struct Diagnostic {
+  /// The error.
+  pub error: CrontabError,
+  /// The expression the error is in.
+  pub source: String,
+  /// The offending part of the expression.
+  pub span: Span,
+  /// The field the error is in, if it is in one.
+  pub field: Option<FieldKind>,
+  hint: Hint,
+  dialect: Dialect,
+}
+
+/// Which hint a diagnostic gives. Errors outside the fields can need a hint
+/// that the error alone doesn't tell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub (crate) enum Hint {
+  /// The usual hint for the error.
+  Error,
+  /// A time zone prefix is missing its name.
+  Zone,
+  /// The dialect has no time zone prefix.
+  ZonePrefix,
+  /// There are too few or too many fields.
+  FieldCount,
+  /// A crontab file entry has a schedule but no command.
+  Command,
+  /// A system crontab entry has a schedule but no user.
+  User,
+  /// A crontab file entry's schedule has a time zone prefix.
+  EntryZone,
+  /// A system crontab entry's user isn't a valid user name.
+  UserName,
+  /// An anacrontab job is missing a column or has an invalid one.
+  AnacronJob,
+  /// The day fields use `?` where they can't.
+  NoSpecificValue,
+  /// A Kubernetes CronJob has no schedule or an invalid time zone.
+  CronJob,
+}
+
+impl Diagnostic {
+  pub (crate) fn new(error: CrontabError, source: &str, span: Span,
+                     field: Option<FieldKind>) -> Diagnostic {
+    Diagnostic {
+      error,
+      source: source.to_string(),
+      span,
+      field,
+      hint: Hint::Error,
+      dialect: Dialect::Vixie,
+    }
+  }
+
+  /// Give hints for the dialect the source was parsed in.
+  pub (crate) fn in_dialect(mut self, dialect: Dialect) -> Diagnostic {
+    self.dialect = dialect;
+    self
+  }
+
+  /// Replace the hint at how to fix the error.
+  pub (crate) fn with_hint(mut self, hint: Hint) -> Diagnostic {
+    self.hint = hint;
+    self
+  }
+
+  /// The diagnostic for the same error in a longer source, which has this
+  /// one's source at `offset`, eg. the line a schedule is on.
+  pub (crate) fn within(self, source: &str, offset: usize) -> Diagnostic {
+    Diagnostic {
+      source: source.to_string(),
+      span: self.span.offset(offset),
+      ..self
+    }
+  }
+
+  /// A hint at how to fix the error, if there is one, eg. "did you mean
+  /// `MON`?".
+  pub fn help(&self) -> Option<String> {
+    match self.hint {
+      Hint::Error => help(&self.error, self.field, self.dialect),
+      Hint::Zone => Some("name a time zone, eg. CRON_TZ=Europe/London".to_string()),
+      Hint::ZonePrefix => Some(match self.dialect {
+        Dialect::Kubernetes => "set the CronJob's `timeZone` field instead".to_string(),
+        _ => "set the time zone where the schedule is defined instead".to_string(),
+      }),
+      Hint::FieldCount => {
+        let mut fields = self.dialect.fields();
+        if self.dialect.optional_year() {
+          fields = &fields[.. fields.len() - 1];
+        }
+        let names : Vec<String> = fields.iter().map(|f| f.name().to_string()).collect();
+        Some(format!("an expression has {} fields: {}{}", number(fields.len()),
+                     join_list(&names, ", ", " and ", false),
+                     if self.dialect.optional_year() { ", then optionally a year" } else { "" }))
+      },
+      Hint::NoSpecificValue => {
+        Some("use `?` for the day of month or the day of week, whichever the \
+              other one decides".to_string())
+      },
+      Hint::Command => {
+        Some("an entry is a schedule followed by the command to run".to_string())
+      },
+      Hint::EntryZone => {
+        Some("set `CRON_TZ` on a line of its own before the entry instead".to_string())
+      },
+      Hint::User => {
+        Some("a system crontab entry is a schedule, the user to run as and \
+              the command to run".to_string())
+      },
+      Hint::AnacronJob => {
+        Some("a job is a period in days or @monthly, a delay in minutes, an \
+              identifier without `/` and a command".to_string())
+      },
+      Hint::CronJob => {
+        Some("a CronJob has a schedule in `spec.schedule` and may name a time \
+              zone in `spec.timeZone`, eg. Europe/London".to_string())
+      },
+      Hint::UserName => {
+        Some("user names are letters, digits, `_`, `.` and `-`".to_string())
+      },
+    }
+  }
+
+  /// The error message, the expression, a line of carets under the
+  /// offending part labelled with its field, and a hint, eg.
+  ///
+  /// ```text
+  /// error: Value 60 is greater than maximum: 59
+  ///   60 * * * *
+  ///   ^^ minute
+  ///   = help: the minute field accepts 0-59
+  /// ```
+  pub fn render(&self, style: Style) -> String {
+    self.render_at(style, None)
+  }
+
+  /// Render with the number of the line the source is on in a file.
+  pub (crate) fn render_at(&self, style: Style, line_number: Option<usize>)
+      -> String {
+    let (red, cyan, bold, reset) = match style {
+      Style::Plain => ("", "", "", ""),
+      Style::Ansi => ("\x1b[1;31m", "\x1b[1;36m", "\x1b[1m", "\x1b[0m"),
+    };
+
+    // Columns are counted in characters, so line breaks and tabs are shown
+    // as single spaces to keep the carets lined up.
+    let line : String = self.source.chars()
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+    let start = self.source.get(.. self.span.start).map_or(0, |s| s.chars().count());
+    let width = self.span.text(&self.source).chars().count().max(1);
+
+    let mut carets = format!("{}{}", " ".repeat(start), "^".repeat(width));
+    if let Some(field) = self.field {
+      carets.push(' ');
+      carets.push_str(field.name());
+    }
+
+    let mut rendered = format!("{}error{}{}: {}{}\n", red, reset, bold, self.error, reset);
+    if let Some(number) = line_number {
+      rendered.push_str(&format!("  {}-->{} line {}\n", cyan, reset, number));
+    }
+    rendered.push_str(&format!("  {}\n  {}{}{}", line, red, carets, reset));
+
+    if let Some(help) = self.help() {
+      rendered.push_str(&format!("\n  {}= help{}: {}", cyan, reset, help));
+    }
+
+    rendered
+  }
+}
+
+/// Renders in plain text.
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.render(Style::Plain))
+  }
+}
+
+/// A number of fields in words.
+fn number(n: usize) -> String {
+  match n {
+    5 => "five".to_string(),
+    6 => "six".to_string(),
+    7 => "seven".to_string(),
+    _ => n.to_string(),
+  }
+}
+
+/// The default hint for an error.
+fn help(error: &CrontabError, field: Option<FieldKind>, dialect: Dialect)
+    -> Option<String> {
+  match *error {
+    CrontabError::UnknownName { suggestion: Some(ref suggestion), .. } => {
+      Some(format!("did you mean `{}`?", suggestion))
+    },
+    CrontabError::UnknownName { field: Some(field), .. } => {
+      names(field).map(|names| format!("the {} field accepts {}", field.name(), names))
+    },
+    CrontabError::UnknownName { field: None, .. } => {
+      Some("macros are @yearly, @annually, @monthly, @weekly, @daily, \
+            @midnight and @hourly".to_string())
+    },
+    CrontabError::FieldOutsideRange { .. } | CrontabError::ErrParseInt(_) => {
+      field.map(|field| accepts(field, dialect))
+    },
+    // The only format error within a field is a zero step.
+    CrontabError::ErrCronFormat(_) => field.map(|_| "steps must be at least 1".to_string()),
+  }
+}
+
+/// What a field accepts, eg. "the month field accepts 1-12 or JAN-DEC".
+fn accepts(field: FieldKind, dialect: Dialect) -> String {
+  let (min, max) = dialect.range(field);
+  let mut accepts = format!("the {} field accepts {}-{}", field.name(), min, max);
+  if let Some(names) = names(field) {
+    accepts.push_str(&format!(" or {}", names));
+  }
+  accepts
+}
+
+/// The range of names a field accepts, if it accepts names.
+fn names(field: FieldKind) -> Option<String> {
+  match field {
+    FieldKind::Month => Some(format!("{}-{}", Month::Jan.abbreviation(),
+                                     Month::Dec.abbreviation())),
+    FieldKind::DayOfWeek => Some(format!("{}-{}", Weekday::Sun.abbreviation(),
+                                         Weekday::Sat.abbreviation())),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ast::parse_expression;
+  use crontab::Crontab;
+  use expectest::prelude::*;
+  use super::*;
+
+  fn render(source: &str) -> String {
+    Crontab::diagnose(source).unwrap_err().render(Style::Plain)
+  }
+
+  #[test]
+  fn value_outside_range() {
+    expect!(render("60 * * * *")).to(be_equal_to(
+      "error: Value 60 is greater than maximum: 59\n  \
+       60 * * * *\n  \
+       ^^ minute\n  \
+       = help: the minute field accepts 0-59".to_string()));
+
+    expect!(render("0 0 * 1-13 *")).to(be_equal_to(
+      "error: Value 13 is greater than maximum: 12\n  \
+       0 0 * 1-13 *\n  \
+       \x20     ^^^^ month\n  \
+       = help: the month field accepts 1-12 or JAN-DEC".to_string()));
+
+    expect!(render("30-5 * * * *")).to(be_equal_to(
+      "error: Range 30-5 runs backwards; write 30-59,0-5 to wrap around\n  \
+       30-5 * * * *\n  \
+       ^^^^ minute\n  \
+       = help: the minute field accepts 0-59".to_string()));
+  }
+
+  #[test]
+  fn unknown_names() {
+    expect!(render("0 9 * * MNO-FRI")).to(be_equal_to(
+      "error: Unknown day of week name: MNO. Did you mean MON?\n  \
+       0 9 * * MNO-FRI\n  \
+       \x20       ^^^ day of week\n  \
+       = help: did you mean `MON`?".to_string()));
+
+    expect!(render("0 9 * XYZ *")).to(be_equal_to(
+      "error: Unknown month name: XYZ\n  \
+       0 9 * XYZ *\n  \
+       \x20     ^^^ month\n  \
+       = help: the month field accepts JAN-DEC".to_string()));
+
+    expect!(render("@dialy")).to(be_equal_to(
+      "error: Unknown macro: @dialy. Did you mean @daily?\n  \
+       @dialy\n  \
+       ^^^^^^\n  \
+       = help: did you mean `@daily`?".to_string()));
+  }
+
+  #[test]
+  fn bad_numbers_and_steps() {
+    let diagnostic = Crontab::diagnose("*/x * * * *").unwrap_err();
+    expect!(diagnostic.span.text(&diagnostic.source)).to(be_equal_to("x"));
+    expect!(diagnostic.field).to(be_some().value(FieldKind::Minute));
+
+    let diagnostic = Crontab::diagnose("0 */0 * * *").unwrap_err();
+    expect!(diagnostic.span.text(&diagnostic.source)).to(be_equal_to("0"));
+    expect!(diagnostic.span.start).to(be_equal_to(4));
+    expect!(diagnostic.help()).to(be_some().value("steps must be at least 1".to_string()));
+  }
+
+  #[test]
+  fn wrong_number_of_fields() {
+    expect!(render("\t* * *")).to(be_equal_to(
+      "error: Invalid format: \t* * *\n  \
+       \x20* * *\n  \
+       \x20^^^^^\n  \
+       = help: an expression has five fields: minute, hour, day of month, \
+       month and day of week".to_string()));
+
+    let diagnostic = parse_expression("", Dialect::Vixie).unwrap_err();
+    expect!(diagnostic.render(Style::Plain).contains("\n  ^\n")).to(be_true());
+  }
+
+  #[test]
+  fn ansi() {
+    let rendered = Crontab::diagnose("60 * * * *").unwrap_err().render(Style::Ansi);
+    expect!(rendered.starts_with("\x1b[1;31merror\x1b[0m")).to(be_true());
+    expect!(rendered.contains("\x1b[1;31m^^ minute\x1b[0m")).to(be_true());
+    expect!(rendered.contains("\x1b[1;36m= help\x1b[0m")).to(be_true());
+  }
+
+  #[test]
+  fn aws() {
+    let render = |source: &str| {
+      Crontab::diagnose_dialect(source, Dialect::Aws).unwrap_err().render(Style::Plain)
+    };
+
+    expect!(render("0 12 ? * 0 *")).to(be_equal_to(
+      "error: Value 0 is less than minimum: 1\n  \
+       0 12 ? * 0 *\n  \
+       \x20        ^ day of week\n  \
+       = help: the day of week field accepts 1-7 or SUN-SAT".to_string()));
+
+    expect!(render("0 12 * * * *").ends_with(
+      "= help: use `?` for the day of month or the day of week, whichever the other one \
+       decides")).to(be_true());
+
+    expect!(render("0 12 ? * *").ends_with(
+      "= help: an expression has six fields: minute, hour, day of month, month, day of \
+       week and year")).to(be_true());
+  }
+}
@@ -0,0 +1,184 @@
+//! The flavours of cron expression syntax.
+
+use ast::FieldKind;
+use std::fmt;
+
+/// A flavour of cron expression syntax.
+///
+/// In every dialect, steps count from the start of their range, so `5/15`
+/// is 5, 20, 35 and 50, and `*/10` in the day of month is 1, 11, 21 and 31.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Dialect {
+  /// Vixie cron and its descendants, such as cronie: five fields, month and
+  /// weekday names, macros such as `@daily` and an optional `CRON_TZ=`
+  /// prefix.
+  #[default]
+  Vixie,
+  /// Amazon EventBridge: six fields ending with the year, optionally
+  /// wrapped in `cron(...)`, eg. `cron(0 12 ? * MON-FRI *)`. Days of the
+  /// week are [1,7] with 1 as Sunday, exactly one of the day fields must be
+  /// `?`, and the day fields accept `L`, `W` and `#`.
+  Aws,
+  /// Kubernetes CronJobs, which use robfig/cron: five fields and macros as
+  /// in Vixie cron, where `?` is another way to write `*` in any field.
+  /// There's no zone prefix, since a CronJob's zone belongs in its
+  /// `timeZone` field; see `CronJob`.
+  Kubernetes,
+  /// Quartz, the Java scheduler: six fields starting with the second, and
+  /// an optional seventh for the year, eg. `0 0/5 14,18 * * ?`. As in the
+  /// AWS dialect, days of the week are [1,7] with 1 as Sunday, exactly one
+  /// of the day fields must be `?`, and the day fields accept `L`, `W` and
+  /// `#`.
+  Quartz,
+  /// Spring's `@Scheduled` cron expressions: six fields starting with the
+  /// second, eg. `0 */15 9-17 * * MON-FRI`. Days of the week are [0,7] with
+  /// both 0 and 7 as Sunday, `?` means `*`, and the day fields accept `L`,
+  /// `W` and `#`. Macros such as
+  /// `@daily` fire at second 0. When both day fields are restricted, a day
+  /// must match both of them, rather than either as in cron.
+  Spring,
+}
+
+const VIXIE_FIELDS : [FieldKind; 5] = [
+  FieldKind::Minute,
+  FieldKind::Hour,
+  FieldKind::DayOfMonth,
+  FieldKind::Month,
+  FieldKind::DayOfWeek,
+];
+
+const SECONDS_FIELDS : [FieldKind; 7] = [
+  FieldKind::Second,
+  FieldKind::Minute,
+  FieldKind::Hour,
+  FieldKind::DayOfMonth,
+  FieldKind::Month,
+  FieldKind::DayOfWeek,
+  FieldKind::Year,
+];
+
+const AWS_FIELDS : [FieldKind; 6] = [
+  FieldKind::Minute,
+  FieldKind::Hour,
+  FieldKind::DayOfMonth,
+  FieldKind::Month,
+  FieldKind::DayOfWeek,
+  FieldKind::Year,
+];
+
+impl Dialect {
+  /// The name of the dialect, eg. "vixie".
+  pub fn name(self) -> &'static str {
+    match self {
+      Dialect::Vixie => "vixie",
+      Dialect::Aws => "aws",
+      Dialect::Kubernetes => "kubernetes",
+      Dialect::Quartz => "quartz",
+      Dialect::Spring => "spring",
+    }
+  }
+
+  /// The dialect with a name, as given by `Dialect::name`.
+  #[cfg(feature = "serde")]
+  pub (crate) fn from_name(name: &str) -> Option<Dialect> {
+    match name {
+      "vixie" => Some(Dialect::Vixie),
+      "aws" => Some(Dialect::Aws),
+      "kubernetes" => Some(Dialect::Kubernetes),
+      "quartz" => Some(Dialect::Quartz),
+      "spring" => Some(Dialect::Spring),
+      _ => None,
+    }
+  }
+
+  /// The fields of an expression, in order, including an optional year.
+  pub (crate) fn fields(self) -> &'static [FieldKind] {
+    match self {
+      Dialect::Vixie | Dialect::Kubernetes => &VIXIE_FIELDS,
+      Dialect::Aws => &AWS_FIELDS,
+      Dialect::Quartz => &SECONDS_FIELDS,
+      Dialect::Spring => &SECONDS_FIELDS[.. 6],
+    }
+  }
+
+  /// Whether the last field, the year, may be left out.
+  pub (crate) fn optional_year(self) -> bool {
+    self == Dialect::Quartz
+  }
+
+  /// Whether expressions start with the second.
+  pub (crate) fn seconds(self) -> bool {
+    self.fields()[0] == FieldKind::Second
+  }
+
+  /// The inclusive range of values a field accepts, as written in the
+  /// dialect.
+  pub (crate) fn range(self, kind: FieldKind) -> (u32, u32) {
+    match (self, kind) {
+      (Dialect::Aws, FieldKind::DayOfWeek) | (Dialect::Quartz, FieldKind::DayOfWeek) => (1, 7),
+      (Dialect::Spring, FieldKind::DayOfWeek) => (0, 7),
+      _ => (kind.min(), kind.max()),
+    }
+  }
+
+  /// A value of a field as written in the dialect, as the value in schedule
+  /// components. Days of the week become [0,6] with 0 as Sunday.
+  pub (crate) fn schedule_value(self, kind: FieldKind, value: u32) -> u32 {
+    match (self, kind) {
+      (Dialect::Aws, FieldKind::DayOfWeek) | (Dialect::Quartz, FieldKind::DayOfWeek) => value - 1,
+      (Dialect::Spring, FieldKind::DayOfWeek) => value % 7,
+      _ => value,
+    }
+  }
+
+  /// A value of a field in schedule components, as written in the dialect.
+  pub (crate) fn written_value(self, kind: FieldKind, value: u32) -> u32 {
+    match (self, kind) {
+      (Dialect::Aws, FieldKind::DayOfWeek) | (Dialect::Quartz, FieldKind::DayOfWeek) => value + 1,
+      _ => value,
+    }
+  }
+
+  /// Whether the day fields accept `?`, `L`, `W` and `#`.
+  pub (crate) fn day_extensions(self) -> bool {
+    match self {
+      Dialect::Aws | Dialect::Quartz | Dialect::Spring => true,
+      Dialect::Vixie | Dialect::Kubernetes => false,
+    }
+  }
+
+  /// Whether `?` means `*`, in any field and alongside other items.
+  pub (crate) fn any_value_question_mark(self) -> bool {
+    self == Dialect::Kubernetes || self == Dialect::Spring
+  }
+
+  /// Whether exactly one of the day fields must be `?`.
+  pub (crate) fn requires_no_specific_day(self) -> bool {
+    self == Dialect::Aws || self == Dialect::Quartz
+  }
+
+  /// Whether a day must match both day fields when both are restricted,
+  /// rather than either of them.
+  pub (crate) fn both_days(self) -> bool {
+    self == Dialect::Spring
+  }
+
+  /// Whether macros such as `@daily` are accepted.
+  pub (crate) fn macros(self) -> bool {
+    match self {
+      Dialect::Vixie | Dialect::Kubernetes | Dialect::Spring => true,
+      Dialect::Aws | Dialect::Quartz => false,
+    }
+  }
+
+  /// Whether a `CRON_TZ=` or `TZ=` prefix is accepted.
+  pub (crate) fn zones(self) -> bool {
+    self == Dialect::Vixie
+  }
+}
+
+impl fmt::Display for Dialect {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
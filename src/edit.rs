@@ -0,0 +1,350 @@
+//! Editing crontab files without disturbing their comments, alignment or
+//! blank lines.
+//!
+//! Each edit rewrites only the lines it changes, and within a rescheduled
+//! entry only the schedule, so printing a file gives back the text it was
+//! parsed from, byte for byte, apart from the edits.
+
+use ast::Span;
+use crontab::Crontab;
+use diagnostic::{Diagnostic, Hint};
+use error::CrontabError;
+use file::{CrontabFile, FileError, Format, Line, LineKind, parse_line};
+use std::fmt;
+
+impl CrontabFile {
+  /// Append an entry, or any other line, to the end of the file, returning
+  /// its line number. If the last line has no line break, it gets the same
+  /// kind of line break as the rest of the file.
+  ///
+  /// ```
+  /// let mut file = crontab::CrontabFile::parse("MAILTO=ops\n").unwrap();
+  /// file.add("0 4 * * * vacuumdb --all").unwrap();
+  /// assert_eq!(file.to_string(), "MAILTO=ops\n0 4 * * * vacuumdb --all\n");
+  /// ```
+  pub fn add(&mut self, text: &str) -> Result<usize, FileError> {
+    let number = self.lines.len() + 1;
+    let kind = self.parse_edit(number, text)?;
+    let ending = self.line_ending();
+
+    if let Some(last) = self.lines.last_mut() {
+      if last.ending.is_empty() {
+        last.ending = ending.to_string();
+      }
+    }
+
+    self.lines.push(Line {
+      number,
+      text: text.to_string(),
+      ending: ending.to_string(),
+      kind,
+    });
+
+    Ok(number)
+  }
+
+  /// Remove a line, returning it. The lines after it are renumbered. Fails
+  /// if there is no line `number`.
+  pub fn remove(&mut self, number: usize) -> Result<Line, FileError> {
+    let index = self.index(number)?;
+    let line = self.lines.remove(index);
+
+    // Keep the file ending as it did.
+    if index == self.lines.len() && index > 0 {
+      self.lines[index - 1].ending = line.ending.clone();
+    }
+
+    for line in &mut self.lines[index ..] {
+      line.number -= 1;
+    }
+
+    Ok(line)
+  }
+
+  /// Change the schedule of an entry, leaving the rest of its line as it
+  /// was. The schedule can't have a time zone prefix, which would make the
+  /// line a variable: set `CRON_TZ` on a line of its own instead.
+  ///
+  /// ```
+  /// let mut file = crontab::CrontabFile::parse(
+  ///   "30  2 * * *   backup   # nightly\n").unwrap();
+  /// file.reschedule(1, "@weekly").unwrap();
+  /// assert_eq!(file.to_string(), "@weekly   backup   # nightly\n");
+  ///
+  /// assert!(file.reschedule(1, "CRON_TZ=UTC @weekly").is_err());
+  /// assert!(file.reschedule(2, "@weekly").is_err());
+  /// ```
+  pub fn reschedule(&mut self, number: usize, schedule: &str) -> Result<(), FileError> {
+    let index = self.index(number)?;
+    let span = match self.lines[index].kind {
+      LineKind::Entry(ref entry) => entry.schedule_span,
+      _ => return Err(not_an_entry(&self.lines[index])),
+    };
+
+    if schedule != "@reboot" {
+      let crontab = Crontab::diagnose(schedule)
+          .map_err(|diagnostic| FileError::new(number, diagnostic))?;
+      if let Some(zone) = crontab.zone() {
+        let error = CrontabError::ErrCronFormat(format!(
+          "An entry's schedule can't have a time zone prefix: {}", schedule));
+        let diagnostic = Diagnostic::new(error, schedule, zone.span, None)
+            .with_hint(Hint::EntryZone);
+        return Err(FileError::new(number, diagnostic));
+      }
+    }
+
+    let text = splice(&self.lines[index].text, span, schedule);
+    let kind = self.parse_edit(number, &text)?;
+    if !matches!(kind, LineKind::Entry(_)) {
+      let error = CrontabError::ErrCronFormat(
+        format!("The schedule would make line {} something other than an entry", number));
+      let diagnostic = Diagnostic::new(error, &text, Span::new(0, text.len()), None);
+      return Err(FileError::new(number, diagnostic));
+    }
+
+    let line = &mut self.lines[index];
+    line.text = text;
+    line.kind = kind;
+    Ok(())
+  }
+
+  /// Comment out an entry, so that it no longer runs, by putting a `#` at
+  /// the start of its line.
+  pub fn disable(&mut self, number: usize) -> Result<(), FileError> {
+    let index = self.index(number)?;
+    if !self.lines[index].is_entry() {
+      return Err(not_an_entry(&self.lines[index]));
+    }
+
+    let text = format!("#{}", self.lines[index].text);
+    self.replace(index, text)
+  }
+
+  /// Uncomment a disabled entry, removing the `#` and any whitespace after
+  /// it.
+  ///
+  /// ```
+  /// let mut file = crontab::CrontabFile::parse("  # 0 3 * * * backup\n").unwrap();
+  /// file.enable(1).unwrap();
+  /// assert_eq!(file.to_string(), "  0 3 * * * backup\n");
+  /// file.disable(1).unwrap();
+  /// assert_eq!(file.to_string(), "#  0 3 * * * backup\n");
+  /// ```
+  pub fn enable(&mut self, number: usize) -> Result<(), FileError> {
+    let index = self.index(number)?;
+    let text = match self.lines[index].disabled_entry(self.format) {
+      Some(text) => text,
+      None => return Err(not_an_entry(&self.lines[index])),
+    };
+
+    self.replace(index, text)
+  }
+
+  /// The numbers of the comment lines that are disabled entries.
+  pub fn disabled(&self) -> Vec<usize> {
+    self.lines.iter()
+        .filter(|line| line.disabled_entry(self.format).is_some())
+        .map(|line| line.number)
+        .collect()
+  }
+
+  /// The index of line `number`, or an error if there is no such line.
+  fn index(&self, number: usize) -> Result<usize, FileError> {
+    if number < 1 || number > self.lines.len() {
+      let error = CrontabError::ErrCronFormat(
+        format!("No line {} in a file of {} lines", number, self.lines.len()));
+      return Err(FileError::new(number, Diagnostic::new(error, "", Span::new(0, 0), None)));
+    }
+
+    Ok(number - 1)
+  }
+
+  /// The line break used most in the file, or `"\n"` for a new file.
+  fn line_ending(&self) -> &'static str {
+    let crlf = self.lines.iter().filter(|line| line.ending == "\r\n").count();
+    let lf = self.lines.iter().filter(|line| line.ending == "\n").count();
+    if crlf > lf { "\r\n" } else { "\n" }
+  }
+
+  fn parse_edit(&self, number: usize, text: &str) -> Result<LineKind, FileError> {
+    if text.contains(['\n', '\r']) {
+      let error = CrontabError::ErrCronFormat(
+        format!("A line can't contain a line break: {:?}", text));
+      let diagnostic = Diagnostic::new(error, text, Span::new(0, text.len()), None);
+      return Err(FileError::new(number, diagnostic));
+    }
+
+    parse_line(text, self.format).map_err(|diagnostic| FileError::new(number, diagnostic))
+  }
+
+  fn replace(&mut self, index: usize, text: String) -> Result<(), FileError> {
+    let kind = self.parse_edit(index + 1, &text)?;
+    let line = &mut self.lines[index];
+    line.text = text;
+    line.kind = kind;
+    Ok(())
+  }
+}
+
+impl Line {
+  fn is_entry(&self) -> bool {
+    matches!(self.kind, LineKind::Entry(_))
+  }
+
+  /// The text of the line without its `#`, if it is a commented-out entry.
+  fn disabled_entry(&self, format: Format) -> Option<String> {
+    if let LineKind::Comment(ref comment) = self.kind {
+      let hash = self.text.find('#')?;
+      let text = format!("{}{}", &self.text[.. hash], comment.trim_start());
+
+      // Any comment with an `=` would pass for a variable, so only entries
+      // count.
+      if let Ok(LineKind::Entry(_)) = parse_line(&text, format) {
+        return Some(text);
+      }
+    }
+
+    None
+  }
+}
+
+/// Prints the file as written, with any edits.
+impl fmt::Display for CrontabFile {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for line in &self.lines {
+      write!(f, "{}{}", line.text, line.ending)?;
+    }
+    Ok(())
+  }
+}
+
+fn not_an_entry(line: &Line) -> FileError {
+  let error = CrontabError::ErrCronFormat(format!("Line {} is not an entry", line.number));
+  let span = Span::new(0, line.text.len());
+  FileError::new(line.number, Diagnostic::new(error, &line.text, span, None))
+}
+
+/// The text with a span of it replaced.
+fn splice(text: &str, span: Span, replacement: &str) -> String {
+  format!("{}{}{}", &text[.. span.start], replacement, &text[span.end ..])
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use file::CrontabFile;
+
+  const FILE : &str = "# Backups\r\n\
+                       MAILTO=ops  # not a comment\r\n\
+                       \r\n\
+                       30   2 * * *\tbackup  --all %now\r\n\
+                       #0 5 * * 1 weekly-report\r\n\
+                       @reboot   warm-cache";
+
+  #[test]
+  fn round_trip() {
+    let file = CrontabFile::parse(FILE).unwrap();
+    expect!(file.to_string()).to(be_equal_to(FILE.to_string()));
+
+    for text in &["", "\n", "\n\n", "a=b", "a=b\n\n  \n", "* * * * * x\r\ny=z\n"] {
+      expect!(CrontabFile::parse(text).unwrap().to_string()).to(be_equal_to(text.to_string()));
+    }
+  }
+
+  #[test]
+  fn reschedule() {
+    let mut file = CrontabFile::parse(FILE).unwrap();
+    file.reschedule(4, "*/15 9-17 * * MON-FRI").unwrap();
+    file.reschedule(6, "@hourly").unwrap();
+
+    expect!(file.to_string()).to(be_equal_to(
+      FILE.replace("30   2 * * *", "*/15 9-17 * * MON-FRI").replace("@reboot", "@hourly")));
+    expect!(file.entries()[0].crontab.as_ref().unwrap().source())
+        .to(be_equal_to("*/15 9-17 * * MON-FRI"));
+    expect!(file.entries()[1].command.as_str()).to(be_equal_to("warm-cache"));
+  }
+
+  #[test]
+  fn reschedule_errors() {
+    let mut file = CrontabFile::parse(FILE).unwrap();
+
+    let error = file.reschedule(4, "0 25 * * *").unwrap_err();
+    expect!(error.line).to(be_equal_to(4));
+    expect!(error.diagnostic.error.to_string())
+        .to(be_equal_to("Value 25 is greater than maximum: 23".to_string()));
+
+    expect!(file.reschedule(4, "0 2 * *")).to(be_err());
+    expect!(file.reschedule(4, "0 2 * * * extra")).to(be_err());
+    expect!(file.reschedule(4, "0 2 * *\n*")).to(be_err());
+    expect!(file.reschedule(2, "@daily")).to(be_err());
+    expect!(file.to_string()).to(be_equal_to(FILE.to_string()));
+
+    // A zone prefix would make the entry a variable.
+    let error = file.reschedule(4, "CRON_TZ=UTC 0 2 * * *").unwrap_err();
+    expect!(error.diagnostic.span.text(&error.diagnostic.source)).to(be_equal_to("CRON_TZ=UTC"));
+    expect!(error.diagnostic.help()).to(be_some().value(
+      "set `CRON_TZ` on a line of its own before the entry instead".to_string()));
+    expect!(file.entries().len()).to(be_equal_to(2));
+    expect!(file.to_string()).to(be_equal_to(FILE.to_string()));
+  }
+
+  #[test]
+  fn missing_lines() {
+    let mut file = CrontabFile::parse(FILE).unwrap();
+
+    let error = file.remove(7).unwrap_err();
+    expect!(error.line).to(be_equal_to(7));
+    expect!(error.diagnostic.error.to_string())
+        .to(be_equal_to("No line 7 in a file of 6 lines".to_string()));
+
+    expect!(file.remove(0)).to(be_err());
+    expect!(file.reschedule(7, "@daily")).to(be_err());
+    expect!(file.enable(7)).to(be_err());
+    expect!(file.disable(0)).to(be_err());
+    expect!(file.to_string()).to(be_equal_to(FILE.to_string()));
+  }
+
+  #[test]
+  fn enable_and_disable() {
+    let mut file = CrontabFile::parse(FILE).unwrap();
+    expect!(file.disabled()).to(be_equal_to(vec![5]));
+
+    file.enable(5).unwrap();
+    file.disable(4).unwrap();
+    expect!(file.disabled()).to(be_equal_to(vec![4]));
+    expect!(file.entries().len()).to(be_equal_to(2));
+    expect!(file.to_string()).to(be_equal_to(
+      FILE.replace("30   2", "#30   2").replace("#0 5", "0 5")));
+
+    // Comments that aren't entries stay comments.
+    expect!(file.enable(1)).to(be_err());
+    expect!(file.disable(1)).to(be_err());
+    expect!(file.disable(3)).to(be_err());
+  }
+
+  #[test]
+  fn add_and_remove() {
+    let mut file = CrontabFile::parse(FILE).unwrap();
+    expect!(file.add("0 0 1 * * invoice")).to(be_ok().value(7));
+    expect!(file.to_string()).to(be_equal_to(format!("{}\r\n0 0 1 * * invoice\r\n", FILE)));
+
+    let removed = file.remove(2).unwrap();
+    expect!(removed.text.as_str()).to(be_equal_to("MAILTO=ops  # not a comment"));
+    expect!(file.lines.iter().map(|l| l.number).collect::<Vec<usize>>())
+        .to(be_equal_to(vec![1, 2, 3, 4, 5, 6]));
+    expect!(file.variable("MAILTO")).to(be_none());
+
+    // Removing the last line keeps the file ending the way it did.
+    let mut file = CrontabFile::parse("a=b\nc=d").unwrap();
+    file.remove(2).unwrap();
+    expect!(file.to_string()).to(be_equal_to("a=b".to_string()));
+
+    expect!(file.add("0 0 * * *")).to(be_err());
+    expect!(file.add("x\ny")).to(be_err());
+    expect!(file.to_string()).to(be_equal_to("a=b".to_string()));
+
+    let mut file = CrontabFile::parse("").unwrap();
+    file.add("# new").unwrap();
+    expect!(file.to_string()).to(be_equal_to("# new\n".to_string()));
+  }
+}
@@ -1,8 +1,9 @@
+use ast::{FieldKind, Span};
 use std::fmt;
 use std::num::ParseIntError;
 
 // TODO: These errors could use some improvement, but that would be breaking.
-/// A library error.
+/// A library error. See `Diagnostic` for showing one with its context.
 #[derive(Debug)]
 pub enum CrontabError {
   /// Error parsing the crontab schedule.
@@ -15,6 +16,37 @@ pub enum CrontabError {
     /// Description of the error.
     description: String,
   },
+  /// Parse error. A month or weekday name, or a macro, that isn't known,
+  /// such as "MNO" or "@dialy".
+  UnknownName {
+    /// The name as written.
+    name: String,
+    /// The field the name is in, or `None` for a macro.
+    field: Option<FieldKind>,
+    /// Where the name is in the expression.
+    span: Span,
+    /// A similar known name, if there is one, eg. "MON" for "MNO".
+    suggestion: Option<String>,
+  },
+}
+
+impl CrontabError {
+  /// A similar known name to the one that wasn't recognized, if any.
+  ///
+  /// ```
+  /// let err = crontab::Crontab::parse("0 9 * * MNO-FRI").unwrap_err();
+  /// assert_eq!(err.suggestion(), Some("MON"));
+  /// assert_eq!(err.to_string(),
+  ///   "Unknown day of week name: MNO. Did you mean MON?");
+  /// ```
+  pub fn suggestion(&self) -> Option<&str> {
+    match *self {
+      CrontabError::UnknownName { ref suggestion, .. } => {
+        suggestion.as_ref().map(|s| s.as_str())
+      },
+      _ => None,
+    }
+  }
 }
 
 impl From<ParseIntError> for CrontabError {
@@ -25,11 +57,22 @@ impl From<ParseIntError> for CrontabError {
 
 impl fmt::Display for CrontabError {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    match self {
-      &CrontabError::ErrCronFormat(ref x) => write!(f, "<ErrCronFormat> {:?}", x),
-      &CrontabError::ErrParseInt(ref e) => write!(f, "<ErrParseInt> {:?}", e),
-      &CrontabError::FieldOutsideRange{ ref description } => {
-        write!(f, "<FieldOutsideRange> {:?}", description)
+    match *self {
+      CrontabError::ErrCronFormat(ref x) => write!(f, "{}", x),
+      CrontabError::ErrParseInt(ref e) => write!(f, "Invalid number: {}", e),
+      CrontabError::FieldOutsideRange{ ref description } => {
+        write!(f, "{}", description)
+      },
+      CrontabError::UnknownName { ref name, field, ref suggestion, .. } => {
+        let kind = match field {
+          Some(field) => format!("{} name", field.name()),
+          None => "macro".to_string(),
+        };
+        write!(f, "Unknown {}: {}", kind, name)?;
+        if let Some(ref suggestion) = *suggestion {
+          write!(f, ". Did you mean {}?", suggestion)?;
+        }
+        Ok(())
       },
     }
   }
@@ -0,0 +1,352 @@
+//! Explanations of why a time does or doesn't match a schedule.
+
+use ast::FieldKind;
+use calendar::{Month, Weekday};
+use crontab::{day_rule, field_matches};
+use dialect::Dialect;
+use formatting::{FieldItem, field_items, format_day_special};
+use parsing::ScheduleComponents;
+use std::fmt;
+use time::Tm;
+use times::weekday;
+
+/// How the day of month and day of week fields combine to select days.
+///
+/// A field is restricted when it leaves out some days, however it's written.
+/// Vixie cron goes by whether a field starts with `*` instead, so there
+/// `0 0 1-31 * MON` runs every day and `0 0 */2 * MON` on odd-numbered
+/// Mondays, where here they run on Mondays and on odd days or Mondays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DayRule {
+  /// Neither field is restricted, so every day matches.
+  Any,
+  /// Only the day of month is restricted, so it alone selects days.
+  DayOfMonth,
+  /// Only the day of week is restricted, so it alone selects days.
+  DayOfWeek,
+  /// Both fields are restricted, so a day matches when *either* does.
+  Either,
+  /// Both fields are restricted, and a day matches only when *both* do, as
+  /// in Spring.
+  Both,
+}
+
+impl DayRule {
+  /// Whether a day matches, given whether its day of month and day of week
+  /// match their fields.
+  pub fn matches(self, day_of_month: bool, day_of_week: bool) -> bool {
+    match self {
+      DayRule::Any => true,
+      DayRule::DayOfMonth => day_of_month,
+      DayRule::DayOfWeek => day_of_week,
+      DayRule::Either => day_of_month || day_of_week,
+      DayRule::Both => day_of_month && day_of_week,
+    }
+  }
+}
+
+/// The verdict for a single field of a time.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FieldVerdict {
+  /// Which field this is.
+  pub field: FieldKind,
+  /// The value of the field in the time, eg. 6 for Saturday.
+  pub value: u32,
+  /// Whether the value is one of the field's values.
+  pub matches: bool,
+  /// Whether the field is restricted, rather than including every value.
+  pub restricted: bool,
+  /// The values of the field, eg. "Mon–Fri" or "*/15".
+  pub allowed: String,
+}
+
+/// Why a time does or doesn't match a schedule, field by field. See
+/// `Crontab::explain`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Explanation {
+  /// The verdicts for the minute, hour, day of month, month and day of
+  /// week, in that order, then the second if the schedule has seconds and
+  /// the year if it restricts it.
+  pub fields: Vec<FieldVerdict>,
+  /// How the day fields were combined.
+  pub day_rule: DayRule,
+  /// Whether the time matches the schedule.
+  pub matches: bool,
+}
+
+impl Explanation {
+  /// Explain a time against schedule components. The day of week is taken
+  /// from the date, rather than `tm_wday`.
+  pub (crate) fn new(times: &ScheduleComponents, time: &Tm) -> Explanation {
+    let values = [
+      (FieldKind::Minute, times.minutes(), time.tm_min as u32),
+      (FieldKind::Hour, times.hours(), time.tm_hour as u32),
+      (FieldKind::DayOfMonth, times.days(), time.tm_mday as u32),
+      (FieldKind::Month, times.months(), Month::from_tm(time).number()),
+      (FieldKind::DayOfWeek, times.weekdays(), weekday(time)),
+    ];
+
+    let mut fields : Vec<FieldVerdict> = values.iter()
+        .map(|&(field, values, value)| FieldVerdict {
+          field,
+          value,
+          matches: field_matches(values, value),
+          restricted: values.len() as u32 != field.max() - field.min() + 1,
+          allowed: allowed(values, field),
+        })
+        .collect();
+
+    // Days such as `L` or `MON#2` depend on the whole date.
+    for &(index, day_of_month) in &[(2, true), (4, false)] {
+      let verdict = &mut fields[index];
+      verdict.restricted = times.restricts_days(day_of_month);
+      verdict.matches = if day_of_month {
+        times.day_of_month_matches(time)
+      } else {
+        times.day_of_week_matches(time)
+      };
+
+      let values = if day_of_month { times.days() } else { times.weekdays() };
+      let mut allowed = Vec::new();
+      if !values.is_empty() {
+        allowed.push(verdict.allowed.clone());
+      }
+      allowed.extend(times.day_specials().iter()
+          .filter(|special| special.is_day_of_month() == day_of_month)
+          .map(|&special| format_day_special(special, Dialect::Vixie)));
+      verdict.allowed = allowed.join(", ");
+    }
+
+    if let Some(seconds) = times.seconds() {
+      let value = time.tm_sec as u32;
+      fields.push(FieldVerdict {
+        field: FieldKind::Second,
+        value,
+        matches: field_matches(seconds, value),
+        restricted: seconds.len() < 60,
+        allowed: allowed(seconds, FieldKind::Second),
+      });
+    }
+
+    if let Some(years) = times.years() {
+      let value = (time.tm_year + 1900) as u32;
+      fields.push(FieldVerdict {
+        field: FieldKind::Year,
+        value,
+        matches: field_matches(years, value),
+        restricted: true,
+        allowed: allowed(years, FieldKind::Year),
+      });
+    }
+
+    let day_rule = day_rule(times);
+    let matches = fields[0].matches
+        && fields[1].matches
+        && fields[3].matches
+        && day_rule.matches(fields[2].matches, fields[4].matches)
+        && fields.iter().skip(5).all(|verdict| verdict.matches);
+
+    Explanation {
+      fields,
+      day_rule,
+      matches,
+    }
+  }
+
+  /// The verdict for a field.
+  pub fn field(&self, field: FieldKind) -> &FieldVerdict {
+    self.fields.iter()
+        .find(|verdict| verdict.field == field)
+        .expect("every field has a verdict")
+  }
+}
+
+/// The name of a value, eg. "Saturday" for day of week 6.
+fn value_name(value: u32, field: FieldKind) -> String {
+  let name = match field {
+    FieldKind::Month => Month::from_number(value).map(Month::name),
+    FieldKind::DayOfWeek => Weekday::from_number(value).map(Weekday::name),
+    _ => None,
+  };
+
+  name.map(|name| name.to_string()).unwrap_or_else(|| value.to_string())
+}
+
+/// The values of a field as canonical items, with weekdays and months
+/// abbreviated, eg. "Mon–Fri".
+fn allowed(values: &[u32], field: FieldKind) -> String {
+  let short = |value: u32| match field {
+    FieldKind::Month => Month::from_number(value).map(|m| title(m.abbreviation())),
+    FieldKind::DayOfWeek => {
+      Weekday::from_number(value).map(|w| title(w.abbreviation()))
+    },
+    _ => None,
+  }.unwrap_or_else(|| value.to_string());
+
+  field_items(values, field.min(), field.max()).iter()
+      .map(|item| match *item {
+        FieldItem::All => "*".to_string(),
+        FieldItem::Step(step) => format!("*/{}", step),
+        FieldItem::SteppedRange(start, end, step) => {
+          format!("{}–{}/{}", short(start), short(end), step)
+        },
+        FieldItem::Range(start, end) => format!("{}–{}", short(start), short(end)),
+        FieldItem::Value(value) => short(value),
+      })
+      .collect::<Vec<String>>()
+      .join(", ")
+}
+
+/// "MON" as "Mon".
+fn title(abbreviation: &str) -> String {
+  let lower = abbreviation.to_lowercase();
+  let mut chars = lower.chars();
+  match chars.next() {
+    Some(first) => first.to_uppercase().chain(chars).collect(),
+    None => String::new(),
+  }
+}
+
+/// A line per field, then the day rule and the overall verdict, eg.
+///
+/// ```text
+/// minute 0 ✓
+/// hour 9 ✓
+/// day of month 14 ✓ (unrestricted)
+/// month October ✓ (unrestricted)
+/// day of week Saturday ✗ (allowed: Mon–Fri)
+/// day rule: only the day of week is restricted, so it must match
+/// does not match
+/// ```
+impl fmt::Display for Explanation {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for verdict in &self.fields {
+      write!(f, "{} {} {}", verdict.field.name(),
+             value_name(verdict.value, verdict.field),
+             if verdict.matches { "✓" } else { "✗" })?;
+
+      if !verdict.restricted {
+        writeln!(f, " (unrestricted)")?;
+      } else if !verdict.matches {
+        writeln!(f, " (allowed: {})", verdict.allowed)?;
+      } else {
+        writeln!(f)?;
+      }
+    }
+
+    let rule = match self.day_rule {
+      DayRule::Any => "neither day field is restricted, so every day matches",
+      DayRule::DayOfMonth => {
+        "only the day of month is restricted, so it must match"
+      },
+      DayRule::DayOfWeek => "only the day of week is restricted, so it must match",
+      DayRule::Either => {
+        "both day fields are restricted, so either one matching is enough"
+      },
+      DayRule::Both => "both day fields are restricted, and both must match",
+    };
+
+    writeln!(f, "day rule: {}", rule)?;
+    write!(f, "{}", if self.matches { "matches" } else { "does not match" })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crontab::Crontab;
+  use expectest::prelude::*;
+  use parsing::parse_cron;
+  use test_helpers::get_tm;
+
+  fn explain(schedule: &str, time: &Tm) -> Explanation {
+    Explanation::new(&parse_cron(schedule).unwrap(), time)
+  }
+
+  #[test]
+  fn weekday_mismatch() {
+    // 2017-10-14 was a Saturday.
+    let explanation = explain("0 9 * * MON-FRI", &get_tm(2017, 10, 14, 9, 0, 0));
+
+    expect!(explanation.matches).to(be_false());
+    expect!(explanation.day_rule).to(be_equal_to(DayRule::DayOfWeek));
+    expect!(explanation.field(FieldKind::Minute).matches).to(be_true());
+    expect!(explanation.field(FieldKind::Hour).matches).to(be_true());
+    expect!(explanation.field(FieldKind::DayOfMonth).restricted).to(be_false());
+
+    let weekday = explanation.field(FieldKind::DayOfWeek);
+    expect!(weekday.value).to(be_equal_to(6));
+    expect!(weekday.matches).to(be_false());
+    expect!(weekday.allowed.as_str()).to(be_equal_to("Mon–Fri"));
+
+    expect!(explanation.to_string()).to(be_equal_to(
+      "minute 0 ✓\n\
+       hour 9 ✓\n\
+       day of month 14 ✓ (unrestricted)\n\
+       month October ✓ (unrestricted)\n\
+       day of week Saturday ✗ (allowed: Mon–Fri)\n\
+       day rule: only the day of week is restricted, so it must match\n\
+       does not match".to_string()));
+  }
+
+  #[test]
+  fn either_day_rule() {
+    // 2017-10-14 was a Saturday, but it's the 14th.
+    let explanation = explain("0 9 1,14 * MON", &get_tm(2017, 10, 14, 9, 0, 0));
+    expect!(explanation.day_rule).to(be_equal_to(DayRule::Either));
+    expect!(explanation.field(FieldKind::DayOfWeek).matches).to(be_false());
+    expect!(explanation.matches).to(be_true());
+
+    let explanation = explain("0 9 1,15 * MON", &get_tm(2017, 10, 14, 9, 0, 0));
+    expect!(explanation.matches).to(be_false());
+  }
+
+  #[test]
+  fn other_day_rules() {
+    let time = get_tm(2017, 10, 14, 9, 30, 0);
+
+    let explanation = explain("*/15 9-17 * * *", &time);
+    expect!(explanation.day_rule).to(be_equal_to(DayRule::Any));
+    expect!(explanation.matches).to(be_true());
+
+    let explanation = explain("0 9 15 * *", &time);
+    expect!(explanation.day_rule).to(be_equal_to(DayRule::DayOfMonth));
+    expect!(explanation.field(FieldKind::Minute).allowed.as_str()).to(be_equal_to("0"));
+    expect!(explanation.matches).to(be_false());
+  }
+
+  #[test]
+  fn restricted_by_values() {
+    // Unlike in Vixie cron, a field that includes every day is unrestricted
+    // however it's written, and one that leaves some out is restricted even
+    // if it starts with `*`.
+    let time = get_tm(2017, 10, 14, 0, 0, 0);
+    expect!(explain("0 0 1-31 * 1", &time).day_rule).to(be_equal_to(DayRule::DayOfWeek));
+    expect!(explain("0 0 */2 * 1", &time).day_rule).to(be_equal_to(DayRule::Either));
+  }
+
+  #[test]
+  fn allowed_values() {
+    expect!(allowed(&[1, 7], FieldKind::Month)).to(be_equal_to("Jan, Jul".to_string()));
+    expect!(allowed(&[0, 15, 30, 45], FieldKind::Minute)).to(be_equal_to("*/15".to_string()));
+    expect!(allowed(&[9, 10, 11, 12], FieldKind::Hour)).to(be_equal_to("9–12".to_string()));
+  }
+
+  #[test]
+  fn day_specials_and_years() {
+    let crontab = Crontab::parse_dialect("0 9 1,L * ? 2017", Dialect::Aws).unwrap();
+
+    let explanation = crontab.explain(&get_tm(2017, 10, 14, 9, 0, 0));
+    expect!(explanation.matches).to(be_false());
+    expect!(explanation.fields.len()).to(be_equal_to(6));
+    expect!(explanation.field(FieldKind::DayOfMonth).allowed.as_str()).to(be_equal_to("1, L"));
+    expect!(explanation.field(FieldKind::DayOfWeek).restricted).to(be_false());
+    expect!(explanation.to_string().contains("\nyear 2017 ✓\n")).to(be_true());
+
+    expect!(crontab.explain(&get_tm(2017, 10, 31, 9, 0, 0)).matches).to(be_true());
+
+    let explanation = crontab.explain(&get_tm(2018, 1, 1, 9, 0, 0));
+    expect!(explanation.matches).to(be_false());
+    expect!(explanation.field(FieldKind::Year).allowed.as_str()).to(be_equal_to("2017"));
+  }
+}
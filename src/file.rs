@@ -0,0 +1,567 @@
+//! Whole crontab files: entries, environment variables, comments and blank
+//! lines.
+
+use ast::{Span, words};
+use crontab::Crontab;
+use diagnostic::{Diagnostic, Hint, Style};
+use error::CrontabError;
+use std::fmt;
+
+/// A parsed crontab file, as edited with `crontab -e`.
+///
+/// ```
+/// use crontab::CrontabFile;
+///
+/// let file = CrontabFile::parse(
+///   "# Nightly backups\n\
+///    MAILTO=ops@example.com\n\
+///    \n\
+///    30 2 * * * /usr/local/bin/backup --quiet\n\
+///    @reboot /usr/local/bin/warm-cache\n").unwrap();
+///
+/// assert_eq!(file.lines.len(), 5);
+/// assert_eq!(file.entries().len(), 2);
+/// assert_eq!(file.variable("MAILTO"), Some("ops@example.com"));
+///
+/// let entry = file.entries()[0];
+/// assert_eq!(entry.command, "/usr/local/bin/backup --quiet");
+/// assert_eq!(entry.crontab.as_ref().unwrap().source(), "30 2 * * *");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrontabFile {
+  /// Every line of the file, in order.
+  pub lines: Vec<Line>,
+  pub (crate) format: Format,
+}
+
+/// A line of a crontab file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+  /// The line number, counting from 1.
+  pub number: usize,
+  /// The line as written, without its line break.
+  pub text: String,
+  /// The line break as written: `"\n"`, `"\r\n"`, or `""` for a last line
+  /// without one.
+  pub ending: String,
+  /// What the line is.
+  pub kind: LineKind,
+}
+
+/// What a line of a crontab file is.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineKind {
+  /// An empty line, or one of only whitespace.
+  Blank,
+  /// A comment, with the text after the `#`.
+  Comment(String),
+  /// An environment variable assignment, eg. `MAILTO=ops@example.com`.
+  Variable(Variable),
+  /// A schedule and the command it runs.
+  Entry(Box<Entry>),
+}
+
+/// An environment variable assignment.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Variable {
+  /// The name of the variable, without any quotes.
+  pub name: String,
+  /// The value of the variable, without any quotes.
+  pub value: String,
+}
+
+/// Whether entries name a user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub (crate) enum Format {
+  /// A user's crontab, whose entries run as its owner.
+  User,
+  /// A system crontab, whose entries name a user.
+  System,
+}
+
+/// A schedule and the command it runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+  /// When the command runs, or `None` for `@reboot`, which runs it once
+  /// when cron starts.
+  pub crontab: Option<Crontab>,
+  /// Where the schedule is in the line.
+  pub schedule_span: Span,
+  /// The user the command runs as, in a system crontab.
+  pub user: Option<String>,
+  /// The command as written, including any `%`s.
+  pub command: String,
+}
+
+impl Entry {
+  /// The command passed to the shell: the command up to the first `%` that
+  /// isn't escaped as `\%`, with escaped `%`s unescaped.
+  ///
+  /// ```
+  /// let file = crontab::CrontabFile::parse(
+  ///   "0 0 * * * date +\\%F%line one%line two").unwrap();
+  /// let entry = file.entries()[0];
+  ///
+  /// assert_eq!(entry.command_line(), "date +%F");
+  /// assert_eq!(entry.input(), Some("line one\nline two".to_string()));
+  /// ```
+  pub fn command_line(&self) -> String {
+    split_command(&self.command).0
+  }
+
+  /// The standard input of the command: the text after the first unescaped
+  /// `%`, with further unescaped `%`s as line breaks, or `None` if there
+  /// isn't a `%`.
+  pub fn input(&self) -> Option<String> {
+    split_command(&self.command).1
+  }
+}
+
+/// An error on a line of a crontab file.
+#[derive(Debug)]
+pub struct FileError {
+  /// The number of the line, counting from 1.
+  pub line: usize,
+  /// The error, with the line as its source.
+  pub diagnostic: Box<Diagnostic>,
+}
+
+impl FileError {
+  pub (crate) fn new(line: usize, diagnostic: Diagnostic) -> FileError {
+    FileError {
+      line,
+      diagnostic: Box::new(diagnostic),
+    }
+  }
+
+  /// Render the error like `Diagnostic::render`, with the line number.
+  pub fn render(&self, style: Style) -> String {
+    self.diagnostic.render_at(style, Some(self.line))
+  }
+}
+
+/// Renders in plain text.
+impl fmt::Display for FileError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.render(Style::Plain))
+  }
+}
+
+impl CrontabFile {
+  /// Parse a user's crontab file. Lines may end in `\n` or `\r\n`. The
+  /// first line that isn't valid is reported as an error.
+  pub fn parse(text: &str) -> Result<CrontabFile, FileError> {
+    CrontabFile::parse_format(text, Format::User)
+  }
+
+  /// Parse a system crontab file, such as `/etc/crontab` or a file in
+  /// `/etc/cron.d`, whose entries name the user to run the command as
+  /// between the schedule and the command.
+  ///
+  /// ```
+  /// let file = crontab::CrontabFile::parse_system(
+  ///   "17 * * * * root cd / && run-parts --report /etc/cron.hourly").unwrap();
+  /// let entry = file.entries()[0];
+  ///
+  /// assert_eq!(entry.user, Some("root".to_string()));
+  /// assert_eq!(entry.command, "cd / && run-parts --report /etc/cron.hourly");
+  /// ```
+  pub fn parse_system(text: &str) -> Result<CrontabFile, FileError> {
+    CrontabFile::parse_format(text, Format::System)
+  }
+
+  /// Whether cron reads a file of this name in `/etc/cron.d`. As with
+  /// `run-parts`, names may only contain letters, digits, `_` and `-`, so
+  /// backups such as `job~` or `job.dpkg-old` and hidden files are skipped.
+  /// Any directories before the name are ignored.
+  ///
+  /// ```
+  /// use crontab::CrontabFile;
+  ///
+  /// assert!(CrontabFile::is_cron_d_name("/etc/cron.d/php"));
+  /// assert!(CrontabFile::is_cron_d_name("e2scrub_all"));
+  /// assert!(!CrontabFile::is_cron_d_name("php.dpkg-old"));
+  /// assert!(!CrontabFile::is_cron_d_name("backup.cron"));
+  /// ```
+  pub fn is_cron_d_name(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+
+    !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+  }
+
+  fn parse_format(text: &str, format: Format) -> Result<CrontabFile, FileError> {
+    let mut lines = Vec::new();
+
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+      let ending = if line.ends_with("\r\n") {
+        "\r\n"
+      } else if line.ends_with('\n') {
+        "\n"
+      } else {
+        ""
+      };
+
+      let text = &line[.. line.len() - ending.len()];
+      let kind = parse_line(text, format)
+          .map_err(|diagnostic| FileError::new(i + 1, diagnostic))?;
+
+      lines.push(Line {
+        number: i + 1,
+        text: text.to_string(),
+        ending: ending.to_string(),
+        kind,
+      });
+    }
+
+    Ok(CrontabFile { lines, format })
+  }
+
+  /// The entries of the file, in order.
+  pub fn entries(&self) -> Vec<&Entry> {
+    self.lines.iter()
+        .filter_map(|line| match line.kind {
+          LineKind::Entry(ref entry) => Some(&**entry),
+          _ => None,
+        })
+        .collect()
+  }
+
+  /// The environment variable assignments of the file, in order.
+  pub fn variables(&self) -> Vec<&Variable> {
+    self.lines.iter()
+        .filter_map(|line| match line.kind {
+          LineKind::Variable(ref variable) => Some(variable),
+          _ => None,
+        })
+        .collect()
+  }
+
+  /// The value of an environment variable. If it is assigned more than
+  /// once, this is the last value, as the last entries see it.
+  pub fn variable(&self, name: &str) -> Option<&str> {
+    self.variables().into_iter()
+        .rev()
+        .find(|variable| variable.name == name)
+        .map(|variable| variable.value.as_str())
+  }
+}
+
+pub (crate) fn parse_line(line: &str, format: Format) -> Result<LineKind, Diagnostic> {
+  let trimmed = line.trim_start();
+
+  if trimmed.is_empty() {
+    return Ok(LineKind::Blank);
+  }
+
+  if let Some(comment) = trimmed.strip_prefix('#') {
+    return Ok(LineKind::Comment(comment.to_string()));
+  }
+
+  if let Some(variable) = parse_variable(trimmed) {
+    return Ok(LineKind::Variable(variable));
+  }
+
+  parse_entry(line, format).map(|entry| LineKind::Entry(Box::new(entry)))
+}
+
+/// An assignment such as `NAME = value`, where either side may be quoted,
+/// or `None` if the line isn't one. As in cron, the name ends at the first
+/// whitespace or `=`, so `* * * * * A=b` is an entry.
+pub (crate) fn parse_variable(line: &str) -> Option<Variable> {
+  let (name, rest) = match line.chars().next() {
+    Some(quote) if quote == '"' || quote == '\'' => {
+      let end = line[1 ..].find(quote)? + 1;
+      (&line[1 .. end], &line[end + 1 ..])
+    },
+    _ => {
+      let end = line.find(|c: char| c == '=' || c.is_whitespace())?;
+      (&line[.. end], &line[end ..])
+    },
+  };
+
+  let value = rest.trim_start().strip_prefix('=')?.trim();
+
+  if name.is_empty() {
+    return None;
+  }
+
+  Some(Variable {
+    name: name.to_string(),
+    value: unquote(value).to_string(),
+  })
+}
+
+/// A value without matching quotes around it.
+fn unquote(value: &str) -> &str {
+  for quote in &['"', '\''] {
+    if value.len() >= 2 && value.starts_with(*quote) && value.ends_with(*quote) {
+      return &value[1 .. value.len() - 1];
+    }
+  }
+  value
+}
+
+fn parse_entry(line: &str, format: Format) -> Result<Entry, Diagnostic> {
+  let words = words(line);
+  let fields = if words[0].0.starts_with('@') { 1 } else { 5 };
+  let last = words[fields.min(words.len()) - 1].1;
+  let span = Span::new(words[0].1.start, last.end);
+
+  let crontab = if words[0].0 == "@reboot" {
+    None
+  } else {
+    let crontab = Crontab::diagnose(span.text(line))
+        .map_err(|diagnostic| diagnostic.within(line, span.start))?;
+    Some(crontab)
+  };
+
+  let end = Span::new(line.len(), line.len());
+  let mut command_start = span.end;
+
+  let user = match format {
+    Format::User => None,
+    Format::System => {
+      let &(user, user_span) = words.get(fields).ok_or_else(|| {
+        let error = CrontabError::ErrCronFormat(format!("Missing user: {}", line));
+        Diagnostic::new(error, line, end, None).with_hint(Hint::User)
+      })?;
+
+      if !is_user_name(user) {
+        let error = CrontabError::ErrCronFormat(format!("Invalid user name: {}", user));
+        return Err(Diagnostic::new(error, line, user_span, None).with_hint(Hint::UserName));
+      }
+
+      command_start = user_span.end;
+      Some(user.to_string())
+    },
+  };
+
+  let command = line[command_start ..].trim_start();
+
+  if command.is_empty() {
+    let error = CrontabError::ErrCronFormat(format!("Missing command: {}", line));
+    return Err(Diagnostic::new(error, line, end, None).with_hint(Hint::Command));
+  }
+
+  Ok(Entry {
+    crontab,
+    schedule_span: span,
+    user,
+    command: command.to_string(),
+  })
+}
+
+/// Whether a word is a valid user name: up to 32 letters, digits, `_`, `.`
+/// and `-`, not starting with `-`, and optionally ending in `$` as Samba's
+/// machine accounts do.
+fn is_user_name(name: &str) -> bool {
+  let name = name.strip_suffix('$').unwrap_or(name);
+
+  !name.is_empty()
+      && name.len() <= 32
+      && !name.starts_with('-')
+      && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-')
+}
+
+/// Split a command at its first unescaped `%` into the command line and its
+/// standard input, as cron does.
+fn split_command(command: &str) -> (String, Option<String>) {
+  let mut command_line = String::new();
+  let mut input : Option<String> = None;
+  let mut escaped = false;
+
+  for c in command.chars() {
+    let out = match input {
+      Some(ref mut input) => input,
+      None => &mut command_line,
+    };
+
+    if escaped {
+      // Only `%`s are unescaped; other backslashes are kept.
+      if c != '%' {
+        out.push('\\');
+      }
+      out.push(c);
+      escaped = false;
+    } else if c == '\\' {
+      escaped = true;
+    } else if c == '%' {
+      match input {
+        Some(ref mut input) => input.push('\n'),
+        None => input = Some(String::new()),
+      }
+    } else {
+      out.push(c);
+    }
+  }
+
+  if escaped {
+    input.as_mut().unwrap_or(&mut command_line).push('\\');
+  }
+
+  (command_line, input)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+
+  fn kinds(text: &str) -> Vec<LineKind> {
+    CrontabFile::parse(text).unwrap().lines.into_iter().map(|l| l.kind).collect()
+  }
+
+  fn variable(name: &str, value: &str) -> LineKind {
+    LineKind::Variable(Variable { name: name.to_string(), value: value.to_string() })
+  }
+
+  #[test]
+  fn line_kinds() {
+    let file = CrontabFile::parse("# m h dom mon dow command\r\n\
+                                   \n   \t\n\
+                                   SHELL=/bin/bash\n\
+                                   */5 * * * * run-parts /etc/cron.5min\n\
+                                   \x20 @daily  rotate-logs\n").unwrap();
+
+    expect!(file.lines.len()).to(be_equal_to(6));
+    expect!(file.lines[0].kind.clone())
+        .to(be_equal_to(LineKind::Comment(" m h dom mon dow command".to_string())));
+    expect!(file.lines[0].text.as_str()).to(be_equal_to("# m h dom mon dow command"));
+    expect!(file.lines[1].kind.clone()).to(be_equal_to(LineKind::Blank));
+    expect!(file.lines[2].kind.clone()).to(be_equal_to(LineKind::Blank));
+    expect!(file.lines[3].kind.clone()).to(be_equal_to(variable("SHELL", "/bin/bash")));
+    expect!(file.lines[5].number).to(be_equal_to(6));
+
+    let entries = file.entries();
+    expect!(entries[0].command.as_str()).to(be_equal_to("run-parts /etc/cron.5min"));
+    expect!(entries[0].crontab.as_ref().unwrap().schedule().minutes().len()).to(be_equal_to(12));
+    expect!(entries[1].command.as_str()).to(be_equal_to("rotate-logs"));
+    expect!(entries[1].crontab.as_ref().unwrap().source()).to(be_equal_to("@daily"));
+  }
+
+  #[test]
+  fn variables() {
+    expect!(kinds("A = b")).to(be_equal_to(vec![variable("A", "b")]));
+    expect!(kinds("A=")).to(be_equal_to(vec![variable("A", "")]));
+    expect!(kinds("A=b=c")).to(be_equal_to(vec![variable("A", "b=c")]));
+    expect!(kinds("MAILTO=\"\"")).to(be_equal_to(vec![variable("MAILTO", "")]));
+    expect!(kinds("'A B' = ' c '")).to(be_equal_to(vec![variable("A B", " c ")]));
+    expect!(kinds("PATH=/usr/bin:/bin # not a comment"))
+        .to(be_equal_to(vec![variable("PATH", "/usr/bin:/bin # not a comment")]));
+
+    let file = CrontabFile::parse("A=1\nB=2\nA=3\n").unwrap();
+    expect!(file.variables().len()).to(be_equal_to(3));
+    expect!(file.variable("A")).to(be_some().value("3"));
+    expect!(file.variable("C")).to(be_none());
+  }
+
+  #[test]
+  fn entries_with_equals_signs() {
+    let file = CrontabFile::parse("0 * * * * FOO=bar env").unwrap();
+    expect!(file.entries()[0].command.as_str()).to(be_equal_to("FOO=bar env"));
+  }
+
+  #[test]
+  fn reboot() {
+    let file = CrontabFile::parse("@reboot start-agent").unwrap();
+    let entry = file.entries()[0];
+    expect!(entry.crontab.as_ref()).to(be_none());
+    expect!(entry.command.as_str()).to(be_equal_to("start-agent"));
+  }
+
+  #[test]
+  fn percent_signs() {
+    expect!(split_command("echo hi")).to(be_equal_to(("echo hi".to_string(), None)));
+    expect!(split_command("mail -s x root%Dear root,%%Bye%"))
+        .to(be_equal_to(("mail -s x root".to_string(),
+                         Some("Dear root,\n\nBye\n".to_string()))));
+    expect!(split_command("date +\\%Y\\%m%\\%d"))
+        .to(be_equal_to(("date +%Y%m".to_string(), Some("%d".to_string()))));
+    expect!(split_command("echo a\\b\\"))
+        .to(be_equal_to(("echo a\\b\\".to_string(), None)));
+  }
+
+  #[test]
+  fn system_crontabs() {
+    let file = CrontabFile::parse_system("SHELL=/bin/sh\n\
+                                          25 6 * * *\troot\ttest -x /usr/sbin/anacron\n\
+                                          @reboot www-data /srv/start\n").unwrap();
+
+    let entries = file.entries();
+    expect!(entries[0].user.clone()).to(be_some().value("root".to_string()));
+    expect!(entries[0].command.as_str()).to(be_equal_to("test -x /usr/sbin/anacron"));
+    expect!(entries[1].user.clone()).to(be_some().value("www-data".to_string()));
+    expect!(entries[1].crontab.as_ref()).to(be_none());
+
+    // A user's crontab has no user column, so it is part of the command.
+    let file = CrontabFile::parse("0 0 * * * root reboot").unwrap();
+    expect!(file.entries()[0].user.clone()).to(be_none());
+    expect!(file.entries()[0].command.as_str()).to(be_equal_to("root reboot"));
+  }
+
+  #[test]
+  fn user_names() {
+    expect!(is_user_name("root")).to(be_true());
+    expect!(is_user_name("_apt")).to(be_true());
+    expect!(is_user_name("first.last")).to(be_true());
+    expect!(is_user_name("host$")).to(be_true());
+    expect!(is_user_name("-rf")).to(be_false());
+    expect!(is_user_name("a/b")).to(be_false());
+    expect!(is_user_name("$")).to(be_false());
+    expect!(is_user_name(&"a".repeat(33))).to(be_false());
+  }
+
+  #[test]
+  fn system_crontab_errors() {
+    let error = CrontabFile::parse_system("0 0 * * * ro:ot backup").unwrap_err();
+    expect!(error.diagnostic.span.text(&error.diagnostic.source)).to(be_equal_to("ro:ot"));
+    expect!(error.diagnostic.error.to_string())
+        .to(be_equal_to("Invalid user name: ro:ot".to_string()));
+
+    let error = CrontabFile::parse_system("0 0 * * *").unwrap_err();
+    expect!(error.diagnostic.error.to_string())
+        .to(be_equal_to("Missing user: 0 0 * * *".to_string()));
+
+    let error = CrontabFile::parse_system("@daily root").unwrap_err();
+    expect!(error.diagnostic.error.to_string())
+        .to(be_equal_to("Missing command: @daily root".to_string()));
+  }
+
+  #[test]
+  fn cron_d_names() {
+    expect!(CrontabFile::is_cron_d_name("anacron")).to(be_true());
+    expect!(CrontabFile::is_cron_d_name("Sys_Stat-2")).to(be_true());
+    expect!(CrontabFile::is_cron_d_name(".placeholder")).to(be_false());
+    expect!(CrontabFile::is_cron_d_name("job~")).to(be_false());
+    expect!(CrontabFile::is_cron_d_name("job.rpmsave")).to(be_false());
+    expect!(CrontabFile::is_cron_d_name("/etc/cron.d/")).to(be_false());
+  }
+
+  #[test]
+  fn errors() {
+    let error = CrontabFile::parse("# ok\n0 0 * * MNO backup\n").unwrap_err();
+    expect!(error.line).to(be_equal_to(2));
+    expect!(error.diagnostic.source.as_str()).to(be_equal_to("0 0 * * MNO backup"));
+    expect!(error.diagnostic.span.text(&error.diagnostic.source)).to(be_equal_to("MNO"));
+    expect!(error.to_string()).to(be_equal_to(
+      "error: Unknown day of week name: MNO. Did you mean MON?\n  \
+       --> line 2\n  \
+       0 0 * * MNO backup\n  \
+       \x20       ^^^ day of week\n  \
+       = help: did you mean `MON`?".to_string()));
+
+    let error = CrontabFile::parse("\n\n  0 0 * * *\n").unwrap_err();
+    expect!(error.line).to(be_equal_to(3));
+    expect!(error.diagnostic.span.start).to(be_equal_to(11));
+    expect!(error.diagnostic.help()).to(be_some().value(
+      "an entry is a schedule followed by the command to run".to_string()));
+
+    let error = CrontabFile::parse("0 0 * backup").unwrap_err();
+    expect!(error.diagnostic.span.text(&error.diagnostic.source)).to(be_equal_to("0 0 * backup"));
+    expect!(error.diagnostic.help().unwrap().contains("five fields")).to(be_true());
+
+    expect!(CrontabFile::parse("@dialy backup")).to(be_err());
+    expect!(CrontabFile::parse("@reboot")).to(be_err());
+  }
+}
@@ -0,0 +1,402 @@
+//! Rendering of schedule components back into crontab expressions.
+
+use ast::FieldKind;
+use dialect::Dialect;
+use parsing::{DaySpecial, ScheduleComponents};
+use std::collections::HashSet;
+
+/// One comma-separated item of a rendered field.
+#[derive(Clone, Debug, PartialEq)]
+pub (crate) enum FieldItem {
+  /// Every value in the field, eg. `*`.
+  All,
+  /// Every value of the field from its minimum, stepping by the step, eg.
+  /// `*/15`.
+  Step(u32),
+  /// Every value from the start of an inclusive range, stepping by the step,
+  /// eg. `0-30/5`.
+  SteppedRange(u32, u32, u32),
+  /// Every value within an inclusive range, eg. `1-5`.
+  Range(u32, u32),
+  /// A single value, eg. `7`.
+  Value(u32),
+}
+
+impl FieldItem {
+  /// Sort key for items in a list: wildcards first, then by first value.
+  fn sort_key(&self) -> (bool, u32) {
+    match *self {
+      FieldItem::All => (false, 0),
+      FieldItem::Step(step) => (false, step),
+      FieldItem::SteppedRange(start, _, _) => (true, start),
+      FieldItem::Range(start, _) => (true, start),
+      FieldItem::Value(value) => (true, value),
+    }
+  }
+
+  fn render(&self) -> String {
+    match *self {
+      FieldItem::All => "*".to_string(),
+      FieldItem::Step(step) => format!("*/{}", step),
+      FieldItem::SteppedRange(start, end, step) => {
+        format!("{}-{}/{}", start, end, step)
+      },
+      FieldItem::Range(start, end) => format!("{}-{}", start, end),
+      FieldItem::Value(value) => value.to_string(),
+    }
+  }
+}
+
+/// Format schedule components as the shortest equivalent five-field crontab
+/// expression. Parsing the result yields the same components.
+pub (crate) fn format_cron(schedule: &ScheduleComponents) -> String {
+  format_dialect(schedule, Dialect::Vixie)
+}
+
+/// Format schedule components as the shortest equivalent expression in a
+/// dialect, without AWS's `cron(...)` wrapper. Where a dialect needs one of
+/// the day fields to be `?`, it is the one that isn't restricted.
+///
+/// Fields the dialect lacks, such as the year in Vixie cron, are left out,
+/// as is an optional year that is every year, and days picked by their
+/// place in the month are written as `L`, `W` and `#` even in dialects that
+/// don't accept them. Schedules without seconds fire at second 0.
+pub (crate) fn format_dialect(schedule: &ScheduleComponents, dialect: Dialect)
+    -> String {
+  let no_specific_day = if !dialect.requires_no_specific_day() {
+    None
+  } else if !schedule.restricts_days(false) {
+    Some(FieldKind::DayOfWeek)
+  } else if !schedule.restricts_days(true) {
+    Some(FieldKind::DayOfMonth)
+  } else {
+    None
+  };
+
+  let fields : Vec<String> = dialect.fields().iter()
+      .filter(|&&kind| {
+        !(kind == FieldKind::Year && dialect.optional_year() && schedule.years().is_none())
+      })
+      .map(|&kind| {
+        let values = match kind {
+          _ if Some(kind) == no_specific_day => return "?".to_string(),
+          FieldKind::Second => match schedule.seconds() {
+            Some(seconds) => seconds,
+            None => return "0".to_string(),
+          },
+          FieldKind::Minute => schedule.minutes(),
+          FieldKind::Hour => schedule.hours(),
+          FieldKind::DayOfMonth => schedule.days(),
+          FieldKind::Month => schedule.months(),
+          FieldKind::DayOfWeek => schedule.weekdays(),
+          FieldKind::Year => match schedule.years() {
+            Some(years) => years,
+            None => return "*".to_string(),
+          },
+        };
+
+        let mut items : Vec<String> = field_items(values, kind.min(), kind.max()).iter()
+            .map(|item| render_dialect_item(item, kind, dialect))
+            .collect();
+
+        let day_of_month = kind == FieldKind::DayOfMonth;
+        if day_of_month || kind == FieldKind::DayOfWeek {
+          items.extend(schedule.day_specials().iter()
+              .filter(|special| special.is_day_of_month() == day_of_month)
+              .map(|special| format_day_special(*special, dialect)));
+        }
+
+        items.join(",")
+      })
+      .collect();
+
+  fields.join(" ")
+}
+
+/// Render an item of a field in a dialect's numbering and steps.
+fn render_dialect_item(item: &FieldItem, kind: FieldKind, dialect: Dialect)
+    -> String {
+  let value = |value: u32| dialect.written_value(kind, value);
+
+  match *item {
+    FieldItem::All => "*".to_string(),
+    FieldItem::Step(step) => format!("*/{}", step),
+    FieldItem::SteppedRange(start, end, step) => {
+      format!("{}-{}/{}", value(start), value(end), step)
+    },
+    FieldItem::Range(start, end) => format!("{}-{}", value(start), value(end)),
+    FieldItem::Value(v) => value(v).to_string(),
+  }
+}
+
+/// A day picked by its place in the month, as written in a dialect, eg.
+/// `L`, `15W` or `2#1`.
+pub (crate) fn format_day_special(special: DaySpecial, dialect: Dialect) -> String {
+  let weekday = |value: u32| dialect.written_value(FieldKind::DayOfWeek, value);
+
+  match special {
+    DaySpecial::LastDay(0) => "L".to_string(),
+    DaySpecial::LastDay(offset) => format!("L-{}", offset),
+    DaySpecial::LastBusinessDay => "LW".to_string(),
+    DaySpecial::NearestBusinessDay(day) => format!("{}W", day),
+    DaySpecial::Last(day) => format!("{}L", weekday(day)),
+    DaySpecial::Nth(day, n) => format!("{}#{}", weekday(day), n),
+  }
+}
+
+/// Format the values of a single field. The values must be unique, ordered
+/// and within the range of the field.
+pub (crate) fn format_field(values: &[u32], field_min: u32, field_max: u32)
+    -> String {
+  render_items(&field_items(values, field_min, field_max))
+}
+
+/// Break the values of a single field down into the shortest list of items.
+/// Steps follow the parser's semantics: `a-b/n` selects every `n`th value
+/// from `a` up to `b`, and `*/n` every `n`th value from the field's
+/// minimum, so `*/10` in the day of month is 1, 11, 21 and 31.
+pub (crate) fn field_items(values: &[u32], field_min: u32, field_max: u32)
+    -> Vec<FieldItem> {
+  if values.len() as u32 == field_max - field_min + 1 {
+    return vec![FieldItem::All];
+  }
+
+  // Consecutive runs, eg. "1-5,7".
+  let mut best = run_items(values);
+  let mut best_len = render_items(&best).len();
+
+  // Steps, optionally with the leftover values as runs, eg. "*/15,7". Only
+  // used when strictly shorter, so "0,15,30" isn't rewritten as "0-30/15".
+  // A step wider than the field picks one value, and a step without `*/n`
+  // or a chain of three values leaves every value to the runs.
+  for step in 2 .. field_max - field_min + 1 {
+    if !steps_from_min(values, field_min, field_max, step) && !chains(values, step) {
+      continue;
+    }
+
+    let items = step_items(values, field_min, field_max, step);
+    let len = render_items(&items).len();
+
+    if len < best_len {
+      best = items;
+      best_len = len;
+    }
+  }
+
+  best
+}
+
+fn render_items(items: &[FieldItem]) -> String {
+  items.iter()
+      .map(|item| item.render())
+      .collect::<Vec<String>>()
+      .join(",")
+}
+
+/// Collapse runs of three or more consecutive values into ranges.
+fn run_items(values: &[u32]) -> Vec<FieldItem> {
+  let mut items = Vec::new();
+  let mut i = 0;
+
+  while i < values.len() {
+    let mut j = i;
+    while j + 1 < values.len() && values[j + 1] == values[j] + 1 {
+      j += 1;
+    }
+
+    if j - i >= 2 {
+      items.push(FieldItem::Range(values[i], values[j]));
+    } else {
+      for value in &values[i .. j + 1] {
+        items.push(FieldItem::Value(*value));
+      }
+    }
+
+    i = j + 1;
+  }
+
+  items
+}
+
+/// Whether the values include every `step`th value from the field's minimum,
+/// as `*/step` selects.
+fn steps_from_min(values: &[u32], field_min: u32, field_max: u32, step: u32) -> bool {
+  (field_min .. field_max + 1).step_by(step as usize)
+      .all(|value| values.binary_search(&value).is_ok())
+}
+
+/// Whether any three of the values are `step` apart, as in `a-b/step`.
+fn chains(values: &[u32], step: u32) -> bool {
+  let contains = |value: u32| values.binary_search(&value).is_ok();
+  values.iter().any(|&value| contains(value + step) && contains(value + 2 * step))
+}
+
+/// Cover chains of three or more values `step` apart with stepped items, and
+/// the remaining values with runs.
+fn step_items(values: &[u32], field_min: u32, field_max: u32, step: u32)
+    -> Vec<FieldItem> {
+  let steps_from = |start: u32| (start .. field_max + 1).step_by(step as usize);
+
+  let contains = |value: &u32| values.binary_search(value).is_ok();
+  let mut items = Vec::new();
+  let mut covered = HashSet::new();
+
+  if steps_from_min(values, field_min, field_max, step) {
+    items.push(FieldItem::Step(step));
+    covered.extend(steps_from(field_min));
+  }
+
+  // The chains starting within the first step of the field, and after
+  // each gap.
+  for start in field_min .. (field_min + step).min(field_max + 1) {
+    let mut chain : Vec<u32> = Vec::new();
+
+    for value in steps_from(start) {
+      if contains(&value) && !covered.contains(&value) {
+        chain.push(value);
+        continue;
+      }
+      push_chain(&mut items, &mut covered, &chain, step);
+      chain.clear();
+    }
+
+    push_chain(&mut items, &mut covered, &chain, step);
+  }
+
+  let rest : Vec<u32> = values.iter()
+      .filter(|value| !covered.contains(value))
+      .cloned()
+      .collect();
+
+  items.extend(run_items(&rest));
+  items.sort_by_key(|item| item.sort_key());
+  items
+}
+
+fn push_chain(items: &mut Vec<FieldItem>,
+              covered: &mut HashSet<u32>,
+              chain: &[u32],
+              step: u32) {
+  if chain.len() < 3 {
+    return;
+  }
+
+  items.push(FieldItem::SteppedRange(chain[0], chain[chain.len() - 1], step));
+  covered.extend(chain);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+  use parsing::parse_cron;
+
+  fn canonical(schedule: &str) -> String {
+    format_cron(&parse_cron(schedule).unwrap())
+  }
+
+  #[test]
+  fn wildcards() {
+    expect!(canonical("* * * * *")).to(be_equal_to("* * * * *".to_string()));
+    expect!(canonical("0-59 0-23 1-31 1-12 0-6"))
+        .to(be_equal_to("* * * * *".to_string()));
+  }
+
+  #[test]
+  fn steps() {
+    expect!(canonical("0,15,30,45 * * * *"))
+        .to(be_equal_to("*/15 * * * *".to_string()));
+    expect!(canonical("* 0,4,8,12,16,20 10,20,30 3,6,9,12 0,2,4,6"))
+        .to(be_equal_to("* */4 10,20,30 3-12/3 */2".to_string()));
+    expect!(canonical("0-30/5 * * * *"))
+        .to(be_equal_to("0-30/5 * * * *".to_string()));
+  }
+
+  #[test]
+  fn day_and_month_steps_round_trip() {
+    for schedule in &["0 0 */10 */3 *", "0 0 10,20,30 3,6,9,12 *", "0 0 1,11,21,31 1,4,7,10 *",
+                      "0 0 1-20/5 2-12/2 *", "0 0 4-28/4,31 */4,12 *"] {
+      let parsed = parse_cron(schedule).unwrap();
+      let formatted = format_cron(&parsed);
+      expect!(parse_cron(&formatted).unwrap()).to(be_equal_to(parsed));
+    }
+
+    // Steps count from the start of the field or range.
+    expect!(canonical("0 0 1,11,21,31 1,4,7,10 *"))
+        .to(be_equal_to("0 0 */10 */3 *".to_string()));
+    expect!(canonical("0 0 10,20,30 3,6,9,12 *"))
+        .to(be_equal_to("0 0 10,20,30 3-12/3 *".to_string()));
+    expect!(canonical("0 0 1-20/5 * *"))
+        .to(be_equal_to("0 0 1-16/5 * *".to_string()));
+  }
+
+  #[test]
+  fn ranges() {
+    expect!(canonical("1,2,3,4,5 * * * 1,2,3,4,5"))
+        .to(be_equal_to("1-5 * * * 1-5".to_string()));
+    expect!(canonical("1,2,3,5,7,8,9 * * * *"))
+        .to(be_equal_to("1-3,5,7-9 * * * *".to_string()));
+    expect!(canonical("1,2 * * * *"))
+        .to(be_equal_to("1,2 * * * *".to_string()));
+  }
+
+  #[test]
+  fn steps_with_extra_values() {
+    expect!(canonical("0,7,15,30,45 * * * *"))
+        .to(be_equal_to("*/15,7 * * * *".to_string()));
+    expect!(canonical("* * * 1-4,*/2 *"))
+        .to(be_equal_to("* * * */2,2,4 *".to_string()));
+  }
+
+  #[test]
+  fn year_steps() {
+    let (min, max) = (FieldKind::Year.min(), FieldKind::Year.max());
+    expect!(format_field(&[2020, 2025, 2030, 2100], min, max))
+        .to(be_equal_to("2020-2030/5,2100".to_string()));
+
+    let years : Vec<u32> = (min .. max + 1).filter(|year| year % 7 != 0).collect();
+    let expression = format_field(&years, min, max);
+    expect!(expression.split(',').count()).to(be_less_than(years.len()));
+  }
+
+  #[test]
+  fn single_values() {
+    expect!(canonical("0 0 1 1 *")).to(be_equal_to("0 0 1 1 *".to_string()));
+    expect!(canonical("59 23 31 12 6"))
+        .to(be_equal_to("59 23 31 12 6".to_string()));
+  }
+
+  #[test]
+  fn round_trip() {
+    // Pseudo-random subsets of each field, from a simple LCG.
+    let mut seed : u64 = 42;
+    let mut next = || {
+      seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+      seed >> 33
+    };
+
+    for _ in 0 .. 500 {
+      let density = next() % 8 + 1;
+      let mut subset = |min: u32, max: u32| {
+        let mut values : Vec<u32> = (min .. max + 1)
+            .filter(|_| next() % density == 0)
+            .collect();
+        if values.is_empty() {
+          values.push(min);
+        }
+        values
+      };
+
+      let schedule = ScheduleComponents::new(
+        subset(0, 59),
+        subset(0, 23),
+        subset(1, 31),
+        subset(1, 12),
+        subset(0, 6)).unwrap();
+
+      let expression = format_cron(&schedule);
+      let parsed = parse_cron(&expression).unwrap();
+      expect!(parsed).to(be_equal_to(schedule));
+    }
+  }
+}
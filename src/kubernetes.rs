@@ -0,0 +1,428 @@
+//! Kubernetes CronJob manifests, for checking schedules without a cluster.
+
+use ast::Span;
+use crontab::Crontab;
+use diagnostic::{Diagnostic, Hint, Style};
+use dialect::Dialect;
+use error::CrontabError;
+use file::FileError;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A CronJob from a manifest, with its schedule parsed in the Kubernetes
+/// dialect.
+///
+/// ```
+/// use crontab::CronJob;
+///
+/// let jobs = CronJob::parse_manifest("\
+/// apiVersion: batch/v1
+/// kind: CronJob
+/// metadata:
+///   name: backup
+/// spec:
+///   schedule: \"30 2 * * ?\"
+///   timeZone: Europe/London
+///   jobTemplate: {}
+/// ").unwrap();
+///
+/// assert_eq!(jobs[0].name, "backup");
+/// assert_eq!(jobs[0].crontab.schedule().hours(), &[2]);
+/// assert_eq!(jobs[0].time_zone, Some("Europe/London".to_string()));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronJob {
+  /// The CronJob's `metadata.name`.
+  pub name: String,
+  /// The CronJob's `metadata.namespace`, if the manifest sets it.
+  pub namespace: Option<String>,
+  /// The CronJob's `spec.schedule`.
+  pub crontab: Crontab,
+  /// The CronJob's `spec.timeZone`, eg. "Europe/London", or `None` for the
+  /// zone of the controller manager.
+  pub time_zone: Option<String>,
+  /// The number of the line the schedule is on, counting from 1.
+  pub line: usize,
+}
+
+/// An error reading CronJobs from a manifest.
+#[derive(Debug)]
+pub enum ManifestError {
+  /// The manifest couldn't be read.
+  Io(io::Error),
+  /// A CronJob's schedule or time zone isn't valid.
+  Invalid(FileError),
+}
+
+/// A value in a manifest, with the line it's on.
+struct Value<'a> {
+  /// The value without quotes.
+  text: String,
+  /// The line the value is on.
+  line: &'a str,
+  /// The number of the line, counting from 1.
+  number: usize,
+  /// Where the value is on the line, including any quotes.
+  span: Span,
+}
+
+/// The values of a manifest document that CronJobs are read from.
+#[derive(Default)]
+struct Document<'a> {
+  kind: Option<Value<'a>>,
+  name: Option<Value<'a>>,
+  namespace: Option<Value<'a>>,
+  schedule: Option<Value<'a>>,
+  time_zone: Option<Value<'a>>,
+}
+
+impl CronJob {
+  /// Read the CronJobs of a manifest from a file. See `parse_manifest`.
+  pub fn read_manifest<P: AsRef<Path>>(path: P) -> Result<Vec<CronJob>, ManifestError> {
+    let manifest = fs::read_to_string(path).map_err(ManifestError::Io)?;
+    CronJob::parse_manifest(&manifest)
+  }
+
+  /// The CronJobs of a YAML manifest, which may hold several documents
+  /// separated by `---`, skipping other kinds of object. The first CronJob
+  /// that Kubernetes would reject is reported as an error: one without a
+  /// schedule, a schedule that isn't valid in `Dialect::Kubernetes`, or a
+  /// time zone that isn't a zone name. Zone names aren't looked up, so one
+  /// that's well formed but unknown is accepted.
+  ///
+  /// This reads the block style manifests are usually written in, not all of
+  /// YAML: `metadata` and `spec` must be nested mappings, and a schedule
+  /// must be on one line.
+  pub fn parse_manifest(manifest: &str) -> Result<Vec<CronJob>, ManifestError> {
+    documents(manifest).into_iter()
+        .filter(|document| {
+          document.kind.as_ref().is_some_and(|kind| kind.text == "CronJob")
+        })
+        .map(|document| cron_job(document).map_err(ManifestError::Invalid))
+        .collect()
+  }
+}
+
+fn cron_job(document: Document) -> Result<CronJob, FileError> {
+  let schedule = match document.schedule {
+    Some(schedule) => schedule,
+    None => {
+      let kind = document.kind.expect("documents of CronJobs have a kind");
+      let error = CrontabError::ErrCronFormat("A CronJob needs a schedule".to_string());
+      let span = Span::new(0, kind.line.len());
+      return Err(FileError::new(kind.number,
+                                Diagnostic::new(error, kind.line, span, None)
+                                    .with_hint(Hint::CronJob)));
+    },
+  };
+
+  let crontab = Crontab::diagnose_dialect(&schedule.text, Dialect::Kubernetes)
+      .map_err(|diagnostic| {
+        // Point into the line when the schedule is written as it's read.
+        let start = match schedule.line[schedule.span.start ..].chars().next() {
+          Some('"') | Some('\'') => schedule.span.start + 1,
+          _ => schedule.span.start,
+        };
+        let diagnostic = if schedule.line[start ..].starts_with(&schedule.text) {
+          diagnostic.within(schedule.line, start)
+        } else {
+          diagnostic
+        };
+        FileError::new(schedule.number, diagnostic)
+      })?;
+
+  if let Some(ref zone) = document.time_zone {
+    check_time_zone(zone)?;
+  }
+
+  Ok(CronJob {
+    name: document.name.map(|name| name.text).unwrap_or_default(),
+    namespace: document.namespace.map(|namespace| namespace.text),
+    crontab,
+    time_zone: document.time_zone.map(|zone| zone.text),
+    line: schedule.number,
+  })
+}
+
+/// Check a time zone the way Kubernetes does before looking it up: it names
+/// a zone, rather than being empty or `Local`, and each part of the name is
+/// 1 to 14 letters, digits, `.`, `_`, `-` or `+`.
+fn check_time_zone(zone: &Value) -> Result<(), FileError> {
+  let valid_part = |part: &str| {
+    (1 ..= 14).contains(&part.len())
+        && part.chars().all(|c| c.is_ascii_alphanumeric() || "._-+".contains(c))
+  };
+
+  let message = if zone.text.is_empty() {
+    "A time zone can't be empty".to_string()
+  } else if zone.text.eq_ignore_ascii_case("Local") {
+    format!("A time zone must name a zone rather than be {}", zone.text)
+  } else if !zone.text.split('/').all(valid_part) {
+    format!("Invalid time zone: {}", zone.text)
+  } else {
+    return Ok(());
+  };
+
+  let error = CrontabError::ErrCronFormat(message);
+  Err(FileError::new(zone.number,
+                     Diagnostic::new(error, zone.line, zone.span, None)
+                         .with_hint(Hint::CronJob)))
+}
+
+/// The documents of a manifest, with the values CronJobs are read from.
+fn documents(manifest: &str) -> Vec<Document<'_>> {
+  let mut documents = vec![Document::default()];
+  // The keys of the mappings the current line is in, with their indents.
+  let mut path : Vec<(usize, &str)> = Vec::new();
+
+  for (i, line) in manifest.lines().enumerate() {
+    if line.starts_with("---") || line.trim_end() == "..." {
+      documents.push(Document::default());
+      path.clear();
+      continue;
+    }
+
+    let content = line.trim_start();
+    if content.is_empty() || content.starts_with('#') {
+      continue;
+    }
+
+    // The first key of a list item is indented past its `-`.
+    let mut indent = line.len() - content.len();
+    let mut content = content;
+    while let Some(rest) = content.strip_prefix('-') {
+      if !(rest.is_empty() || rest.starts_with(' ')) {
+        break;
+      }
+      let trimmed = rest.trim_start();
+      indent += content.len() - trimmed.len();
+      content = trimmed;
+    }
+
+    while path.last().is_some_and(|&(key_indent, _)| key_indent >= indent) {
+      path.pop();
+    }
+
+    let colon = match key_end(content) {
+      Some(colon) => colon,
+      None => continue,
+    };
+    let key = unquote(&content[.. colon]);
+    let rest = &content[colon + 1 ..];
+    let start = line.len() - rest.trim_start().len();
+    let value = match value(line, start, i + 1) {
+      Some(value) => value,
+      None => {
+        path.push((indent, key));
+        continue;
+      },
+    };
+
+    let keys : Vec<&str> = path.iter().map(|&(_, key)| key).collect();
+    let document = documents.last_mut().expect("there's always a document");
+    match (keys.as_slice(), key) {
+      ([], "kind") => document.kind = Some(value),
+      (["metadata"], "name") => document.name = Some(value),
+      (["metadata"], "namespace") => document.namespace = Some(value),
+      (["spec"], "schedule") => document.schedule = Some(value),
+      (["spec"], "timeZone") => document.time_zone = Some(value),
+      _ => {},
+    }
+  }
+
+  documents
+}
+
+/// Where the `:` after a mapping key is, if the line has one.
+fn key_end(content: &str) -> Option<usize> {
+  if content.starts_with('"') || content.starts_with('\'') {
+    let quote = &content[.. 1];
+    let close = content[1 ..].find(quote)? + 1;
+    return content[close + 1 ..].starts_with(':').then_some(close + 1);
+  }
+
+  content.match_indices(':')
+      .map(|(i, _)| i)
+      .find(|&i| content[i + 1 ..].is_empty() || content[i + 1 ..].starts_with(' '))
+}
+
+/// A key without quotes.
+fn unquote(key: &str) -> &str {
+  key.trim_end().trim_matches(|c| c == '"' || c == '\'')
+}
+
+/// The scalar value starting at `start` on a line, or `None` if there is
+/// none, as when a nested mapping or a block scalar follows.
+fn value(line: &str, start: usize, number: usize) -> Option<Value<'_>> {
+  let written = &line[start ..];
+  let (text, length) = match written.chars().next()? {
+    '"' => {
+      let mut text = String::new();
+      let mut chars = written.char_indices().skip(1);
+      loop {
+        match chars.next()? {
+          (i, '"') => break (text, i + 1),
+          (_, '\\') => text.push(chars.next()?.1),
+          (_, c) => text.push(c),
+        }
+      }
+    },
+    '\'' => {
+      let mut text = String::new();
+      let mut chars = written.char_indices().skip(1).peekable();
+      loop {
+        match chars.next()? {
+          (_, '\'') if chars.peek().is_some_and(|&(_, c)| c == '\'') => {
+            chars.next();
+            text.push('\'');
+          },
+          (i, '\'') => break (text, i + 1),
+          (_, c) => text.push(c),
+        }
+      }
+    },
+    '#' | '|' | '>' => return None,
+    _ => {
+      let end = written.find(" #").unwrap_or(written.len());
+      let text = written[.. end].trim_end();
+      (text.to_string(), text.len())
+    },
+  };
+
+  Some(Value {
+    text,
+    line,
+    number,
+    span: Span::new(start, start + length),
+  })
+}
+
+impl ManifestError {
+  /// Render the error, like `FileError::render` for an invalid CronJob.
+  pub fn render(&self, style: Style) -> String {
+    match *self {
+      ManifestError::Io(ref error) => format!("error: {}", error),
+      ManifestError::Invalid(ref error) => error.render(style),
+    }
+  }
+}
+
+/// Renders in plain text.
+impl fmt::Display for ManifestError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.render(Style::Plain))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+
+  const MANIFEST : &str = "\
+# Jobs for the reports service.
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: settings
+data:
+  schedule: \"not a schedule\"
+---
+apiVersion: batch/v1
+kind: CronJob
+metadata:
+  name: nightly-report
+  namespace: reports
+  labels:
+    name: ignored
+spec:
+  schedule: '0 3 ? * MON-FRI'  # weekdays
+  timeZone: \"America/New_York\"
+  jobTemplate:
+    spec:
+      template:
+        spec:
+          containers:
+            - name: report
+              image: reports:1.4
+          restartPolicy: OnFailure
+---
+kind: CronJob
+metadata:
+  name: cleanup
+spec:
+  schedule: @hourly
+";
+
+  #[test]
+  fn manifests() {
+    let jobs = CronJob::parse_manifest(MANIFEST).unwrap();
+    expect!(jobs.len()).to(be_equal_to(2));
+
+    expect!(jobs[0].name.as_str()).to(be_equal_to("nightly-report"));
+    expect!(jobs[0].namespace.clone()).to(be_some().value("reports".to_string()));
+    expect!(jobs[0].time_zone.clone()).to(be_some().value("America/New_York".to_string()));
+    expect!(jobs[0].crontab.to_string()).to(be_equal_to("0 3 * * 1-5".to_string()));
+    expect!(jobs[0].line).to(be_equal_to(17));
+
+    expect!(jobs[1].name.as_str()).to(be_equal_to("cleanup"));
+    expect!(jobs[1].namespace.clone()).to(be_none());
+    expect!(jobs[1].time_zone.clone()).to(be_none());
+    expect!(jobs[1].crontab.schedule().minutes()).to(be_equal_to(&[0][..]));
+  }
+
+  #[test]
+  fn invalid_cron_jobs() {
+    let error = |manifest: &str| match CronJob::parse_manifest(manifest) {
+      Err(ManifestError::Invalid(error)) => error,
+      other => panic!("expected an invalid CronJob, got {:?}", other),
+    };
+
+    let invalid = error("kind: CronJob\nspec:\n  schedule: \"0 25 * * *\"\n");
+    expect!(invalid.line).to(be_equal_to(3));
+    expect!(invalid.render(Style::Plain)).to(be_equal_to(
+      "error: Value 25 is greater than maximum: 23\n  \
+       --> line 3\n  \
+       \x20 schedule: \"0 25 * * *\"\n  \
+       \x20              ^^ hour\n  \
+       = help: the hour field accepts 0-23".to_string()));
+
+    expect!(error("kind: CronJob\nspec:\n  timeZone: UTC\n").line).to(be_equal_to(1));
+    expect!(error("kind: CronJob\nspec:\n  schedule: 0 0 * * * *\n").line).to(be_equal_to(3));
+    expect!(error("kind: CronJob\nspec:\n  schedule: CRON_TZ=UTC 0 0 * * *\n").line)
+        .to(be_equal_to(3));
+
+    let zone = |zone: &str| {
+      CronJob::parse_manifest(&format!(
+        "kind: CronJob\nspec:\n  schedule: \"@daily\"\n  timeZone: \"{}\"\n", zone))
+    };
+    expect!(zone("Etc/GMT+5")).to(be_ok());
+    expect!(zone("")).to(be_err());
+    expect!(zone("local")).to(be_err());
+    expect!(zone("Europe/London ")).to(be_err());
+    expect!(zone("America/Argentina_Buenos_Aires")).to(be_err());
+  }
+
+  #[test]
+  fn kubernetes_dialect() {
+    let parse = |schedule: &str| Crontab::parse_dialect(schedule, Dialect::Kubernetes);
+
+    // `?` is `*` in every field, and steps count from their start.
+    let crontab = parse("?/20 ? 5/10 ? ?,MON").unwrap();
+    expect!(crontab.schedule().minutes()).to(be_equal_to(&[0, 20, 40][..]));
+    expect!(crontab.schedule().days()).to(be_equal_to(&[5, 15, 25][..]));
+    expect!(crontab.schedule().weekdays()).to(be_equal_to(&[0, 1, 2, 3, 4, 5, 6][..]));
+    expect!(crontab.to_string()).to(be_equal_to("*/20 * 5,15,25 * *".to_string()));
+
+    expect!(parse("@weekly")).to(be_ok());
+    expect!(parse("0 0 L * *")).to(be_err());
+    expect!(parse("0 0 * * * 2030")).to(be_err());
+
+    let diagnostic = Crontab::diagnose_dialect("TZ=UTC 0 0 * * *", Dialect::Kubernetes)
+        .unwrap_err();
+    expect!(diagnostic.help()).to(be_some().value(
+      "set the CronJob's `timeZone` field instead".to_string()));
+  }
+}
@@ -9,6 +9,12 @@
 
 extern crate time;
 
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
 #[cfg(test)]
 #[macro_use(expect)]
 extern crate expectest;
@@ -17,14 +23,54 @@ extern crate expectest;
 #[cfg(test)]
 mod test_helpers;
 
+mod anacron;
+mod ast;
+mod aws;
+mod builder;
+mod calendar;
 mod crontab;
+mod description;
+mod detect;
+mod diagnostic;
+mod dialect;
+mod edit;
 mod error;
+mod explain;
+mod file;
+mod kubernetes;
+mod formatting;
+mod lint;
+mod locales;
 mod parsing;
+#[cfg(feature = "serde")]
+mod serialization;
+mod suggest;
+mod systemd;
 mod times;
+mod transpile;
+mod units;
 
 // Exports
+pub use anacron::{AnacronJob, AnacronPeriod, Anacrontab, Due};
+pub use ast::{Atom, AtomKind, Expression, ExpressionKind, Field, FieldKind};
+pub use ast::{Item, ItemKind, Macro, Span, Special, Zone, ZoneVariable};
+pub use aws::{AwsSchedule, Rate, RateUnit};
+pub use builder::{CrontabBuilder, FieldValues};
+pub use calendar::{Month, Months, Weekday, Weekdays};
 pub use crontab::Crontab;
-pub use parsing::ScheduleComponents;
+pub use description::{Clock, Description, Locale, Segment};
+pub use detect::{Candidate, Detection};
+pub use diagnostic::{Diagnostic, Style};
+pub use dialect::Dialect;
+pub use explain::{DayRule, Explanation, FieldVerdict};
+pub use file::{CrontabFile, Entry, FileError, Line, LineKind, Variable};
+pub use kubernetes::{CronJob, ManifestError};
+pub use lint::{Lint, LintCode};
+pub use locales::{English, French, German, Japanese, Spanish};
+pub use parsing::{DaySpecial, ScheduleComponents};
+pub use systemd::CalendarEvent;
+pub use transpile::Transpiled;
+pub use units::SystemdUnits;
 
 // Re-exports.
 pub use time::Tm;
@@ -0,0 +1,317 @@
+//! Warnings for schedules that are valid, but probably not what was meant.
+
+use ast::{Field, FieldKind, ItemKind, Span, Special};
+use crontab::{Crontab, day_rule};
+use dialect::Dialect;
+use explain::DayRule;
+use formatting::format_field;
+use parsing::item_bounds;
+use std::fmt;
+
+/// The kinds of lint warning.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LintCode {
+  /// Every minute of a restricted hour, eg. `* 2 * * *` runs 60 times from
+  /// 2:00 to 2:59, where `0 2 * * *` was probably meant.
+  EveryMinuteInHour,
+  /// A wildcard step that doesn't divide the field evenly, eg. `*/45` fires
+  /// at :00 and :45, so the gap after :45 is only 15 minutes.
+  UnevenStep,
+  /// No date matches the day of month and month, eg. `0 0 31 4 *`.
+  NeverFires,
+  /// Both day of month and day of week are restricted, so the job runs when
+  /// either matches rather than both, eg. `0 0 13 * 5`.
+  DayOrWeekday,
+  /// A step larger than the range it steps through selects at most one
+  /// value, eg. `0-10/15`.
+  StepExceedsRange,
+}
+
+impl LintCode {
+  /// The name of the lint, eg. "uneven-step".
+  pub fn name(self) -> &'static str {
+    match self {
+      LintCode::EveryMinuteInHour => "every-minute-in-hour",
+      LintCode::UnevenStep => "uneven-step",
+      LintCode::NeverFires => "never-fires",
+      LintCode::DayOrWeekday => "day-or-weekday",
+      LintCode::StepExceedsRange => "step-exceeds-range",
+    }
+  }
+}
+
+impl fmt::Display for LintCode {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+/// A warning about a valid schedule. See `Crontab::lint`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Lint {
+  /// What kind of warning this is.
+  pub code: LintCode,
+  /// A description of the problem.
+  pub message: String,
+  /// The part of the crontab's source the warning is about.
+  pub span: Span,
+}
+
+/// Prints the code and message, eg. "uneven-step: ...".
+impl fmt::Display for Lint {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}: {}", self.code, self.message)
+  }
+}
+
+/// The most days each month can have, including leap days.
+const MONTH_DAYS : [u32; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// Check a crontab for suspicious but valid expressions. Macros are never
+/// suspicious.
+pub (crate) fn lint(crontab: &Crontab) -> Vec<Lint> {
+  let fields = match crontab.expression().fields() {
+    Some(fields) => fields,
+    None => return Vec::new(),
+  };
+
+  let span = |kind: FieldKind| {
+    fields.iter()
+        .find(|field| field.kind == kind)
+        .expect("every dialect has the five cron fields")
+        .span
+  };
+  let schedule = crontab.schedule();
+  let mut lints = Vec::new();
+
+  if schedule.minutes().len() == 60 && schedule.hours().len() < 24 {
+    lints.push(Lint {
+      code: LintCode::EveryMinuteInHour,
+      message: format!(
+        "runs every minute of hour {}; use a single minute such as `0` to \
+         run once an hour", format_field(schedule.hours(), 0, 23)),
+      span: span(FieldKind::Minute),
+    });
+  }
+
+  for field in fields {
+    lint_steps(field, crontab.dialect(), &mut lints);
+  }
+
+  let day_fields = Span::new(span(FieldKind::DayOfMonth).start, span(FieldKind::DayOfWeek).end);
+
+  match day_rule(schedule) {
+    DayRule::DayOfMonth => {
+      // Days picked by their place in the month, such as `L`, are in every
+      // month. Otherwise there is a day of month.
+      let fires = schedule.day_specials().iter().any(|special| special.is_day_of_month())
+          || schedule.days().first().is_some_and(|&first| {
+            schedule.months().iter().any(|month| first <= MONTH_DAYS[*month as usize - 1])
+          });
+
+      if !fires {
+        lints.push(Lint {
+          code: LintCode::NeverFires,
+          message: format!("no month {} has day {}, so this never runs",
+                           format_field(schedule.months(), 1, 12),
+                           format_field(schedule.days(), 1, 31)),
+          span: Span::new(span(FieldKind::DayOfMonth).start, span(FieldKind::Month).end),
+        });
+      }
+    },
+    DayRule::Either => {
+      lints.push(Lint {
+        code: LintCode::DayOrWeekday,
+        message: "day of month and day of week are both restricted, so this \
+                  runs on days matching either of them, not both".to_string(),
+        span: day_fields,
+      });
+    },
+    DayRule::Any | DayRule::DayOfWeek | DayRule::Both => {},
+  }
+
+  lints.sort_by_key(|lint| lint.span.start);
+  lints
+}
+
+fn lint_steps(field: &Field, dialect: Dialect, lints: &mut Vec<Lint>) {
+  let kind = field.kind;
+  let (field_min, field_max) = dialect.range(kind);
+  let size = field_max - field_min + 1;
+
+  for item in &field.items {
+    let base = match item.kind {
+      ItemKind::Step(ref base, _) => base,
+      _ => continue,
+    };
+
+    let (min, max, step_value) = match item_bounds(item, kind, dialect) {
+      Ok(bounds) => bounds,
+      Err(_) => continue,
+    };
+
+    // Ranges of months and days of the week may wrap, eg. `NOV-FEB/2`.
+    let width = if min <= max { max - min } else { size - (min - max) };
+    if step_value > width {
+      lints.push(Lint {
+        code: LintCode::StepExceedsRange,
+        message: format!(
+          "step {} is larger than the range {}-{}, so it selects at most one \
+           {}", step_value, min, max, kind.name()),
+        span: item.span,
+      });
+      continue;
+    }
+
+    // The days in a month vary, so no step divides them evenly, and years
+    // don't restart.
+    let wildcard = base.kind == ItemKind::Special(Special::Any)
+        || base.kind == ItemKind::Special(Special::NoSpecificValue);
+    let parent = match parent(kind) {
+      Some(parent) if wildcard && size % step_value != 0 => parent,
+      _ => continue,
+    };
+
+    let last = min + (max - min) / step_value * step_value;
+    lints.push(Lint {
+      code: LintCode::UnevenStep,
+      message: format!(
+        "`{}` fires every {} from {} and restarts each {}, so the gap after \
+         {} is shorter; it doesn't run every {} {}s",
+        item, step_value, min, parent, last, step_value, kind.name()),
+      span: item.span,
+    });
+  }
+}
+
+/// The unit each field's values repeat within, if they repeat evenly.
+fn parent(kind: FieldKind) -> Option<&'static str> {
+  match kind {
+    FieldKind::Second => Some("minute"),
+    FieldKind::Minute => Some("hour"),
+    FieldKind::Hour => Some("day"),
+    FieldKind::Month => Some("year"),
+    FieldKind::DayOfWeek => Some("week"),
+    FieldKind::DayOfMonth | FieldKind::Year => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+
+  fn codes(schedule: &str) -> Vec<LintCode> {
+    lint(&Crontab::parse(schedule).unwrap()).iter().map(|l| l.code).collect()
+  }
+
+  fn spans(schedule: &str) -> Vec<&str> {
+    lint(&Crontab::parse(schedule).unwrap()).iter()
+        .map(|l| l.span.text(schedule))
+        .collect::<Vec<&str>>()
+  }
+
+  #[test]
+  fn clean_schedules() {
+    let clean = [
+      "* * * * *", "0 2 * * *", "*/15 * * * *", "*/6 */4 * */3 *",
+      "0 9 * * MON-FRI", "0 0 1 * *", "0 0 29 2 *", "30 * * * *", "@daily",
+    ];
+
+    for schedule in clean.iter() {
+      expect!(codes(schedule)).to(be_equal_to(Vec::new()));
+    }
+  }
+
+  #[test]
+  fn every_minute_in_hour() {
+    expect!(codes("* 2 * * *")).to(be_equal_to(vec![LintCode::EveryMinuteInHour]));
+    expect!(spans(" * 2 * * *")).to(be_equal_to(vec!["*"]));
+    expect!(codes("0-59 9-17 * * *")).to(be_equal_to(vec![LintCode::EveryMinuteInHour]));
+  }
+
+  #[test]
+  fn uneven_step() {
+    expect!(codes("*/45 * * * *")).to(be_equal_to(vec![LintCode::UnevenStep]));
+    expect!(codes("0 */5 * * *")).to(be_equal_to(vec![LintCode::UnevenStep]));
+    expect!(codes("0 0 * */5 *")).to(be_equal_to(vec![LintCode::UnevenStep]));
+    expect!(spans("0,*/45 * * * *")).to(be_equal_to(vec!["*/45"]));
+
+    let lints = lint(&Crontab::parse("*/45 * * * *").unwrap());
+    expect!(lints[0].message.contains("gap after 45")).to(be_true());
+
+    // Steps through explicit ranges aren't expected to repeat evenly.
+    expect!(codes("0-50/20 * * * *")).to(be_equal_to(Vec::new()));
+  }
+
+  #[test]
+  fn never_fires() {
+    expect!(codes("0 0 31 4 *")).to(be_equal_to(vec![LintCode::NeverFires]));
+    expect!(codes("0 0 30,31 2 *")).to(be_equal_to(vec![LintCode::NeverFires]));
+    expect!(codes("0 0 31 4,5 *")).to(be_equal_to(Vec::new()));
+    expect!(spans("0 0 31 APR *")).to(be_equal_to(vec!["31 APR"]));
+
+    // A backwards range of days doesn't parse, and `L` alone leaves no day
+    // numbers.
+    expect!(Crontab::parse("0 0 31-1 4 *")).to(be_err());
+    let crontab = Crontab::parse_dialect("0 0 0 L 4 ?", Dialect::Quartz).unwrap();
+    expect!(crontab.schedule().days().is_empty()).to(be_true());
+    expect!(lint(&crontab).is_empty()).to(be_true());
+  }
+
+  #[test]
+  fn day_or_weekday() {
+    expect!(codes("0 0 13 * 5")).to(be_equal_to(vec![LintCode::DayOrWeekday]));
+    expect!(spans("0 0 13 * FRI")).to(be_equal_to(vec!["13 * FRI"]));
+  }
+
+  #[test]
+  fn step_exceeds_range() {
+    expect!(codes("*/60 * * * *")).to(be_equal_to(vec![LintCode::StepExceedsRange]));
+    expect!(codes("0-10/15 * * * *")).to(be_equal_to(vec![LintCode::StepExceedsRange]));
+    expect!(spans("0 0-10/15 * * *")).to(be_equal_to(vec!["0-10/15"]));
+    expect!(codes("50/15 * * * *")).to(be_equal_to(vec![LintCode::StepExceedsRange]));
+
+    // Ranges of months wrap around the end of the year.
+    expect!(codes("0 0 1 NOV-FEB/2 *")).to(be_equal_to(Vec::<LintCode>::new()));
+    expect!(codes("0 0 1 NOV-FEB/5 *")).to(be_equal_to(vec![LintCode::StepExceedsRange]));
+  }
+
+  #[test]
+  fn several_lints_in_order() {
+    expect!(codes("0 */5 31 2 *"))
+        .to(be_equal_to(vec![LintCode::UnevenStep, LintCode::NeverFires]));
+  }
+
+  #[test]
+  fn display() {
+    let lints = lint(&Crontab::parse("0 0 31 4 *").unwrap());
+    expect!(lints[0].to_string())
+        .to(be_equal_to("never-fires: no month 4 has day 31, so this never runs".to_string()));
+  }
+
+  #[test]
+  fn aws() {
+    let lints = |schedule: &str| lint(&Crontab::parse_dialect(schedule, Dialect::Aws).unwrap());
+
+    expect!(lints("0 12 ? * MON-FRI *").is_empty()).to(be_true());
+    expect!(lints("0 0 L 2 ? *").is_empty()).to(be_true());
+    expect!(lints("*/45 * ? * * 2030-2040/3").iter().map(|l| l.message.as_str()).collect::<Vec<&str>>())
+        .to(be_equal_to(vec![
+          "`*/45` fires every 45 from 0 and restarts each hour, so the gap after 45 is shorter; \
+           it doesn't run every 45 minutes"]));
+  }
+
+  #[test]
+  fn quartz() {
+    let source = "*/45 * 9 31 2 ?";
+    let lints = lint(&Crontab::parse_dialect(source, Dialect::Quartz).unwrap());
+    expect!(lints.iter().map(|l| (l.code, l.span.text(source))).collect::<Vec<_>>())
+        .to(be_equal_to(vec![
+          (LintCode::UnevenStep, "*/45"),
+          (LintCode::EveryMinuteInHour, "*"),
+          (LintCode::NeverFires, "31 2"),
+        ]));
+    expect!(lints[0].message.contains("restarts each minute")).to(be_true());
+  }
+}
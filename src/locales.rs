@@ -0,0 +1,1134 @@
+//! Built-in locales for schedule descriptions.
+
+use description::{Clock, Description, Locale, Segment, capitalize, join_list};
+use parsing::DaySpecial;
+
+/// English descriptions, in the style of crontab.guru.
+/// "At 09:00 on every day-of-week from Monday through Friday."
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct English {
+  /// The clock used for times of day. Defaults to the 24-hour clock.
+  pub clock: Clock,
+}
+
+/// German descriptions. "Um 09:00 Uhr von Montag bis Freitag."
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct German;
+
+/// French descriptions. "À 9 h 00 du lundi au vendredi."
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct French;
+
+/// Spanish descriptions. "A las 09:00 de lunes a viernes."
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Spanish;
+
+/// Japanese descriptions. "月曜日から金曜日までの9時00分"
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Japanese {
+  /// The clock used for times of day. Defaults to the 24-hour clock.
+  pub clock: Clock,
+}
+
+impl Default for English {
+  fn default() -> English {
+    English { clock: Clock::TwentyFourHour }
+  }
+}
+
+impl Default for Japanese {
+  fn default() -> Japanese {
+    Japanese { clock: Clock::TwentyFourHour }
+  }
+}
+
+/// How one locale names the values of a field.
+type Namer<'a> = &'a dyn Fn(u32) -> String;
+
+fn number(value: u32) -> String {
+  value.to_string()
+}
+
+fn describe_segments<F>(segments: &[Segment], join: &dyn Fn(&[String]) -> String,
+                        phrase: F) -> String
+    where F: Fn(&Segment) -> String {
+  let phrases : Vec<String> = segments.iter().map(phrase).collect();
+  join(&phrases)
+}
+
+// English.
+
+const ENGLISH_WEEKDAYS : [&str; 7] = [
+  "Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday",
+];
+
+const ENGLISH_MONTHS : [&str; 12] = [
+  "January", "February", "March", "April", "May", "June", "July", "August",
+  "September", "October", "November", "December",
+];
+
+impl English {
+  fn join(items: &[String]) -> String {
+    join_list(items, ", ", " and ", true)
+  }
+
+  fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+      (_, 11) | (_, 12) | (_, 13) => "th",
+      (1, _) => "st",
+      (2, _) => "nd",
+      (3, _) => "rd",
+      _ => "th",
+    };
+    format!("{}{}", n, suffix)
+  }
+
+  fn time(&self, hour: u32, minute: u32) -> String {
+    match self.clock {
+      Clock::TwentyFourHour => format!("{:02}:{:02}", hour, minute),
+      Clock::TwelveHour => {
+        let (hour, period) = twelve_hour(hour, "AM", "PM");
+        format!("{}:{:02} {}", hour, minute, period)
+      },
+    }
+  }
+
+  fn hour(&self, hour: u32) -> String {
+    match self.clock {
+      Clock::TwentyFourHour => hour.to_string(),
+      Clock::TwelveHour => {
+        let (hour, period) = twelve_hour(hour, "AM", "PM");
+        format!("{} {}", hour, period)
+      },
+    }
+  }
+
+  /// "minute 0 and 30", "every 2nd hour from 0 through 20", "Monday".
+  fn field(unit: &str, segments: &[Segment], name: Namer, named: bool)
+      -> String {
+    describe_segments(segments, &English::join, |segment| match *segment {
+      Segment::Every => format!("every {}", unit),
+      Segment::Values(ref values) => {
+        let names : Vec<String> = values.iter().map(|v| name(*v)).collect();
+        if named {
+          English::join(&names)
+        } else {
+          format!("{} {}", unit, English::join(&names))
+        }
+      },
+      Segment::Range(start, end) => {
+        format!("every {} from {} through {}", unit, name(start), name(end))
+      },
+      Segment::Step(step) => format!("every {} {}", English::ordinal(step), unit),
+      Segment::SteppedRange(start, end, step) => {
+        format!("every {} {} from {} through {}",
+          English::ordinal(step), unit, name(start), name(end))
+      },
+    })
+  }
+}
+
+impl English {
+  /// "the last day of the month", "the 2nd Monday of the month".
+  fn day_special(special: DaySpecial) -> String {
+    let weekday = |v: u32| ENGLISH_WEEKDAYS[v as usize];
+    match special {
+      DaySpecial::LastDay(0) => "the last day of the month".to_string(),
+      DaySpecial::LastDay(1) => "1 day before the last day of the month".to_string(),
+      DaySpecial::LastDay(offset) => {
+        format!("{} days before the last day of the month", offset)
+      },
+      DaySpecial::LastBusinessDay => "the last weekday of the month".to_string(),
+      DaySpecial::NearestBusinessDay(day) => {
+        format!("the weekday nearest day-of-month {}", day)
+      },
+      DaySpecial::Last(day) => format!("the last {} of the month", weekday(day)),
+      DaySpecial::Nth(day, n) => {
+        format!("the {} {} of the month", English::ordinal(n), weekday(day))
+      },
+    }
+  }
+}
+
+impl Locale for English {
+  fn describe(&self, description: &Description) -> String {
+    let weekday = |v: u32| ENGLISH_WEEKDAYS[v as usize].to_string();
+    let month = |v: u32| ENGLISH_MONTHS[v as usize - 1].to_string();
+    let hour = |v: u32| self.hour(v);
+
+    let mut sentence = match description.time {
+      Some((h, m)) => self.time(h, m),
+      None => {
+        let mut time = English::field("minute", &description.minutes, &number, false);
+        if let Some(ref hours) = description.hours {
+          time.push_str(" past ");
+          time.push_str(&English::field("hour", hours, &hour, false));
+        }
+        time
+      },
+    };
+    if let Some(ref seconds) = description.seconds {
+      sentence = format!("{} past {}",
+                         English::field("second", seconds, &number, false), sentence);
+    }
+    sentence = format!("At {}", sentence);
+
+    let mut days : Vec<String> = description.days.iter()
+        .map(|days| English::field("day-of-month", days, &number, false))
+        .collect();
+    days.extend(description.day_specials(true).map(English::day_special));
+    if !days.is_empty() {
+      sentence.push_str(" on ");
+      sentence.push_str(&English::join(&days));
+    }
+
+    let mut weekdays : Vec<String> = description.weekdays.iter()
+        .map(|weekdays| English::field("day-of-week", weekdays, &weekday, true))
+        .collect();
+    weekdays.extend(description.day_specials(false).map(English::day_special));
+    if !weekdays.is_empty() {
+      // Cron runs the job when *either* day field matches.
+      sentence.push_str(if description.either_day() {
+        " or on "
+      } else if description.both_days {
+        " and only on "
+      } else {
+        " on "
+      });
+      sentence.push_str(&English::join(&weekdays));
+    }
+
+    if let Some(ref months) = description.months {
+      sentence.push_str(" in ");
+      sentence.push_str(&English::field("month", months, &month, true));
+    }
+
+    if let Some(ref years) = description.years {
+      sentence.push_str(" in ");
+      sentence.push_str(&English::field("year", years, &number, false));
+    }
+
+    sentence.push('.');
+    sentence
+  }
+}
+
+// German.
+
+const GERMAN_WEEKDAYS : [&str; 7] = [
+  "Sonntag", "Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag",
+  "Samstag",
+];
+
+const GERMAN_MONTHS : [&str; 12] = [
+  "Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August",
+  "September", "Oktober", "November", "Dezember",
+];
+
+impl German {
+  fn join(items: &[String]) -> String {
+    join_list(items, ", ", " und ", false)
+  }
+
+  fn seconds(segments: &[Segment]) -> String {
+    describe_segments(segments, &German::join, |segment| match *segment {
+      Segment::Every => "jede Sekunde".to_string(),
+      Segment::Values(ref values) => {
+        format!("in Sekunde {}", German::join(&names(values, &number)))
+      },
+      Segment::Range(start, end) => format!("jede Sekunde von {} bis {}", start, end),
+      Segment::Step(step) => format!("alle {} Sekunden", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("alle {} Sekunden von {} bis {}", step, start, end)
+      },
+    })
+  }
+
+  fn minutes(segments: &[Segment]) -> String {
+    describe_segments(segments, &German::join, |segment| match *segment {
+      Segment::Every => "jede Minute".to_string(),
+      Segment::Values(ref values) => {
+        format!("in Minute {}", German::join(&names(values, &number)))
+      },
+      Segment::Range(start, end) => format!("jede Minute von {} bis {}", start, end),
+      Segment::Step(step) => format!("alle {} Minuten", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("alle {} Minuten von {} bis {}", step, start, end)
+      },
+    })
+  }
+
+  fn hours(segments: &[Segment]) -> String {
+    describe_segments(segments, &German::join, |segment| match *segment {
+      Segment::Every => "jede Stunde".to_string(),
+      Segment::Values(ref values) => {
+        format!("in Stunde {}", German::join(&names(values, &number)))
+      },
+      Segment::Range(start, end) => format!("jede Stunde von {} bis {}", start, end),
+      Segment::Step(step) => format!("alle {} Stunden", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("alle {} Stunden von {} bis {}", step, start, end)
+      },
+    })
+  }
+
+  /// Days of the month are ordinals in German: "am 1. und 15. Tag".
+  fn days(segments: &[Segment]) -> String {
+    let ordinal = |v: u32| format!("{}.", v);
+    describe_segments(segments, &German::join, |segment| match *segment {
+      Segment::Every => "an jedem Tag des Monats".to_string(),
+      Segment::Values(ref values) => {
+        format!("am {} Tag des Monats", German::join(&names(values, &ordinal)))
+      },
+      Segment::Range(start, end) => {
+        format!("an den Tagen {} bis {} des Monats", start, end)
+      },
+      Segment::Step(step) => format!("an jedem {}. Tag des Monats", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("an jedem {}. Tag des Monats zwischen Tag {} und {}",
+          step, start, end)
+      },
+    })
+  }
+
+  /// Weekdays and months: "am Montag", "an jedem 2. Wochentag".
+  fn named(segments: &[Segment], name: Namer, values_prefix: &str,
+           article: &str, noun: &str) -> String {
+    describe_segments(segments, &German::join, |segment| match *segment {
+      Segment::Every => format!("{} {}", article, noun),
+      Segment::Values(ref values) => {
+        format!("{} {}", values_prefix, German::join(&names(values, name)))
+      },
+      Segment::Range(start, end) => format!("von {} bis {}", name(start), name(end)),
+      Segment::Step(step) => format!("{} {}. {}", article, step, noun),
+      Segment::SteppedRange(start, end, step) => {
+        format!("{} {}. {} von {} bis {}",
+          article, step, noun, name(start), name(end))
+      },
+    })
+  }
+}
+
+impl German {
+  /// "am letzten Tag des Monats", "am 2. Montag des Monats".
+  fn day_special(special: DaySpecial) -> String {
+    let weekday = |v: u32| GERMAN_WEEKDAYS[v as usize];
+    match special {
+      DaySpecial::LastDay(0) => "am letzten Tag des Monats".to_string(),
+      DaySpecial::LastDay(1) => "1 Tag vor dem letzten Tag des Monats".to_string(),
+      DaySpecial::LastDay(offset) => {
+        format!("{} Tage vor dem letzten Tag des Monats", offset)
+      },
+      DaySpecial::LastBusinessDay => "am letzten Werktag des Monats".to_string(),
+      DaySpecial::NearestBusinessDay(day) => {
+        format!("am nächstgelegenen Werktag zum {}. Tag des Monats", day)
+      },
+      DaySpecial::Last(day) => format!("am letzten {} des Monats", weekday(day)),
+      DaySpecial::Nth(day, n) => format!("am {}. {} des Monats", n, weekday(day)),
+    }
+  }
+}
+
+impl Locale for German {
+  fn describe(&self, description: &Description) -> String {
+    let weekday = |v: u32| GERMAN_WEEKDAYS[v as usize].to_string();
+    let month = |v: u32| GERMAN_MONTHS[v as usize - 1].to_string();
+
+    let mut sentence = match description.time {
+      Some((h, m)) => format!("um {:02}:{:02} Uhr", h, m),
+      None => {
+        let mut time = German::minutes(&description.minutes);
+        if let Some(ref hours) = description.hours {
+          time.push(' ');
+          time.push_str(&German::hours(hours));
+        }
+        time
+      },
+    };
+    if let Some(ref seconds) = description.seconds {
+      sentence = format!("{} {}", German::seconds(seconds), sentence);
+    }
+
+    let mut days : Vec<String> = description.days.iter()
+        .map(|days| German::days(days))
+        .collect();
+    days.extend(description.day_specials(true).map(German::day_special));
+    if !days.is_empty() {
+      sentence.push(' ');
+      sentence.push_str(&German::join(&days));
+    }
+
+    let mut weekdays : Vec<String> = description.weekdays.iter()
+        .map(|weekdays| German::named(weekdays, &weekday, "am", "an jedem", "Wochentag"))
+        .collect();
+    weekdays.extend(description.day_specials(false).map(German::day_special));
+    if !weekdays.is_empty() {
+      sentence.push_str(if description.either_day() {
+        " oder "
+      } else if description.both_days {
+        " und nur "
+      } else {
+        " "
+      });
+      sentence.push_str(&German::join(&weekdays));
+    }
+
+    if let Some(ref months) = description.months {
+      sentence.push(' ');
+      sentence.push_str(&German::named(months, &month, "im", "in jedem", "Monat"));
+    }
+
+    if let Some(ref years) = description.years {
+      sentence.push(' ');
+      sentence.push_str(&German::named(years, &number, "im Jahr", "in jedem", "Jahr"));
+    }
+
+    sentence.push('.');
+    capitalize(&sentence)
+  }
+}
+
+// French.
+
+const FRENCH_WEEKDAYS : [&str; 7] = [
+  "dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi",
+];
+
+const FRENCH_MONTHS : [&str; 12] = [
+  "janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août",
+  "septembre", "octobre", "novembre", "décembre",
+];
+
+impl French {
+  fn join(items: &[String]) -> String {
+    join_list(items, ", ", " et ", false)
+  }
+
+  /// The first day of the month is an ordinal in French: "le 1er".
+  fn day(day: u32) -> String {
+    if day == 1 { "1er".to_string() } else { day.to_string() }
+  }
+
+  /// "de" elides before a vowel: "d'avril", "d'octobre".
+  fn de(word: &str) -> String {
+    match word.chars().next() {
+      Some('a') | Some('e') | Some('é') | Some('i') | Some('o') | Some('u') => {
+        format!("d'{}", word)
+      },
+      _ => format!("de {}", word),
+    }
+  }
+
+  fn seconds(segments: &[Segment]) -> String {
+    describe_segments(segments, &French::join, |segment| match *segment {
+      Segment::Every => "chaque seconde".to_string(),
+      Segment::Values(ref values) => {
+        let article = if values.len() == 1 { "à la seconde" } else { "aux secondes" };
+        format!("{} {}", article, French::join(&names(values, &number)))
+      },
+      Segment::Range(start, end) => format!("chaque seconde de {} à {}", start, end),
+      Segment::Step(step) => format!("toutes les {} secondes", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("toutes les {} secondes de {} à {}", step, start, end)
+      },
+    })
+  }
+
+  fn minutes(segments: &[Segment]) -> String {
+    describe_segments(segments, &French::join, |segment| match *segment {
+      Segment::Every => "chaque minute".to_string(),
+      Segment::Values(ref values) => {
+        let article = if values.len() == 1 { "à la minute" } else { "aux minutes" };
+        format!("{} {}", article, French::join(&names(values, &number)))
+      },
+      Segment::Range(start, end) => format!("chaque minute de {} à {}", start, end),
+      Segment::Step(step) => format!("toutes les {} minutes", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("toutes les {} minutes de {} à {}", step, start, end)
+      },
+    })
+  }
+
+  fn hours(segments: &[Segment]) -> String {
+    let hour = |v: u32| format!("{} h", v);
+    describe_segments(segments, &French::join, |segment| match *segment {
+      Segment::Every => "chaque heure".to_string(),
+      Segment::Values(ref values) => {
+        let article = if values.len() == 1 { "pendant l'heure de" } else { "pendant les heures de" };
+        format!("{} {}", article, French::join(&names(values, &hour)))
+      },
+      Segment::Range(start, end) => {
+        format!("chaque heure de {} à {}", hour(start), hour(end))
+      },
+      Segment::Step(step) => format!("toutes les {} heures", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("toutes les {} heures de {} à {}", step, hour(start), hour(end))
+      },
+    })
+  }
+
+  fn days(segments: &[Segment]) -> String {
+    describe_segments(segments, &French::join, |segment| match *segment {
+      Segment::Every => "chaque jour du mois".to_string(),
+      Segment::Values(ref values) => {
+        let article = if values.len() == 1 { "le" } else { "les" };
+        format!("{} {} du mois", article, French::join(&names(values, &French::day)))
+      },
+      Segment::Range(start, end) => {
+        format!("du {} au {} du mois", French::day(start), French::day(end))
+      },
+      Segment::Step(step) => format!("tous les {} jours", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("tous les {} jours du {} au {} du mois",
+          step, French::day(start), French::day(end))
+      },
+    })
+  }
+
+  fn weekdays(segments: &[Segment]) -> String {
+    let weekday = |v: u32| FRENCH_WEEKDAYS[v as usize].to_string();
+    describe_segments(segments, &French::join, |segment| match *segment {
+      Segment::Every => "chaque jour de la semaine".to_string(),
+      Segment::Values(ref values) => {
+        let days : Vec<String> = values.iter()
+            .map(|v| format!("le {}", weekday(*v)))
+            .collect();
+        French::join(&days)
+      },
+      Segment::Range(start, end) => {
+        format!("du {} au {}", weekday(start), weekday(end))
+      },
+      Segment::Step(step) => format!("un jour de la semaine sur {}", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("un jour sur {} du {} au {}", step, weekday(start), weekday(end))
+      },
+    })
+  }
+
+  fn months(segments: &[Segment]) -> String {
+    let month = |v: u32| FRENCH_MONTHS[v as usize - 1].to_string();
+    describe_segments(segments, &French::join, |segment| match *segment {
+      Segment::Every => "chaque mois".to_string(),
+      Segment::Values(ref values) => {
+        format!("en {}", French::join(&names(values, &month)))
+      },
+      Segment::Range(start, end) => {
+        format!("{} à {}", French::de(&month(start)), month(end))
+      },
+      Segment::Step(step) => format!("tous les {} mois", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("tous les {} mois {} à {}", step, French::de(&month(start)), month(end))
+      },
+    })
+  }
+}
+
+impl French {
+  fn years(segments: &[Segment]) -> String {
+    describe_segments(segments, &French::join, |segment| match *segment {
+      Segment::Every => "chaque année".to_string(),
+      Segment::Values(ref values) => {
+        format!("en {}", French::join(&names(values, &number)))
+      },
+      Segment::Range(start, end) => format!("de {} à {}", start, end),
+      Segment::Step(step) => format!("tous les {} ans", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("tous les {} ans de {} à {}", step, start, end)
+      },
+    })
+  }
+
+  /// "le dernier jour du mois", "le 2e lundi du mois".
+  fn day_special(special: DaySpecial) -> String {
+    let weekday = |v: u32| FRENCH_WEEKDAYS[v as usize];
+    match special {
+      DaySpecial::LastDay(0) => "le dernier jour du mois".to_string(),
+      DaySpecial::LastDay(1) => "1 jour avant le dernier jour du mois".to_string(),
+      DaySpecial::LastDay(offset) => {
+        format!("{} jours avant le dernier jour du mois", offset)
+      },
+      DaySpecial::LastBusinessDay => "le dernier jour ouvré du mois".to_string(),
+      DaySpecial::NearestBusinessDay(day) => {
+        format!("le jour ouvré le plus proche du {} du mois", French::day(day))
+      },
+      DaySpecial::Last(day) => format!("le dernier {} du mois", weekday(day)),
+      DaySpecial::Nth(day, 1) => format!("le premier {} du mois", weekday(day)),
+      DaySpecial::Nth(day, n) => format!("le {}e {} du mois", n, weekday(day)),
+    }
+  }
+}
+
+impl Locale for French {
+  fn describe(&self, description: &Description) -> String {
+    let mut sentence = match description.time {
+      Some((h, m)) => format!("à {} h {:02}", h, m),
+      None => {
+        let mut time = French::minutes(&description.minutes);
+        if let Some(ref hours) = description.hours {
+          time.push(' ');
+          time.push_str(&French::hours(hours));
+        }
+        time
+      },
+    };
+    if let Some(ref seconds) = description.seconds {
+      sentence = format!("{} {}", French::seconds(seconds), sentence);
+    }
+
+    let mut days : Vec<String> = description.days.iter()
+        .map(|days| French::days(days))
+        .collect();
+    days.extend(description.day_specials(true).map(French::day_special));
+    if !days.is_empty() {
+      sentence.push(' ');
+      sentence.push_str(&French::join(&days));
+    }
+
+    let mut weekdays : Vec<String> = description.weekdays.iter()
+        .map(|weekdays| French::weekdays(weekdays))
+        .collect();
+    weekdays.extend(description.day_specials(false).map(French::day_special));
+    if !weekdays.is_empty() {
+      sentence.push_str(if description.either_day() {
+        " ou "
+      } else if description.both_days {
+        " et seulement "
+      } else {
+        " "
+      });
+      sentence.push_str(&French::join(&weekdays));
+    }
+
+    if let Some(ref months) = description.months {
+      sentence.push(' ');
+      sentence.push_str(&French::months(months));
+    }
+
+    if let Some(ref years) = description.years {
+      sentence.push(' ');
+      sentence.push_str(&French::years(years));
+    }
+
+    sentence.push('.');
+    capitalize(&sentence)
+  }
+}
+
+// Spanish.
+
+const SPANISH_WEEKDAYS : [&str; 7] = [
+  "domingo", "lunes", "martes", "miércoles", "jueves", "viernes", "sábado",
+];
+
+const SPANISH_MONTHS : [&str; 12] = [
+  "enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto",
+  "septiembre", "octubre", "noviembre", "diciembre",
+];
+
+impl Spanish {
+  fn join(items: &[String]) -> String {
+    join_list(items, ", ", " y ", false)
+  }
+
+  /// Weekdays ending in a vowel take an "s" in the plural: "los sábados",
+  /// but "los lunes".
+  fn weekday_plural(weekday: u32) -> String {
+    let name = SPANISH_WEEKDAYS[weekday as usize];
+    if name.ends_with('o') { format!("{}s", name) } else { name.to_string() }
+  }
+
+  fn seconds(segments: &[Segment]) -> String {
+    describe_segments(segments, &Spanish::join, |segment| match *segment {
+      Segment::Every => "cada segundo".to_string(),
+      Segment::Values(ref values) => {
+        let article = if values.len() == 1 { "en el segundo" } else { "en los segundos" };
+        format!("{} {}", article, Spanish::join(&names(values, &number)))
+      },
+      Segment::Range(start, end) => format!("cada segundo del {} al {}", start, end),
+      Segment::Step(step) => format!("cada {} segundos", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("cada {} segundos del {} al {}", step, start, end)
+      },
+    })
+  }
+
+  fn minutes(segments: &[Segment]) -> String {
+    describe_segments(segments, &Spanish::join, |segment| match *segment {
+      Segment::Every => "cada minuto".to_string(),
+      Segment::Values(ref values) => {
+        let article = if values.len() == 1 { "en el minuto" } else { "en los minutos" };
+        format!("{} {}", article, Spanish::join(&names(values, &number)))
+      },
+      Segment::Range(start, end) => format!("cada minuto del {} al {}", start, end),
+      Segment::Step(step) => format!("cada {} minutos", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("cada {} minutos del {} al {}", step, start, end)
+      },
+    })
+  }
+
+  fn hours(segments: &[Segment]) -> String {
+    describe_segments(segments, &Spanish::join, |segment| match *segment {
+      Segment::Every => "cada hora".to_string(),
+      Segment::Values(ref values) => {
+        let article = if values.len() == 1 { "durante la hora" } else { "durante las horas" };
+        format!("{} {}", article, Spanish::join(&names(values, &number)))
+      },
+      Segment::Range(start, end) => format!("cada hora de {} a {}", start, end),
+      Segment::Step(step) => format!("cada {} horas", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("cada {} horas de {} a {}", step, start, end)
+      },
+    })
+  }
+
+  fn days(segments: &[Segment]) -> String {
+    describe_segments(segments, &Spanish::join, |segment| match *segment {
+      Segment::Every => "cada día del mes".to_string(),
+      Segment::Values(ref values) => {
+        let article = if values.len() == 1 { "el día" } else { "los días" };
+        format!("{} {} del mes", article, Spanish::join(&names(values, &number)))
+      },
+      Segment::Range(start, end) => format!("del día {} al {} del mes", start, end),
+      Segment::Step(step) => format!("cada {} días", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("cada {} días del {} al {} del mes", step, start, end)
+      },
+    })
+  }
+
+  fn weekdays(segments: &[Segment]) -> String {
+    let weekday = |v: u32| SPANISH_WEEKDAYS[v as usize].to_string();
+    describe_segments(segments, &Spanish::join, |segment| match *segment {
+      Segment::Every => "cada día de la semana".to_string(),
+      Segment::Values(ref values) => {
+        format!("los {}", Spanish::join(&names(values, &Spanish::weekday_plural)))
+      },
+      Segment::Range(start, end) => {
+        format!("de {} a {}", weekday(start), weekday(end))
+      },
+      Segment::Step(step) => format!("cada {} días de la semana", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("cada {} días de la semana de {} a {}",
+          step, weekday(start), weekday(end))
+      },
+    })
+  }
+
+  fn months(segments: &[Segment]) -> String {
+    let month = |v: u32| SPANISH_MONTHS[v as usize - 1].to_string();
+    describe_segments(segments, &Spanish::join, |segment| match *segment {
+      Segment::Every => "cada mes".to_string(),
+      Segment::Values(ref values) => {
+        format!("en {}", Spanish::join(&names(values, &month)))
+      },
+      Segment::Range(start, end) => format!("de {} a {}", month(start), month(end)),
+      Segment::Step(step) => format!("cada {} meses", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("cada {} meses de {} a {}", step, month(start), month(end))
+      },
+    })
+  }
+}
+
+impl Spanish {
+  fn years(segments: &[Segment]) -> String {
+    describe_segments(segments, &Spanish::join, |segment| match *segment {
+      Segment::Every => "cada año".to_string(),
+      Segment::Values(ref values) => {
+        format!("en {}", Spanish::join(&names(values, &number)))
+      },
+      Segment::Range(start, end) => format!("de {} a {}", start, end),
+      Segment::Step(step) => format!("cada {} años", step),
+      Segment::SteppedRange(start, end, step) => {
+        format!("cada {} años de {} a {}", step, start, end)
+      },
+    })
+  }
+
+  /// "el último día del mes", "el segundo lunes del mes".
+  fn day_special(special: DaySpecial) -> String {
+    const ORDINALS : [&str; 5] = ["primer", "segundo", "tercer", "cuarto", "quinto"];
+    let weekday = |v: u32| SPANISH_WEEKDAYS[v as usize];
+    match special {
+      DaySpecial::LastDay(0) => "el último día del mes".to_string(),
+      DaySpecial::LastDay(1) => "1 día antes del último día del mes".to_string(),
+      DaySpecial::LastDay(offset) => {
+        format!("{} días antes del último día del mes", offset)
+      },
+      DaySpecial::LastBusinessDay => "el último día hábil del mes".to_string(),
+      DaySpecial::NearestBusinessDay(day) => {
+        format!("el día hábil más cercano al día {} del mes", day)
+      },
+      DaySpecial::Last(day) => format!("el último {} del mes", weekday(day)),
+      DaySpecial::Nth(day, n) => {
+        format!("el {} {} del mes", ORDINALS[n as usize - 1], weekday(day))
+      },
+    }
+  }
+}
+
+impl Locale for Spanish {
+  fn describe(&self, description: &Description) -> String {
+    let mut sentence = match description.time {
+      // "A la una", but "a las dos".
+      Some((1, m)) => format!("a la 01:{:02}", m),
+      Some((h, m)) => format!("a las {:02}:{:02}", h, m),
+      None => {
+        let mut time = Spanish::minutes(&description.minutes);
+        if let Some(ref hours) = description.hours {
+          time.push(' ');
+          time.push_str(&Spanish::hours(hours));
+        }
+        time
+      },
+    };
+    if let Some(ref seconds) = description.seconds {
+      sentence = format!("{} {}", Spanish::seconds(seconds), sentence);
+    }
+
+    let mut days : Vec<String> = description.days.iter()
+        .map(|days| Spanish::days(days))
+        .collect();
+    days.extend(description.day_specials(true).map(Spanish::day_special));
+    if !days.is_empty() {
+      sentence.push(' ');
+      sentence.push_str(&Spanish::join(&days));
+    }
+
+    let mut weekdays : Vec<String> = description.weekdays.iter()
+        .map(|weekdays| Spanish::weekdays(weekdays))
+        .collect();
+    weekdays.extend(description.day_specials(false).map(Spanish::day_special));
+    if !weekdays.is_empty() {
+      sentence.push_str(if description.either_day() {
+        " o "
+      } else if description.both_days {
+        " y solo "
+      } else {
+        " "
+      });
+      sentence.push_str(&Spanish::join(&weekdays));
+    }
+
+    if let Some(ref months) = description.months {
+      sentence.push(' ');
+      sentence.push_str(&Spanish::months(months));
+    }
+
+    if let Some(ref years) = description.years {
+      sentence.push(' ');
+      sentence.push_str(&Spanish::years(years));
+    }
+
+    sentence.push('.');
+    capitalize(&sentence)
+  }
+}
+
+// Japanese.
+
+const JAPANESE_WEEKDAYS : [&str; 7] = [
+  "日曜日", "月曜日", "火曜日", "水曜日", "木曜日", "金曜日", "土曜日",
+];
+
+impl Japanese {
+  fn join(items: &[String]) -> String {
+    items.join("、")
+  }
+
+  fn time(&self, hour: u32, minute: u32) -> String {
+    format!("{}{:02}分", self.hour(hour), minute)
+  }
+
+  fn hour(&self, hour: u32) -> String {
+    match self.clock {
+      Clock::TwentyFourHour => format!("{}時", hour),
+      Clock::TwelveHour => {
+        // Noon is 午後0時 on the Japanese 12-hour clock.
+        let period = if hour < 12 { "午前" } else { "午後" };
+        format!("{}{}時", period, hour % 12)
+      },
+    }
+  }
+
+  /// Units are counters appended to each value: "1日", "15分". Steps use
+  /// the counter for intervals, which can differ: "2時間ごと".
+  fn field(segments: &[Segment], name: Namer, interval: &str, every: &str)
+      -> String {
+    describe_segments(segments, &Japanese::join, |segment| match *segment {
+      Segment::Every => every.to_string(),
+      Segment::Values(ref values) => Japanese::join(&names(values, name)),
+      Segment::Range(start, end) => format!("{}から{}まで", name(start), name(end)),
+      Segment::Step(step) => format!("{}{}ごと", step, interval),
+      Segment::SteppedRange(start, end, step) => {
+        format!("{}から{}まで{}{}ごと", name(start), name(end), step, interval)
+      },
+    })
+  }
+}
+
+impl Japanese {
+  /// "月末", "第2月曜日".
+  fn day_special(special: DaySpecial) -> String {
+    let weekday = |v: u32| JAPANESE_WEEKDAYS[v as usize];
+    match special {
+      DaySpecial::LastDay(0) => "月末".to_string(),
+      DaySpecial::LastDay(offset) => format!("月末の{}日前", offset),
+      DaySpecial::LastBusinessDay => "月末の平日".to_string(),
+      DaySpecial::NearestBusinessDay(day) => format!("{}日に最も近い平日", day),
+      DaySpecial::Last(day) => format!("最終{}", weekday(day)),
+      DaySpecial::Nth(day, n) => format!("第{}{}", n, weekday(day)),
+    }
+  }
+}
+
+impl Locale for Japanese {
+  fn describe(&self, description: &Description) -> String {
+    let minute = |v: u32| format!("{}分", v);
+    let hour = |v: u32| self.hour(v);
+    let day = |v: u32| format!("{}日", v);
+    let month = |v: u32| format!("{}月", v);
+    let weekday = |v: u32| JAPANESE_WEEKDAYS[v as usize].to_string();
+
+    let year = |v: u32| format!("{}年", v);
+    let second = |v: u32| format!("{}秒", v);
+
+    // Japanese runs from the largest unit to the smallest, joined with "の".
+    let mut parts = Vec::new();
+
+    if let Some(ref years) = description.years {
+      parts.push(Japanese::field(years, &year, "年", "毎年"));
+    }
+
+    if let Some(ref months) = description.months {
+      parts.push(Japanese::field(months, &month, "か月", "毎月"));
+    }
+
+    let mut days : Vec<String> = description.days.iter()
+        .map(|days| Japanese::field(days, &day, "日", "毎日"))
+        .collect();
+    days.extend(description.day_specials(true).map(Japanese::day_special));
+
+    let mut weekdays : Vec<String> = description.weekdays.iter()
+        .map(|weekdays| Japanese::field(weekdays, &weekday, "曜日", "毎日"))
+        .collect();
+    weekdays.extend(description.day_specials(false).map(Japanese::day_special));
+
+    match (days.is_empty(), weekdays.is_empty()) {
+      (false, false) => {
+        let either = if description.both_days { "かつ" } else { "または" };
+        parts.push(format!("{}{}{}", Japanese::join(&days), either, Japanese::join(&weekdays)));
+      },
+      (false, true) => parts.push(Japanese::join(&days)),
+      (true, false) => parts.push(Japanese::join(&weekdays)),
+      (true, true) => {},
+    }
+
+    match description.time {
+      Some((h, m)) => parts.push(self.time(h, m)),
+      None => {
+        if let Some(ref hours) = description.hours {
+          parts.push(Japanese::field(hours, &hour, "時間", "毎時"));
+        }
+        parts.push(Japanese::field(&description.minutes, &minute, "分", "毎分"));
+      },
+    }
+
+    if let Some(ref seconds) = description.seconds {
+      parts.push(Japanese::field(seconds, &second, "秒", "毎秒"));
+    }
+
+    parts.join("の")
+  }
+}
+
+fn names(values: &[u32], name: Namer) -> Vec<String> {
+  values.iter().map(|v| name(*v)).collect()
+}
+
+fn twelve_hour(hour: u32, am: &'static str, pm: &'static str)
+    -> (u32, &'static str) {
+  let period = if hour < 12 { am } else { pm };
+  match hour % 12 {
+    0 => (12, period),
+    h => (h, period),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crontab::Crontab;
+  use dialect::Dialect;
+  use expectest::prelude::*;
+  use parsing::parse_cron;
+
+  fn describe<L: Locale>(locale: &L, schedule: &str) -> String {
+    locale.describe(&Description::new(&parse_cron(schedule).unwrap()))
+  }
+
+  #[test]
+  fn english() {
+    let en = English::default();
+    expect!(describe(&en, "* * * * *")).to(be_equal_to("At every minute.".to_string()));
+    expect!(describe(&en, "0 9 * * *")).to(be_equal_to("At 09:00.".to_string()));
+    expect!(describe(&en, "0 * * * *")).to(be_equal_to("At minute 0.".to_string()));
+    expect!(describe(&en, "*/15 * * * *"))
+        .to(be_equal_to("At every 15th minute.".to_string()));
+    expect!(describe(&en, "* 2 * * *"))
+        .to(be_equal_to("At every minute past hour 2.".to_string()));
+    expect!(describe(&en, "0,15,30 9,17 * * *"))
+        .to(be_equal_to("At minute 0, 15, and 30 past hour 9 and 17.".to_string()));
+    expect!(describe(&en, "23 0-20/2 * * *"))
+        .to(be_equal_to("At minute 23 past every 2nd hour from 0 through 20.".to_string()));
+    expect!(describe(&en, "0 9 * * 1-5"))
+        .to(be_equal_to("At 09:00 on every day-of-week from Monday through Friday.".to_string()));
+    expect!(describe(&en, "0 4 8-14 * *"))
+        .to(be_equal_to("At 04:00 on every day-of-month from 8 through 14.".to_string()));
+    expect!(describe(&en, "0 0 1 */3 *"))
+        .to(be_equal_to("At 00:00 on day-of-month 1 in every 3rd month.".to_string()));
+    expect!(describe(&en, "0 0 * 1,7 0,6"))
+        .to(be_equal_to("At 00:00 on Sunday and Saturday in January and July.".to_string()));
+    expect!(describe(&en, "0 0 1,15 * 3"))
+        .to(be_equal_to("At 00:00 on day-of-month 1 and 15 or on Wednesday.".to_string()));
+    expect!(describe(&en, "*/15,7 * * * *"))
+        .to(be_equal_to("At every 15th minute and minute 7.".to_string()));
+  }
+
+  #[test]
+  fn english_twelve_hour_clock() {
+    let en = English { clock: Clock::TwelveHour };
+    expect!(describe(&en, "30 17 * * *")).to(be_equal_to("At 5:30 PM.".to_string()));
+    expect!(describe(&en, "0 0 * * *")).to(be_equal_to("At 12:00 AM.".to_string()));
+    expect!(describe(&en, "0 12 * * *")).to(be_equal_to("At 12:00 PM.".to_string()));
+    expect!(describe(&en, "0 9,17 * * *"))
+        .to(be_equal_to("At minute 0 past hour 9 AM and 5 PM.".to_string()));
+  }
+
+  #[test]
+  fn english_ordinals() {
+    let ordinals : Vec<String> = vec![1, 2, 3, 4, 11, 12, 13, 21, 22].into_iter()
+        .map(English::ordinal)
+        .collect();
+    expect!(ordinals.join(" "))
+        .to(be_equal_to("1st 2nd 3rd 4th 11th 12th 13th 21st 22nd".to_string()));
+  }
+
+  #[test]
+  fn german() {
+    expect!(describe(&German, "* * * * *")).to(be_equal_to("Jede Minute.".to_string()));
+    expect!(describe(&German, "0 9 * * 1-5"))
+        .to(be_equal_to("Um 09:00 Uhr von Montag bis Freitag.".to_string()));
+    expect!(describe(&German, "*/15 * * * *"))
+        .to(be_equal_to("Alle 15 Minuten.".to_string()));
+    expect!(describe(&German, "0,30 9,17 * * *"))
+        .to(be_equal_to("In Minute 0 und 30 in Stunde 9 und 17.".to_string()));
+    expect!(describe(&German, "0 0 1,15 * 3"))
+        .to(be_equal_to("Um 00:00 Uhr am 1. und 15. Tag des Monats oder am Mittwoch.".to_string()));
+    expect!(describe(&German, "0 0 * 3 */2"))
+        .to(be_equal_to("Um 00:00 Uhr an jedem 2. Wochentag im März.".to_string()));
+    expect!(describe(&German, "0 0 * 1-6 *"))
+        .to(be_equal_to("Um 00:00 Uhr von Januar bis Juni.".to_string()));
+  }
+
+  #[test]
+  fn french() {
+    expect!(describe(&French, "* * * * *")).to(be_equal_to("Chaque minute.".to_string()));
+    expect!(describe(&French, "0 9 * * 1-5"))
+        .to(be_equal_to("À 9 h 00 du lundi au vendredi.".to_string()));
+    expect!(describe(&French, "*/15 * * * *"))
+        .to(be_equal_to("Toutes les 15 minutes.".to_string()));
+    expect!(describe(&French, "0 0 1,15 * 3"))
+        .to(be_equal_to("À 0 h 00 les 1er et 15 du mois ou le mercredi.".to_string()));
+    expect!(describe(&French, "30 6 1 4-8 *"))
+        .to(be_equal_to("À 6 h 30 le 1er du mois d'avril à août.".to_string()));
+    expect!(describe(&French, "0 12 * * 0,6"))
+        .to(be_equal_to("À 12 h 00 le dimanche et le samedi.".to_string()));
+  }
+
+  #[test]
+  fn spanish() {
+    expect!(describe(&Spanish, "* * * * *")).to(be_equal_to("Cada minuto.".to_string()));
+    expect!(describe(&Spanish, "0 9 * * 1-5"))
+        .to(be_equal_to("A las 09:00 de lunes a viernes.".to_string()));
+    expect!(describe(&Spanish, "30 1 * * *"))
+        .to(be_equal_to("A la 01:30.".to_string()));
+    expect!(describe(&Spanish, "*/15 * * * *"))
+        .to(be_equal_to("Cada 15 minutos.".to_string()));
+    expect!(describe(&Spanish, "0 12 * * 0,3,5"))
+        .to(be_equal_to("A las 12:00 los domingos, miércoles y viernes.".to_string()));
+    expect!(describe(&Spanish, "0 0 1,15 * 3"))
+        .to(be_equal_to("A las 00:00 los días 1 y 15 del mes o los miércoles.".to_string()));
+  }
+
+  #[test]
+  fn japanese() {
+    let ja = Japanese::default();
+    expect!(describe(&ja, "* * * * *")).to(be_equal_to("毎分".to_string()));
+    expect!(describe(&ja, "0 9 * * 1-5"))
+        .to(be_equal_to("月曜日から金曜日までの9時00分".to_string()));
+    expect!(describe(&ja, "*/15 * * * *")).to(be_equal_to("15分ごと".to_string()));
+    expect!(describe(&ja, "0 */2 * * *")).to(be_equal_to("2時間ごとの0分".to_string()));
+    expect!(describe(&ja, "0 0 1,15 1,7 *"))
+        .to(be_equal_to("1月、7月の1日、15日の0時00分".to_string()));
+    expect!(describe(&ja, "0 0 1 * 0"))
+        .to(be_equal_to("1日または日曜日の0時00分".to_string()));
+
+    let ja = Japanese { clock: Clock::TwelveHour };
+    expect!(describe(&ja, "30 17 * * *")).to(be_equal_to("午後5時30分".to_string()));
+    expect!(describe(&ja, "0 12 * * *")).to(be_equal_to("午後0時00分".to_string()));
+  }
+
+  #[test]
+  fn aws_days_and_years() {
+    let describe = |locale: &dyn Locale, schedule: &str| {
+      let crontab = Crontab::parse_dialect(schedule, Dialect::Aws).unwrap();
+      locale.describe(&Description::new(crontab.schedule()))
+    };
+
+    let en = English::default();
+    expect!(describe(&en, "0 9 1,L * ? *"))
+        .to(be_equal_to("At 09:00 on day-of-month 1 and the last day of the month.".to_string()));
+    expect!(describe(&en, "0 9 L-3 * ? 2030"))
+        .to(be_equal_to("At 09:00 on 3 days before the last day of the month in year 2030.".to_string()));
+    expect!(describe(&en, "0 9 ? * MON#2,6L *")).to(be_equal_to(
+      "At 09:00 on the last Friday of the month and the 2nd Monday of the month.".to_string()));
+    expect!(describe(&en, "0 9 15W * ? 2030-2035")).to(be_equal_to(
+      "At 09:00 on the weekday nearest day-of-month 15 in every year from 2030 through 2035."
+          .to_string()));
+
+    expect!(describe(&German, "0 9 LW * ? *"))
+        .to(be_equal_to("Um 09:00 Uhr am letzten Werktag des Monats.".to_string()));
+    expect!(describe(&French, "0 9 ? * 2#1 *"))
+        .to(be_equal_to("À 9 h 00 le premier lundi du mois.".to_string()));
+    expect!(describe(&Spanish, "0 9 ? * 2#3 *"))
+        .to(be_equal_to("A las 09:00 el tercer lunes del mes.".to_string()));
+    expect!(describe(&Japanese::default(), "0 9 L * ? 2030"))
+        .to(be_equal_to("2030年の月末の9時00分".to_string()));
+  }
+
+  #[test]
+  fn seconds_and_both_days() {
+    let describe = |locale: &dyn Locale, schedule: &str| {
+      let crontab = Crontab::parse_dialect(schedule, Dialect::Spring).unwrap();
+      locale.describe(&Description::new(crontab.schedule()))
+    };
+
+    let en = English::default();
+    expect!(describe(&en, "30 0 9 * * *")).to(be_equal_to("At second 30 past 09:00.".to_string()));
+    expect!(describe(&en, "*/10 * * * * *"))
+        .to(be_equal_to("At every 10th second past every minute.".to_string()));
+    expect!(describe(&en, "0 0 0 13 * FRI"))
+        .to(be_equal_to("At 00:00 on day-of-month 13 and only on Friday.".to_string()));
+
+    expect!(describe(&German, "*/10 * * * * *"))
+        .to(be_equal_to("Alle 10 Sekunden jede Minute.".to_string()));
+    expect!(describe(&French, "30 0 9 13 * FRI"))
+        .to(be_equal_to("À la seconde 30 à 9 h 00 le 13 du mois et seulement le vendredi.".to_string()));
+    expect!(describe(&Spanish, "30 0 9 13 * FRI"))
+        .to(be_equal_to("En el segundo 30 a las 09:00 el día 13 del mes y solo los viernes.".to_string()));
+    expect!(describe(&Japanese::default(), "30 0 9 13 * FRI"))
+        .to(be_equal_to("13日かつ金曜日の9時00分の30秒".to_string()));
+  }
+}
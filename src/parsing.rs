@@ -1,125 +1,728 @@
-#![allow(deprecated)]
-
+use ast::{Atom, AtomKind, Expression, ExpressionKind, Field, FieldKind, Item};
+use ast::{ItemKind, Span, Special};
+use calendar::{Month, Weekday};
+use diagnostic::Located;
+use dialect::Dialect;
 use error::CrontabError;
+use formatting::format_cron;
 use std::collections::HashSet;
 use std::iter::FromIterator;
+use suggest::suggest;
+use time::Tm;
+use times::{days_in_month, weekday};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serialization::ScheduleFields;
 
 /// The components of a crontab schedule.
-/// The values in each field are guaranteed to be both unique and ordered.
-#[derive(Clone, Debug, Default)]
+/// The values in each field are guaranteed to be non-empty, unique, ordered
+/// and within the field's range.
+///
+/// The fields used to be public. Code that built the struct by hand should
+/// use `ScheduleComponents::new`, or `ScheduleComponents::from_unordered`
+/// when the values aren't already sorted, and read the fields through the
+/// accessors of the same names.
+///
+/// Schedules from dialects such as AWS can also be limited to some years,
+/// and pick days with `L`, `W` and `#`, as `DaySpecial`s. A day field may
+/// then have no values, eg. for `L` alone.
+///
+/// With the `serde` feature enabled, the components serialize as a
+/// structured object of their field lists, and are validated on
+/// deserialization.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "ScheduleFields"))]
 pub struct ScheduleComponents {
+  #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+  seconds: Option<Vec<u32>>,
+  minutes: Vec<u32>,
+  hours: Vec<u32>,
+  days: Vec<u32>,
+  months: Vec<u32>,
+  weekdays: Vec<u32>,
+  #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+  years: Option<Vec<u32>>,
+  #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+  day_specials: Vec<DaySpecial>,
+  #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_false"))]
+  both_days: bool,
+}
+
+/// A day picked by its place in the month rather than by number, from the
+/// `L`, `W` and `#` of dialects such as AWS. Days of the week are [0,6] with
+/// 0 as Sunday.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DaySpecial {
+  /// The last day of the month, or a number of days before it: `L` or
+  /// `L-3`.
+  LastDay(u32),
+  /// The last weekday, Monday to Friday, of the month: `LW`.
+  LastBusinessDay,
+  /// The weekday, Monday to Friday, nearest a day of the month, without
+  /// leaving the month, eg. `15W`. Months without the day are skipped.
+  NearestBusinessDay(u32),
+  /// The last of a day of the week in the month, eg. `FRIL`.
+  Last(u32),
+  /// The nth of a day of the week in the month, from 1 to 5, eg. `MON#2`.
+  Nth(u32, u32),
+}
+
+impl DaySpecial {
+  /// Whether this picks a day of the month, rather than a day of the week.
+  pub fn is_day_of_month(self) -> bool {
+    match self {
+      DaySpecial::LastDay(_)
+          | DaySpecial::LastBusinessDay
+          | DaySpecial::NearestBusinessDay(_) => true,
+      DaySpecial::Last(_) | DaySpecial::Nth(_, _) => false,
+    }
+  }
+
+  /// Whether the date of a time is the day this picks.
+  ///
+  /// ```
+  /// # extern crate crontab;
+  /// # extern crate time;
+  /// # fn main() {
+  /// use crontab::DaySpecial;
+  ///
+  /// // Friday 2017-03-31.
+  /// let time = time::at_utc(time::Timespec::new(1490918400, 0));
+  /// assert!(DaySpecial::LastDay(0).matches(&time));
+  /// assert!(DaySpecial::LastBusinessDay.matches(&time));
+  /// assert!(DaySpecial::Last(5).matches(&time));
+  /// assert!(!DaySpecial::Nth(5, 4).matches(&time));
+  /// # }
+  /// ```
+  pub fn matches(self, time: &Tm) -> bool {
+    let day = time.tm_mday as u32;
+    let last = days_in_month(time) as u32;
+    // The day of the week of another day of the same month.
+    let weekday_of = |other: u32| (weekday(time) + 35 + other - day) % 7;
+
+    match self {
+      DaySpecial::LastDay(offset) => day + offset == last,
+      DaySpecial::LastBusinessDay => {
+        day == match weekday_of(last) {
+          6 => last - 1,
+          0 => last - 2,
+          _ => last,
+        }
+      },
+      DaySpecial::NearestBusinessDay(nearest) => {
+        nearest <= last && day == match weekday_of(nearest) {
+          6 if nearest == 1 => 3,
+          6 => nearest - 1,
+          0 if nearest == last => last - 2,
+          0 => nearest + 1,
+          _ => nearest,
+        }
+      },
+      DaySpecial::Last(weekday_value) => {
+        weekday(time) == weekday_value && day + 7 > last
+      },
+      DaySpecial::Nth(weekday_value, n) => {
+        weekday(time) == weekday_value && (day - 1) / 7 + 1 == n
+      },
+    }
+  }
+
+  /// Check that the numbers are within range.
+  fn check(self) -> Result<(), CrontabError> {
+    match self {
+      DaySpecial::LastDay(offset) => check_range(offset, offset, 0, 30),
+      DaySpecial::LastBusinessDay => Ok(()),
+      DaySpecial::NearestBusinessDay(day) => check_range(day, day, 1, 31),
+      DaySpecial::Last(weekday) => check_range(weekday, weekday, 0, 6),
+      DaySpecial::Nth(weekday, n) => {
+        check_range(weekday, weekday, 0, 6)?;
+        check_range(n, n, 1, 5)
+      },
+    }
+  }
+}
+
+impl ScheduleComponents {
+  /// Create schedule components from the values of each field. The values of
+  /// each field must be non-empty, unique, in ascending order and within the
+  /// field's range.
+  ///
+  /// ```
+  /// use crontab::ScheduleComponents;
+  ///
+  /// let schedule = ScheduleComponents::new(
+  ///     vec![0], vec![9, 17], (1..32).collect(), (1..13).collect(), vec![1, 5])
+  ///   .unwrap();
+  /// assert_eq!(schedule.to_expression(), "0 9,17 * * 1,5");
+  ///
+  /// assert!(ScheduleComponents::new(
+  ///     vec![60], vec![0], vec![1], vec![1], vec![0]).is_err());
+  /// ```
+  pub fn new(minutes: Vec<u32>,
+             hours: Vec<u32>,
+             days: Vec<u32>,
+             months: Vec<u32>,
+             weekdays: Vec<u32>) -> Result<ScheduleComponents, CrontabError> {
+    ScheduleComponents {
+      seconds: None,
+      minutes,
+      hours,
+      days,
+      months,
+      weekdays,
+      years: None,
+      day_specials: Vec::new(),
+      both_days: false,
+    }.checked()
+  }
+
+  /// Like `ScheduleComponents::new`, but also with the years, or `None` for
+  /// every year, and the days picked by their place in the month.
+  pub (crate) fn with_extensions(minutes: Vec<u32>,
+                                 hours: Vec<u32>,
+                                 days: Vec<u32>,
+                                 months: Vec<u32>,
+                                 weekdays: Vec<u32>,
+                                 years: Option<Vec<u32>>,
+                                 day_specials: Vec<DaySpecial>)
+      -> Result<ScheduleComponents, CrontabError> {
+    ScheduleComponents {
+      seconds: None,
+      minutes,
+      hours,
+      days,
+      months,
+      weekdays,
+      years,
+      day_specials: sorted(day_specials),
+      both_days: false,
+    }.checked()
+  }
+
+  /// The components firing at the given seconds of each minute, as in the
+  /// Quartz and Spring dialects, rather than only at second 0. This replaces
+  /// the `seconds` field, which was never set. The seconds must be
+  /// non-empty, unique, in ascending order and within range.
+  ///
+  /// ```
+  /// use crontab::ScheduleComponents;
+  ///
+  /// let schedule = ScheduleComponents::default().with_seconds(vec![0, 30]).unwrap();
+  /// assert_eq!(schedule.seconds(), Some(&[0, 30][..]));
+  ///
+  /// let schedule = ScheduleComponents::default().with_seconds(vec![0]).unwrap();
+  /// assert_eq!(schedule.seconds(), None);
+  /// ```
+  pub fn with_seconds(self, seconds: Vec<u32>) -> Result<ScheduleComponents, CrontabError> {
+    ScheduleComponents {
+      seconds: Some(seconds).filter(|seconds| *seconds != [0]),
+      ..self
+    }.checked()
+  }
+
+  /// The components with whether a day must match both day fields when both
+  /// are restricted.
+  #[cfg(feature = "serde")]
+  pub (crate) fn with_both_days(self, both_days: bool) -> ScheduleComponents {
+    ScheduleComponents { both_days, ..self }
+  }
+
+  /// Check that each field is valid. A day field may be empty when a day
+  /// special picks its days.
+  fn checked(self) -> Result<ScheduleComponents, CrontabError> {
+    let specials = |day_of_month: bool| {
+      self.day_specials.iter().any(|special| special.is_day_of_month() == day_of_month)
+    };
+
+    if let Some(ref seconds) = self.seconds {
+      check_field("seconds", seconds, 0, 59)?;
+    }
+    check_field("minutes", &self.minutes, 0, 59)?;
+    check_field("hours", &self.hours, 0, 23)?;
+    if !(self.days.is_empty() && specials(true)) {
+      check_field("days", &self.days, 1, 31)?;
+    }
+    check_field("months", &self.months, 1, 12)?;
+    if !(self.weekdays.is_empty() && specials(false)) {
+      check_field("weekdays", &self.weekdays, 0, 6)?;
+    }
+    if let Some(ref years) = self.years {
+      check_field("years", years, FieldKind::Year.min(), FieldKind::Year.max())?;
+    }
+    for special in &self.day_specials {
+      special.check()?;
+    }
+
+    Ok(self)
+  }
+
+  /// Like `ScheduleComponents::new`, but sorts and removes duplicate values
+  /// first. The values must still be non-empty and within range.
+  pub fn from_unordered(minutes: Vec<u32>,
+                        hours: Vec<u32>,
+                        days: Vec<u32>,
+                        months: Vec<u32>,
+                        weekdays: Vec<u32>)
+      -> Result<ScheduleComponents, CrontabError> {
+    ScheduleComponents::new(
+      sorted(minutes),
+      sorted(hours),
+      sorted(days),
+      sorted(months),
+      sorted(weekdays))
+  }
+
+  /// Like `ScheduleComponents::from_unordered`, but with the months and
+  /// days of the week as `Month`s and `Weekday`s.
+  ///
+  /// ```
+  /// use crontab::{Month, ScheduleComponents, Weekday};
+  ///
+  /// let schedule = ScheduleComponents::from_calendar(
+  ///     vec![0], vec![9], (1..32).collect(), &[Month::Jul, Month::Jan],
+  ///     &Weekday::range(Weekday::Sat..=Weekday::Sun).collect::<Vec<Weekday>>())
+  ///   .unwrap();
+  /// assert_eq!(schedule.to_expression(), "0 9 * 1,7 0,6");
+  /// assert_eq!(schedule.typed_months(), [Month::Jan, Month::Jul]);
+  /// assert_eq!(schedule.typed_weekdays(), [Weekday::Sun, Weekday::Sat]);
+  /// ```
+  pub fn from_calendar(minutes: Vec<u32>,
+                       hours: Vec<u32>,
+                       days: Vec<u32>,
+                       months: &[Month],
+                       weekdays: &[Weekday])
+      -> Result<ScheduleComponents, CrontabError> {
+    ScheduleComponents::from_unordered(
+      minutes,
+      hours,
+      days,
+      months.iter().map(|month| month.number()).collect(),
+      weekdays.iter().map(|weekday| weekday.number()).collect())
+  }
+
+  /// Seconds in the schedule, or `None` when it only fires at second 0 of
+  /// each minute, as cron does.
+  /// Range [0,59] inclusive.
+  pub fn seconds(&self) -> Option<&[u32]> {
+    self.seconds.as_deref()
+  }
+
   /// Minutes in the schedule.
   /// Range [0,59] inclusive.
-  pub minutes: Vec<u32>,
+  pub fn minutes(&self) -> &[u32] {
+    &self.minutes
+  }
 
   /// Hours in the schedule.
   /// Range [0,23] inclusive.
-  pub hours: Vec<u32>,
+  pub fn hours(&self) -> &[u32] {
+    &self.hours
+  }
 
   /// Days of the month in the schedule.
   /// Range [1,31] inclusive.
-  pub days: Vec<u32>,
+  pub fn days(&self) -> &[u32] {
+    &self.days
+  }
 
   /// Months in the schedule.
   /// Range [1,12] inclusive.
-  pub months: Vec<u32>,
+  pub fn months(&self) -> &[u32] {
+    &self.months
+  }
+
+  /// Months in the schedule, as `Month`s.
+  pub fn typed_months(&self) -> Vec<Month> {
+    self.months.iter().filter_map(|&month| Month::from_number(month)).collect()
+  }
 
   /// Days of the week in the schedule.
   /// Range [0,6] inclusive.
-  pub weekdays: Vec<u32>,
+  pub fn weekdays(&self) -> &[u32] {
+    &self.weekdays
+  }
+
+  /// Days of the week in the schedule, as `Weekday`s.
+  pub fn typed_weekdays(&self) -> Vec<Weekday> {
+    self.weekdays.iter().filter_map(|&weekday| Weekday::from_number(weekday)).collect()
+  }
+
+  /// Years in the schedule, or `None` when it runs every year.
+  /// Range [1970,2199] inclusive.
+  pub fn years(&self) -> Option<&[u32]> {
+    self.years.as_deref()
+  }
+
+  /// Days picked by their place in the month, such as `L` for the last day.
+  pub fn day_specials(&self) -> &[DaySpecial] {
+    &self.day_specials
+  }
 
-  /// Seconds in the schedule.
-  /// Not yet in use. Do not use.
-  #[deprecated(since="0.2.0", note="Field is never set!")]
-  pub seconds: Vec<u32>,
+  /// Whether a day must match both day fields when both are restricted, as
+  /// in Spring, rather than either of them, as in cron.
+  pub fn both_days(&self) -> bool {
+    self.both_days
+  }
+
+  /// Whether the day of month field picks the date of a time.
+  pub (crate) fn day_of_month_matches(&self, time: &Tm) -> bool {
+    self.days.binary_search(&(time.tm_mday as u32)).is_ok()
+        || self.day_specials.iter()
+            .any(|special| special.is_day_of_month() && special.matches(time))
+  }
+
+  /// Whether the day of week field picks the date of a time.
+  pub (crate) fn day_of_week_matches(&self, time: &Tm) -> bool {
+    self.weekdays.binary_search(&weekday(time)).is_ok()
+        || self.day_specials.iter()
+            .any(|special| !special.is_day_of_month() && special.matches(time))
+  }
+
+  /// Whether a day field is restricted, rather than including every day.
+  pub (crate) fn restricts_days(&self, day_of_month: bool) -> bool {
+    let values = if day_of_month { &self.days } else { &self.weekdays };
+    let all = if day_of_month { 31 } else { 7 };
+    values.len() < all
+        || self.day_specials.iter().any(|special| special.is_day_of_month() == day_of_month)
+  }
+
+  /// The components split into one that picks days by the day of month and
+  /// one that picks them by the day of week. Together they fire when a
+  /// schedule that matches either day field does.
+  pub (crate) fn split_days(&self) -> (ScheduleComponents, ScheduleComponents) {
+    let (month_specials, week_specials) = self.day_specials.iter().copied()
+        .partition(|special| special.is_day_of_month());
+
+    let by_day_of_month = ScheduleComponents {
+      weekdays: (0 .. 7).collect(),
+      day_specials: month_specials,
+      both_days: false,
+      ..self.clone()
+    };
+    let by_day_of_week = ScheduleComponents {
+      days: (1 .. 32).collect(),
+      day_specials: week_specials,
+      both_days: false,
+      ..self.clone()
+    };
+    (by_day_of_month, by_day_of_week)
+  }
+
+  /// Render the components as the shortest equivalent crontab expression.
+  /// Full fields become `*`, steps become `*/15` or `0-30/5`, and runs of
+  /// consecutive values become ranges. Parsing the result yields the same
+  /// components.
+  ///
+  /// ```
+  /// let crontab = crontab::Crontab::parse("0,15,30,45 9,10,11,12 * * *").unwrap();
+  /// assert_eq!(crontab.schedule().to_expression(), "*/15 9-12 * * *");
+  /// ```
+  pub fn to_expression(&self) -> String {
+    format_cron(self)
+  }
 }
 
-pub (crate) fn parse_cron(schedule: &str)
-    -> Result<ScheduleComponents, CrontabError> {
-  let fields : Vec<&str> = schedule.trim()
-      .split_whitespace()
-      .collect();
+/// The default schedule runs every minute, like `* * * * *`.
+impl Default for ScheduleComponents {
+  fn default() -> ScheduleComponents {
+    ScheduleComponents {
+      seconds: None,
+      minutes: (0 .. 60).collect(),
+      hours: (0 .. 24).collect(),
+      days: (1 .. 32).collect(),
+      months: (1 .. 13).collect(),
+      weekdays: (0 .. 7).collect(),
+      years: None,
+      day_specials: Vec::new(),
+      both_days: false,
+    }
+  }
+}
 
-  if fields.len() != 5 {
-    return Err(CrontabError::ErrCronFormat(
-      format!("Invalid format: {}", schedule)));
-  }
-
-  let minutes = parse_field(fields[0], 0, 59)?;
-  let hours = parse_field(fields[1], 0, 23)?;
-  let days = parse_field(fields[2], 1, 31)?;
-  let months = parse_field(fields[3], 1, 12)?;
-  let weekdays = parse_field(fields[4], 0, 6)?;
-
-  Ok(ScheduleComponents {
-    minutes: minutes,
-    hours: hours,
-    days: days,
-    months: months,
-    weekdays: weekdays,
-    seconds: Vec::new(), // FIXME: Implement (though nonstandard).
-  })
+#[cfg(feature = "serde")]
+fn is_false(value: &bool) -> bool {
+  !*value
 }
 
-fn parse_field(field: &str, field_min: u32, field_max: u32)
-    -> Result<Vec<u32>, CrontabError> {
+fn sorted<T: Ord>(mut values: Vec<T>) -> Vec<T> {
+  values.sort();
+  values.dedup();
+  values
+}
 
-  let mut components = HashSet::<u32>::new();
+/// Check that a field's values are non-empty, unique, ordered and within the
+/// field's permitted range.
+fn check_field(name: &str, values: &[u32], field_min: u32, field_max: u32)
+    -> Result<(), CrontabError> {
+  if values.is_empty() {
+    return Err(CrontabError::ErrCronFormat(
+      format!("No values given for {}", name)));
+  }
 
-  for part in field.split(",") {
-    let mut min = field_min;
-    let mut max = field_max;
-    let mut step = 1;
+  if values.windows(2).any(|pair| pair[0] >= pair[1]) {
+    return Err(CrontabError::ErrCronFormat(
+      format!("Values for {} are not unique and ordered: {:?}", name, values)));
+  }
 
-    // stepped, eg. */2 or 1-45/3
-    let stepped : Vec<&str> = part.splitn(2, "/").collect();
+  check_range(values[0], values[values.len() - 1], field_min, field_max)
+}
 
-    // ranges, eg. 1-30
-    let range : Vec<&str> = stepped[0].splitn(2, "-").collect();
+pub (crate) fn parse_cron(schedule: &str)
+    -> Result<ScheduleComponents, CrontabError> {
+  ScheduleComponents::from_expression(&Expression::parse(schedule)?)
+}
 
-    if stepped.len() == 2 {
-      step = stepped[1].parse::<u32>()?;
-    }
+impl ScheduleComponents {
+  /// Evaluate a parsed expression into its schedule components. Names are
+  /// resolved and values checked against the range of their field here.
+  ///
+  /// ```
+  /// use crontab::{Expression, ScheduleComponents};
+  ///
+  /// let expression = Expression::parse("0 9 * * MON-FRI").unwrap();
+  /// let schedule = ScheduleComponents::from_expression(&expression).unwrap();
+  /// assert_eq!(schedule.weekdays(), &[1, 2, 3, 4, 5]);
+  /// ```
+  pub fn from_expression(expression: &Expression)
+      -> Result<ScheduleComponents, CrontabError> {
+    ScheduleComponents::from_dialect_expression(expression, Dialect::Vixie)
+  }
 
-    if range.len() == 2 {
-      min = range[0].parse::<u32>()?;
-      max = range[1].parse::<u32>()?;
-    }
+  /// Evaluate an expression parsed in a dialect, with the dialect's
+  /// numbering and steps.
+  ///
+  /// ```
+  /// use crontab::{Dialect, Expression, ScheduleComponents};
+  ///
+  /// let expression = Expression::parse_dialect("0 9 ? * 2-6 *", Dialect::Aws).unwrap();
+  /// let schedule = ScheduleComponents::from_dialect_expression(&expression, Dialect::Aws)
+  ///   .unwrap();
+  /// assert_eq!(schedule.weekdays(), &[1, 2, 3, 4, 5]);
+  /// ```
+  pub fn from_dialect_expression(expression: &Expression, dialect: Dialect)
+      -> Result<ScheduleComponents, CrontabError> {
+    evaluate(expression, dialect).map_err(|(error, _, _)| error)
+  }
+}
 
-    if stepped.len() == 1 && range.len() == 1 && part != "*" {
-      min = part.parse::<u32>()?;
-      max = min;
+/// Evaluate a parsed expression, locating any error in its field. The result
+/// is checked like components built by hand, so it's never left with an
+/// empty field.
+pub (crate) fn evaluate(expression: &Expression, dialect: Dialect)
+    -> Result<ScheduleComponents, (CrontabError, Span, Option<FieldKind>)> {
+  let fields = match expression.kind {
+    ExpressionKind::Macro(m) => {
+      return Ok(parse_cron(m.expansion()).expect("macros expand to valid expressions"));
+    },
+    ExpressionKind::Fields(ref fields) => fields,
+  };
+
+  let mut schedule = ScheduleComponents::default();
+
+  for field in fields {
+    let (values, specials) = field_values(field, dialect)
+        .map_err(|(error, span)| (error, span, Some(field.kind)))?;
+    schedule.day_specials.extend(specials);
+
+    match field.kind {
+      FieldKind::Second => schedule.seconds = Some(values).filter(|values| *values != [0]),
+      FieldKind::Minute => schedule.minutes = values,
+      FieldKind::Hour => schedule.hours = values,
+      FieldKind::DayOfMonth => schedule.days = values,
+      FieldKind::Month => schedule.months = values,
+      FieldKind::DayOfWeek => schedule.weekdays = values,
+      FieldKind::Year => {
+        let all = FieldKind::Year.max() - FieldKind::Year.min() + 1;
+        if values.len() as u32 != all {
+          schedule.years = Some(values);
+        }
+      },
     }
+  }
 
-    if min < field_min {
-      return Err(CrontabError::FieldOutsideRange {
-        description: format!("Value {} is less than minimum: {}", min, field_min)
-      });
-    }
+  schedule.day_specials.sort();
+  schedule.day_specials.dedup();
+  schedule.both_days = dialect.both_days()
+      && schedule.restricts_days(true)
+      && schedule.restricts_days(false);
+  schedule.checked().map_err(|error| (error, expression.span, None))
+}
 
-    if max > field_max {
-      return Err(CrontabError::FieldOutsideRange {
-        description: format!("Value {} is greater than maximum: {}", max, field_max)
-      });
+/// The unique and ordered values selected by a field, in the numbering of
+/// schedule components, and the days it picks by their place in the month.
+fn field_values(field: &Field, dialect: Dialect)
+    -> Located<(Vec<u32>, Vec<DaySpecial>)> {
+  let (field_min, field_max) = dialect.range(field.kind);
+  let mut components = HashSet::<u32>::new();
+  let mut specials = Vec::new();
+
+  for item in &field.items {
+    if let Some(special) = day_special(item, field.kind, dialect)? {
+      specials.push(special);
+      continue;
     }
 
-    let values = (min .. max + 1).filter(|i| i % step == 0)
-        .collect::<Vec<u32>>();
+    let (min, max, step) = item_bounds(item, field.kind, dialect)?;
+
+    check_range(min.min(max), min.max(max), field_min, field_max)
+        .map_err(|error| (error, item.span))?;
+
+    // Ranges of months and days of the week wrap around the end of the year
+    // and week, as `Month::range` and `Weekday::range` do, eg. `NOV-FEB`.
+    let wraps = field.kind == FieldKind::Month || field.kind == FieldKind::DayOfWeek;
+    if min > max && !wraps {
+      let mut description = format!("Range {} runs backwards", item);
+      if step == 1 {
+        let range = |start: u32, end: u32| {
+          if start == end { start.to_string() } else { format!("{}-{}", start, end) }
+        };
+        description.push_str(&format!("; write {},{} to wrap around",
+                                      range(min, field_max), range(field_min, max)));
+      }
+      return Err((CrontabError::FieldOutsideRange { description }, item.span));
+    }
 
-    components.extend(values);
+    let values : Vec<u32> = if min > max {
+      (min .. field_max + 1).chain(field_min .. max + 1).collect()
+    } else {
+      (min .. max + 1).collect()
+    };
+    components.extend(values.into_iter().step_by(step as usize)
+        .map(|value| dialect.schedule_value(field.kind, value)));
   }
 
-  let mut components : Vec<u32> = Vec::from_iter(components.into_iter());
+  let mut components : Vec<u32> = Vec::from_iter(components);
   components.sort();
 
-  Ok(components)
+  Ok((components, specials))
 }
 
+/// The day an `L`, `W` or `#` item picks, or `None` for other items.
+fn day_special(item: &Item, kind: FieldKind, dialect: Dialect)
+    -> Located<Option<DaySpecial>> {
+  let number = |atom: &Atom| atom_value(atom, kind, dialect);
+  let weekday = |atom: &Atom| {
+    let (min, max) = dialect.range(FieldKind::DayOfWeek);
+    let value = number(atom)?;
+    check_range(value, value, min, max).map_err(|error| (error, atom.span))?;
+    Ok(dialect.schedule_value(FieldKind::DayOfWeek, value))
+  };
+
+  let special = match item.kind {
+    ItemKind::Special(Special::Last) if kind == FieldKind::DayOfMonth => {
+      DaySpecial::LastDay(0)
+    },
+    ItemKind::Special(Special::LastBusinessDay) => DaySpecial::LastBusinessDay,
+    ItemKind::LastDayOffset(ref offset) => DaySpecial::LastDay(number(offset)?),
+    ItemKind::NearestBusinessDay(ref day) => DaySpecial::NearestBusinessDay(number(day)?),
+    ItemKind::Last(ref day) => DaySpecial::Last(weekday(day)?),
+    ItemKind::Nth(ref day, ref n) => DaySpecial::Nth(weekday(day)?, number(n)?),
+    _ => return Ok(None),
+  };
+
+  special.check().map_err(|error| (error, item.span))?;
+  Ok(Some(special))
+}
+
+/// The inclusive range and step selected by an item, as written in the
+/// dialect.
+pub (crate) fn item_bounds(item: &Item, kind: FieldKind, dialect: Dialect)
+    -> Located<(u32, u32, u32)> {
+  let (field_min, field_max) = dialect.range(kind);
+
+  match item.kind {
+    ItemKind::Special(Special::Any)
+        | ItemKind::Special(Special::NoSpecificValue) => Ok((field_min, field_max, 1)),
+    // Saturday, as a day of the week.
+    ItemKind::Special(Special::Last) => Ok((field_max, field_max, 1)),
+    ItemKind::Value(ref atom) => {
+      let value = atom_value(atom, kind, dialect)?;
+      Ok((value, value, 1))
+    },
+    ItemKind::Range(ref start, ref end) => {
+      Ok((atom_value(start, kind, dialect)?, atom_value(end, kind, dialect)?, 1))
+    },
+    ItemKind::Step(ref base, ref step_atom) => {
+      let step = atom_value(step_atom, kind, dialect)?;
+      if step == 0 {
+        let error = CrontabError::ErrCronFormat(
+          format!("Step must be greater than zero: {}", item));
+        return Err((error, step_atom.span));
+      }
+
+      let (min, max, _) = item_bounds(base, kind, dialect)?;
+      match base.kind {
+        // A single value steps up to the end of the field, eg. "5/15".
+        ItemKind::Value(_) => Ok((min, field_max, step)),
+        _ => Ok((min, max, step)),
+      }
+    },
+    ItemKind::Special(Special::LastBusinessDay)
+        | ItemKind::LastDayOffset(_)
+        | ItemKind::NearestBusinessDay(_)
+        | ItemKind::Last(_)
+        | ItemKind::Nth(_, _) => unreachable!("days by place are evaluated separately"),
+  }
+}
+
+/// The number of an atom, resolving month and weekday names.
+fn atom_value(atom: &Atom, kind: FieldKind, dialect: Dialect) -> Located<u32> {
+  match atom.kind {
+    AtomKind::Number(number) => Ok(number),
+    AtomKind::Name(ref name) => {
+      let (number, names) : (Option<u32>, Vec<&str>) = match kind {
+        FieldKind::Month => (
+          Month::from_abbreviation(name).map(Month::number),
+          Month::iter().map(Month::abbreviation).collect(),
+        ),
+        FieldKind::DayOfWeek => (
+          Weekday::from_abbreviation(name)
+              .map(|weekday| dialect.written_value(kind, weekday.number())),
+          Weekday::iter().map(Weekday::abbreviation).collect(),
+        ),
+        _ => (None, Vec::new()),
+      };
+
+      number.ok_or_else(|| {
+        let error = CrontabError::UnknownName {
+          name: name.clone(),
+          field: Some(kind),
+          span: atom.span,
+          suggestion: suggest(name, &names).map(|name| name.to_string()),
+        };
+        (error, atom.span)
+      })
+    },
+  }
+}
+
+/// Check that the range `[min, max]` is within the field's permitted range.
+pub (crate) fn check_range(min: u32, max: u32, field_min: u32, field_max: u32)
+    -> Result<(), CrontabError> {
+  if min < field_min {
+    return Err(CrontabError::FieldOutsideRange {
+      description: format!("Value {} is less than minimum: {}", min, field_min)
+    });
+  }
+
+  if max > field_max {
+    return Err(CrontabError::FieldOutsideRange {
+      description: format!("Value {} is greater than maximum: {}", max, field_max)
+    });
+  }
+
+  Ok(())
+}
 
 #[cfg(test)]
 mod tests {
   use super::*;
   use expectest::prelude::*;
+  use test_helpers::get_tm;
 
   #[test]
   fn parse_fields() {
@@ -139,121 +742,199 @@ mod tests {
     expect!(parse_cron("\n\t*\n\t*\n\t*\n\t*\n\t*\n\t")).to(be_ok());
     expect!(parse_cron("    *    *    *    *    *    ")).to(be_ok());
     expect!(parse_cron("\r\r*\r\n*\t\t*\t\t*\n  *\n\r")).to(be_ok());
+
+    // Whitespace around the expression is trimmed.
+    expect!(parse_cron(" \t0 9 * * 1-5\n").unwrap())
+        .to(be_equal_to(parse_cron("0 9 * * 1-5").unwrap()));
   }
 
   #[test]
   fn wildcards() {
     let parsed = parse_cron("* * * * *").unwrap();
 
-    expect!(parsed.minutes).to(be_equal_to((0..60).collect::<Vec<u32>>()));
-    expect!(parsed.hours).to(be_equal_to((0..24).collect::<Vec<u32>>()));
-    expect!(parsed.days).to(be_equal_to((1..32).collect::<Vec<u32>>()));
-    expect!(parsed.months).to(be_equal_to((1..13).collect::<Vec<u32>>()));
-    expect!(parsed.weekdays).to(be_equal_to((0..7).collect::<Vec<u32>>()));
+    expect!(parsed.minutes().to_vec()).to(be_equal_to((0..60).collect::<Vec<u32>>()));
+    expect!(parsed.hours().to_vec()).to(be_equal_to((0..24).collect::<Vec<u32>>()));
+    expect!(parsed.days().to_vec()).to(be_equal_to((1..32).collect::<Vec<u32>>()));
+    expect!(parsed.months().to_vec()).to(be_equal_to((1..13).collect::<Vec<u32>>()));
+    expect!(parsed.weekdays().to_vec()).to(be_equal_to((0..7).collect::<Vec<u32>>()));
   }
 
   #[test]
   fn ranges() {
     let parsed = parse_cron("0-5 20-23 1-5 1-6 0-6").unwrap();
 
-    expect!(parsed.minutes).to(be_equal_to(vec![0,1,2,3,4,5]));
-    expect!(parsed.hours).to(be_equal_to(vec![20,21,22,23]));
-    expect!(parsed.days).to(be_equal_to(vec![1,2,3,4,5]));
-    expect!(parsed.months).to(be_equal_to(vec![1,2,3,4,5,6]));
-    expect!(parsed.weekdays).to(be_equal_to(vec![0,1,2,3,4,5,6]));
+    expect!(parsed.minutes()).to(be_equal_to(&[0,1,2,3,4,5][..]));
+    expect!(parsed.hours()).to(be_equal_to(&[20,21,22,23][..]));
+    expect!(parsed.days()).to(be_equal_to(&[1,2,3,4,5][..]));
+    expect!(parsed.months()).to(be_equal_to(&[1,2,3,4,5,6][..]));
+    expect!(parsed.weekdays()).to(be_equal_to(&[0,1,2,3,4,5,6][..]));
   }
 
   #[test]
   fn step() {
     let parsed = parse_cron("*/15 */4 */10 */3 */2").unwrap();
 
-    expect!(parsed.minutes).to(be_equal_to(vec![0,15,30,45]));
-    expect!(parsed.hours).to(be_equal_to(vec![0,4,8,12,16,20]));
-    expect!(parsed.days).to(be_equal_to(vec![10,20,30]));
-    expect!(parsed.months).to(be_equal_to(vec![3,6,9,12]));
-    expect!(parsed.weekdays).to(be_equal_to(vec![0,2,4,6]));
+    expect!(parsed.minutes()).to(be_equal_to(&[0,15,30,45][..]));
+    expect!(parsed.hours()).to(be_equal_to(&[0,4,8,12,16,20][..]));
+    expect!(parsed.days()).to(be_equal_to(&[1,11,21,31][..]));
+    expect!(parsed.months()).to(be_equal_to(&[1,4,7,10][..]));
+    expect!(parsed.weekdays()).to(be_equal_to(&[0,2,4,6][..]));
   }
 
   #[test]
   fn ranges_with_step() {
     let parsed = parse_cron("0-30/5 0-12/2 1-20/5 1-10/2 0-5/2").unwrap();
 
-    expect!(parsed.minutes).to(be_equal_to(vec![0,5,10,15,20,25,30]));
-    expect!(parsed.hours).to(be_equal_to(vec![0,2,4,6,8,10,12]));
-    expect!(parsed.days).to(be_equal_to(vec![5,10,15,20]));
-    expect!(parsed.months).to(be_equal_to(vec![2,4,6,8,10]));
-    expect!(parsed.weekdays).to(be_equal_to(vec![0,2,4]));
+    expect!(parsed.minutes()).to(be_equal_to(&[0,5,10,15,20,25,30][..]));
+    expect!(parsed.hours()).to(be_equal_to(&[0,2,4,6,8,10,12][..]));
+    expect!(parsed.days()).to(be_equal_to(&[1,6,11,16][..]));
+    expect!(parsed.months()).to(be_equal_to(&[1,3,5,7,9][..]));
+    expect!(parsed.weekdays()).to(be_equal_to(&[0,2,4][..]));
   }
 
   #[test]
   fn comma_separated() {
     let parsed = parse_cron("0,5,15 0,12 1,15 1,3,6,9,12 0,1,2,3,4").unwrap();
 
-    expect!(parsed.minutes).to(be_equal_to(vec![0,5,15]));
-    expect!(parsed.hours).to(be_equal_to(vec![0,12]));
-    expect!(parsed.days).to(be_equal_to(vec![1,15]));
-    expect!(parsed.months).to(be_equal_to(vec![1,3,6,9,12]));
-    expect!(parsed.weekdays).to(be_equal_to(vec![0,1,2,3,4]));
+    expect!(parsed.minutes()).to(be_equal_to(&[0,5,15][..]));
+    expect!(parsed.hours()).to(be_equal_to(&[0,12][..]));
+    expect!(parsed.days()).to(be_equal_to(&[1,15][..]));
+    expect!(parsed.months()).to(be_equal_to(&[1,3,6,9,12][..]));
+    expect!(parsed.weekdays()).to(be_equal_to(&[0,1,2,3,4][..]));
   }
 
   #[test]
   fn exact_minutes() {
     let parsed = parse_cron("0 * * * *").unwrap();
-    expect!(parsed.minutes).to(be_equal_to(vec![0]));
+    expect!(parsed.minutes()).to(be_equal_to(&[0][..]));
 
     let parsed = parse_cron("5,10,15 * * * *").unwrap();
-    expect!(parsed.minutes).to(be_equal_to(vec![5,10,15]));
+    expect!(parsed.minutes()).to(be_equal_to(&[5,10,15][..]));
 
     let parsed = parse_cron("59 * * * *").unwrap();
-    expect!(parsed.minutes).to(be_equal_to(vec![59]));
+    expect!(parsed.minutes()).to(be_equal_to(&[59][..]));
   }
 
   #[test]
   fn exact_hours() {
     let parsed = parse_cron("* 0 * * *").unwrap();
-    expect!(parsed.hours).to(be_equal_to(vec![0]));
+    expect!(parsed.hours()).to(be_equal_to(&[0][..]));
 
     let parsed = parse_cron("* 1,12,20 * * *").unwrap();
-    expect!(parsed.hours).to(be_equal_to(vec![1, 12, 20]));
+    expect!(parsed.hours()).to(be_equal_to(&[1, 12, 20][..]));
 
     let parsed = parse_cron("* 23 * * *").unwrap();
-    expect!(parsed.hours).to(be_equal_to(vec![23]));
+    expect!(parsed.hours()).to(be_equal_to(&[23][..]));
   }
 
   #[test]
   fn exact_days() {
     let parsed = parse_cron("* * 1 * *").unwrap();
-    expect!(parsed.days).to(be_equal_to(vec![1]));
+    expect!(parsed.days()).to(be_equal_to(&[1][..]));
 
     let parsed = parse_cron("* * 1,10,20,30 * *").unwrap();
-    expect!(parsed.days).to(be_equal_to(vec![1, 10, 20, 30]));
+    expect!(parsed.days()).to(be_equal_to(&[1, 10, 20, 30][..]));
 
     let parsed = parse_cron("* * 31 * *").unwrap();
-    expect!(parsed.days).to(be_equal_to(vec![31]));
+    expect!(parsed.days()).to(be_equal_to(&[31][..]));
   }
 
   #[test]
   fn exact_months() {
     let parsed = parse_cron("* * * 1 *").unwrap();
-    expect!(parsed.months).to(be_equal_to(vec![1]));
+    expect!(parsed.months()).to(be_equal_to(&[1][..]));
 
     let parsed = parse_cron("* * * 1,5,7,10 *").unwrap();
-    expect!(parsed.months).to(be_equal_to(vec![1, 5, 7, 10]));
+    expect!(parsed.months()).to(be_equal_to(&[1, 5, 7, 10][..]));
 
     let parsed = parse_cron("* * * 12 *").unwrap();
-    expect!(parsed.months).to(be_equal_to(vec![12]));
+    expect!(parsed.months()).to(be_equal_to(&[12][..]));
   }
 
   #[test]
   fn exact_weekdays() {
     let parsed = parse_cron("* * * * 0").unwrap();
-    expect!(parsed.weekdays).to(be_equal_to(vec![0]));
+    expect!(parsed.weekdays()).to(be_equal_to(&[0][..]));
 
     let parsed = parse_cron("* * * * 1,2").unwrap();
-    expect!(parsed.weekdays).to(be_equal_to(vec![1, 2]));
+    expect!(parsed.weekdays()).to(be_equal_to(&[1, 2][..]));
 
     let parsed = parse_cron("* * * * 6").unwrap();
-    expect!(parsed.weekdays).to(be_equal_to(vec![6]));
+    expect!(parsed.weekdays()).to(be_equal_to(&[6][..]));
+  }
+
+  #[test]
+  fn names() {
+    let parsed = parse_cron("* * * JAN,jun-Aug MON-FRI").unwrap();
+    expect!(parsed.months()).to(be_equal_to(&[1, 6, 7, 8][..]));
+    expect!(parsed.weekdays()).to(be_equal_to(&[1, 2, 3, 4, 5][..]));
+
+    let parsed = parse_cron("* * * * sun,sat").unwrap();
+    expect!(parsed.weekdays()).to(be_equal_to(&[0, 6][..]));
+
+    // Names only apply to their own field, and must be abbreviations.
+    expect!(parse_cron("* * * MON *")).to(be_err());
+    expect!(parse_cron("* * * * JAN")).to(be_err());
+    expect!(parse_cron("JAN * * * *")).to(be_err());
+    expect!(parse_cron("* * * * MONDAY")).to(be_err());
+    expect!(parse_cron("* * * * MNO-FRI")).to(be_err());
+  }
+
+  #[test]
+  fn wrapping_ranges() {
+    let parsed = parse_cron("0 0 * NOV-FEB SAT-SUN").unwrap();
+    expect!(parsed.months()).to(be_equal_to(&[1, 2, 11, 12][..]));
+    expect!(parsed.weekdays()).to(be_equal_to(&[0, 6][..]));
+
+    let parsed = parse_cron("0 0 * 11-2/2 5-1").unwrap();
+    expect!(parsed.months()).to(be_equal_to(&[1, 11][..]));
+    expect!(parsed.weekdays()).to(be_equal_to(&[0, 1, 5, 6][..]));
+
+    // Quartz numbers Sunday 1 and Saturday 7.
+    let expression = Expression::parse_dialect("0 0 0 ? * SAT-SUN", Dialect::Quartz).unwrap();
+    let parsed = ScheduleComponents::from_dialect_expression(&expression, Dialect::Quartz)
+        .unwrap();
+    expect!(parsed.weekdays()).to(be_equal_to(&[0, 6][..]));
+
+    expect!(parse_cron("* * * 13-2 *")).to(be_err());
+  }
+
+  #[test]
+  fn backwards_ranges() {
+    // Only months and days of the week wrap.
+    expect!(parse_cron("30-5 * * * *")).to(be_err());
+    expect!(parse_cron("0 22-2 * * *")).to(be_err());
+    expect!(parse_cron("0 0 31-1 4 *")).to(be_err());
+    expect!(parse_cron("30-5/5 * * * *")).to(be_err());
+
+    let error = parse_cron("0 0 31-1 * *").unwrap_err();
+    expect!(error.to_string())
+        .to(be_equal_to("Range 31-1 runs backwards; write 31,1 to wrap around".to_string()));
+  }
+
+  #[test]
+  fn name_suggestions() {
+    let suggestion = |schedule: &str| {
+      parse_cron(schedule).unwrap_err().suggestion().map(|s| s.to_string())
+    };
+
+    expect!(suggestion("0 9 * * MNO-FRI")).to(be_some().value("MON".to_string()));
+    expect!(suggestion("0 9 * Jnu *")).to(be_some().value("JUN".to_string()));
+    expect!(suggestion("0 9 * * Monday")).to(be_some().value("MON".to_string()));
+    expect!(suggestion("@dialy")).to(be_some().value("@daily".to_string()));
+    expect!(suggestion("0 9 * * XYZ")).to(be_none());
+    // Names in the wrong field aren't suggested.
+    expect!(suggestion("0 MON * * *")).to(be_none());
+
+    match parse_cron("0 9 * * MON-FIR").unwrap_err() {
+      CrontabError::UnknownName { name, field, span, suggestion } => {
+        expect!(name).to(be_equal_to("FIR".to_string()));
+        expect!(field).to(be_some().value(FieldKind::DayOfWeek));
+        expect!(span).to(be_equal_to(Span::new(12, 15)));
+        expect!(suggestion).to(be_some().value("FRI".to_string()));
+      },
+      err => panic!("Unexpected error: {:?}", err),
+    }
   }
 
   #[test]
@@ -301,19 +982,81 @@ mod tests {
   #[test]
   fn values_deduped() {
     let parsed = parse_cron("1,1,1,1 * * * *").unwrap();
-    expect!(parsed.minutes).to(be_equal_to(vec![1]));
+    expect!(parsed.minutes()).to(be_equal_to(&[1][..]));
 
     let parsed = parse_cron("1,1-3 * * * *").unwrap();
-    expect!(parsed.minutes).to(be_equal_to(vec![1,2,3]));
+    expect!(parsed.minutes()).to(be_equal_to(&[1,2,3][..]));
 
     let parsed = parse_cron("* * * 1-4,2,4,*/2 *").unwrap();
-    expect!(parsed.months).to(be_equal_to(vec![1, 2, 3, 4, 6, 8, 10, 12]));
+    expect!(parsed.months()).to(be_equal_to(&[1, 2, 3, 4, 5, 7, 9, 11][..]));
   }
 
   #[test]
   fn values_in_order() {
     let parsed = parse_cron("4,3,1,2 * * * *").unwrap();
-    expect!(parsed.minutes).to(be_equal_to(vec![1,2,3,4]));
+    expect!(parsed.minutes()).to(be_equal_to(&[1,2,3,4][..]));
+  }
+
+  #[test]
+  fn new_components() {
+    let all = |min: u32, max: u32| (min .. max + 1).collect::<Vec<u32>>();
+
+    let schedule = ScheduleComponents::new(
+      vec![0, 30], vec![12], all(1, 31), all(1, 12), all(0, 6)).unwrap();
+    expect!(schedule.minutes()).to(be_equal_to(&[0, 30][..]));
+    expect!(schedule).to(be_equal_to(parse_cron("0,30 12 * * *").unwrap()));
+
+    expect!(ScheduleComponents::default())
+        .to(be_equal_to(parse_cron("* * * * *").unwrap()));
+
+    // Empty
+    expect!(ScheduleComponents::new(
+      vec![], vec![0], vec![1], vec![1], vec![0])).to(be_err());
+    // Unordered or duplicated
+    expect!(ScheduleComponents::new(
+      vec![30, 0], vec![0], vec![1], vec![1], vec![0])).to(be_err());
+    expect!(ScheduleComponents::new(
+      vec![0], vec![0, 0], vec![1], vec![1], vec![0])).to(be_err());
+    // Outside range
+    expect!(ScheduleComponents::new(
+      vec![0], vec![24], vec![1], vec![1], vec![0])).to(be_err());
+    expect!(ScheduleComponents::new(
+      vec![0], vec![0], vec![0], vec![1], vec![0])).to(be_err());
+    expect!(ScheduleComponents::new(
+      vec![0], vec![0], vec![1], vec![13], vec![0])).to(be_err());
+    expect!(ScheduleComponents::new(
+      vec![0], vec![0], vec![1], vec![1], vec![7])).to(be_err());
+  }
+
+  #[test]
+  fn unordered_components() {
+    let schedule = ScheduleComponents::from_unordered(
+      vec![30, 0, 30], vec![12], vec![15, 1], vec![1], vec![0]).unwrap();
+    expect!(schedule.minutes()).to(be_equal_to(&[0, 30][..]));
+    expect!(schedule.days()).to(be_equal_to(&[1, 15][..]));
+
+    expect!(ScheduleComponents::from_unordered(
+      vec![], vec![0], vec![1], vec![1], vec![0])).to(be_err());
+    expect!(ScheduleComponents::from_unordered(
+      vec![0, 60], vec![0], vec![1], vec![1], vec![0])).to(be_err());
+  }
+
+  #[test]
+  fn macros() {
+    expect!(parse_cron("@yearly").unwrap()).to(be_equal_to(parse_cron("0 0 1 1 *").unwrap()));
+    expect!(parse_cron("@annually").unwrap()).to(be_equal_to(parse_cron("0 0 1 1 *").unwrap()));
+    expect!(parse_cron("@monthly").unwrap()).to(be_equal_to(parse_cron("0 0 1 * *").unwrap()));
+    expect!(parse_cron("@weekly").unwrap()).to(be_equal_to(parse_cron("0 0 * * 0").unwrap()));
+    expect!(parse_cron("@daily").unwrap()).to(be_equal_to(parse_cron("0 0 * * *").unwrap()));
+    expect!(parse_cron("@midnight").unwrap()).to(be_equal_to(parse_cron("0 0 * * *").unwrap()));
+    expect!(parse_cron("@hourly").unwrap()).to(be_equal_to(parse_cron("0 * * * *").unwrap()));
+    expect!(parse_cron("@reboot")).to(be_err());
+  }
+
+  #[test]
+  fn value_with_step() {
+    let parsed = parse_cron("5/15 * * * *").unwrap();
+    expect!(parsed.minutes()).to(be_equal_to(&[5, 20, 35, 50][..]));
   }
 
   #[test]
@@ -333,6 +1076,9 @@ mod tests {
     expect!(parse_cron(",-,- * * * *")).to(be_err());
     expect!(parse_cron(",-,-, * * * *")).to(be_err());
 
+    // Zero step
+    expect!(parse_cron("*/0 * * * *")).to(be_err());
+
     // Allowed whitespace, but incorrect number of fields
     expect!(parse_cron("   ")).to(be_err());
     expect!(parse_cron("  * * * *  ")).to(be_err());
@@ -341,4 +1087,86 @@ mod tests {
     expect!(parse_cron("\n\t* * * *\n\t")).to(be_err());
     expect!(parse_cron("\n\t* * * * * *\n\t")).to(be_err());
   }
+
+  #[test]
+  fn aws() {
+    let aws = |source: &str| {
+      let expression = Expression::parse_dialect(source, Dialect::Aws).unwrap();
+      ScheduleComponents::from_dialect_expression(&expression, Dialect::Aws).unwrap()
+    };
+
+    // Sunday is 1, and steps count from the start of their range.
+    let schedule = aws("5/20 */10 ? * SUN,2-3 2030-2032");
+    expect!(schedule.minutes()).to(be_equal_to(&[5, 25, 45][..]));
+    expect!(schedule.hours()).to(be_equal_to(&[0, 10, 20][..]));
+    expect!(schedule.weekdays()).to(be_equal_to(&[0, 1, 2][..]));
+    expect!(schedule.years()).to(be_some().value(&[2030, 2031, 2032][..]));
+    expect!(aws("0 0 ? * * *").years()).to(be_none());
+
+    let schedule = aws("0 0 1,L,15W * ? *");
+    expect!(schedule.days()).to(be_equal_to(&[1][..]));
+    expect!(schedule.day_specials()).to(be_equal_to(
+      &[DaySpecial::LastDay(0), DaySpecial::NearestBusinessDay(15)][..]));
+
+    let schedule = aws("0 0 ? * 6L,MON#2 *");
+    expect!(schedule.weekdays().is_empty()).to(be_true());
+    expect!(schedule.day_specials()).to(be_equal_to(
+      &[DaySpecial::Last(5), DaySpecial::Nth(1, 2)][..]));
+
+    let expression = Expression::parse_dialect("0 0 ? * 0 *", Dialect::Aws).unwrap();
+    expect!(ScheduleComponents::from_dialect_expression(&expression, Dialect::Aws)).to(be_err());
+    let expression = Expression::parse_dialect("0 0 32W * ? *", Dialect::Aws).unwrap();
+    expect!(ScheduleComponents::from_dialect_expression(&expression, Dialect::Aws)).to(be_err());
+    let expression = Expression::parse_dialect("0 0 ? * MON#6 *", Dialect::Aws).unwrap();
+    expect!(ScheduleComponents::from_dialect_expression(&expression, Dialect::Aws)).to(be_err());
+  }
+
+  #[test]
+  fn quartz_and_spring() {
+    let schedule = |source: &str, dialect: Dialect| {
+      let expression = Expression::parse_dialect(source, dialect).unwrap();
+      ScheduleComponents::from_dialect_expression(&expression, dialect)
+    };
+    let quartz = |source: &str| schedule(source, Dialect::Quartz).unwrap();
+    let spring = |source: &str| schedule(source, Dialect::Spring).unwrap();
+
+    // Quartz's Sunday is 1, Spring's is 0 or 7.
+    expect!(quartz("0 0 0 ? * 1,7").weekdays()).to(be_equal_to(&[0, 6][..]));
+    expect!(quartz("0 0 0 ? * SUN-TUE").weekdays()).to(be_equal_to(&[0, 1, 2][..]));
+    expect!(schedule("0 0 0 ? * 0", Dialect::Quartz)).to(be_err());
+    expect!(spring("0 0 0 * * 7").weekdays()).to(be_equal_to(&[0][..]));
+    expect!(spring("0 0 0 * * 5-7").weekdays()).to(be_equal_to(&[0, 5, 6][..]));
+
+    // Only seconds other than 0 are kept, counting from their start.
+    let schedule = quartz("10/15 0 0 ? * * 2030");
+    expect!(schedule.seconds()).to(be_some().value(&[10, 25, 40, 55][..]));
+    expect!(schedule.years()).to(be_some().value(&[2030][..]));
+    expect!(quartz("0 0 0 ? * *").seconds()).to(be_none());
+    expect!(spring("@hourly")).to(be_equal_to(parse_cron("0 * * * *").unwrap()));
+
+    // Spring needs both day fields to match, when both are restricted.
+    expect!(spring("0 0 0 13 * FRI").both_days()).to(be_true());
+    expect!(spring("0 0 0 13 * ?").both_days()).to(be_false());
+  }
+
+  #[test]
+  fn day_specials_match() {
+    // Tuesday 2017-10-31, the last day of the month.
+    let tm = get_tm(2017, 10, 31, 0, 0, 0);
+    expect!(DaySpecial::LastDay(0).matches(&tm)).to(be_true());
+    expect!(DaySpecial::LastDay(1).matches(&tm)).to(be_false());
+    expect!(DaySpecial::LastBusinessDay.matches(&tm)).to(be_true());
+    expect!(DaySpecial::Last(2).matches(&tm)).to(be_true());
+    expect!(DaySpecial::Nth(2, 5).matches(&tm)).to(be_true());
+    expect!(DaySpecial::Nth(2, 4).matches(&tm)).to(be_false());
+
+    // The 15th of October 2017 is a Sunday, so 15W is Monday the 16th.
+    expect!(DaySpecial::NearestBusinessDay(15).matches(&get_tm(2017, 10, 16, 0, 0, 0)))
+        .to(be_true());
+    expect!(DaySpecial::NearestBusinessDay(15).matches(&get_tm(2017, 10, 15, 0, 0, 0)))
+        .to(be_false());
+    // 1W never crosses into the month before: Sunday 2017-10-01 gives Monday the 2nd.
+    expect!(DaySpecial::NearestBusinessDay(1).matches(&get_tm(2017, 10, 2, 0, 0, 0)))
+        .to(be_true());
+  }
 }
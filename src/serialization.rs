@@ -0,0 +1,346 @@
+//! Serde support, enabled with the `serde` feature.
+
+use crontab::Crontab;
+use dialect::Dialect;
+use error::CrontabError;
+use parsing::{DaySpecial, ScheduleComponents};
+use serde::{Deserialize, Serialize};
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::ser::{SerializeStruct, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// A `Crontab` serializes as its schedule expression, exactly as it was
+/// written. Expressions in dialects other than Vixie cron serialize as an
+/// object with the name of the dialect, eg.
+/// `{"dialect":"aws","expression":"cron(0 12 ? * MON-FRI *)"}`, or, in
+/// formats that aren't human readable such as bincode, as a string with the
+/// name of the dialect in front, eg. `aws:cron(0 12 ? * MON-FRI *)`.
+impl Serialize for Crontab {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    if self.dialect() == Dialect::Vixie {
+      return serializer.serialize_str(self.source());
+    }
+    if !serializer.is_human_readable() {
+      return serializer.serialize_str(&compact_source(self));
+    }
+
+    let mut state = serializer.serialize_struct("Crontab", 2)?;
+    state.serialize_field("dialect", self.dialect().name())?;
+    state.serialize_field("expression", self.source())?;
+    state.end()
+  }
+}
+
+/// A `Crontab` deserializes from a schedule expression, which is run
+/// through `Crontab::parse`, or from an object with a dialect and an
+/// expression, which is run through `Crontab::parse_dialect`. Formats that
+/// aren't human readable, such as bincode, don't describe their own data,
+/// so they are read as a string, with the name of any dialect other than
+/// Vixie cron in front.
+impl<'de> Deserialize<'de> for Crontab {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D)
+      -> Result<Crontab, D::Error> {
+    if deserializer.is_human_readable() {
+      deserializer.deserialize_any(CrontabVisitor)
+    } else {
+      deserializer.deserialize_str(CrontabVisitor)
+    }
+  }
+}
+
+/// The string a crontab serializes as when the format isn't human readable.
+fn compact_source(crontab: &Crontab) -> String {
+  match crontab.dialect() {
+    Dialect::Vixie => crontab.source().to_string(),
+    dialect => format!("{}:{}", dialect.name(), crontab.source()),
+  }
+}
+
+fn parse_dialect<E: de::Error>(expression: &str, dialect: Dialect) -> Result<Crontab, E> {
+  Crontab::parse_dialect(expression, dialect).map_err(|err| {
+    E::custom(format!("invalid {} crontab schedule {:?}: {}", dialect, expression, err))
+  })
+}
+
+struct CrontabVisitor;
+
+impl<'de> Visitor<'de> for CrontabVisitor {
+  type Value = Crontab;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "a crontab schedule expression")
+  }
+
+  fn visit_str<E: de::Error>(self, value: &str) -> Result<Crontab, E> {
+    if let Some(colon) = value.find(':') {
+      match Dialect::from_name(&value[.. colon]) {
+        Some(Dialect::Vixie) | None => (),
+        Some(dialect) => return parse_dialect(&value[colon + 1 ..], dialect),
+      }
+    }
+
+    Crontab::parse(value).map_err(|err| {
+      E::custom(format!("invalid crontab schedule {:?}: {}", value, err))
+    })
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Crontab, A::Error> {
+    let mut dialect : Option<String> = None;
+    let mut expression : Option<String> = None;
+
+    while let Some(key) = map.next_key::<String>()? {
+      match key.as_str() {
+        "dialect" => dialect = Some(map.next_value()?),
+        "expression" => expression = Some(map.next_value()?),
+        _ => return Err(de::Error::unknown_field(&key, &["dialect", "expression"])),
+      }
+    }
+
+    let dialect = dialect.ok_or_else(|| de::Error::missing_field("dialect"))?;
+    let expression = expression.ok_or_else(|| de::Error::missing_field("expression"))?;
+    let dialect = Dialect::from_name(&dialect).ok_or_else(|| {
+      de::Error::custom(format!("unknown crontab dialect {:?}", dialect))
+    })?;
+
+    parse_dialect(&expression, dialect)
+  }
+}
+
+/// The unvalidated fields of `ScheduleComponents`, which are checked by
+/// `ScheduleComponents::new` when deserializing.
+#[derive(Deserialize)]
+pub (crate) struct ScheduleFields {
+  #[serde(default)]
+  seconds: Option<Vec<u32>>,
+  minutes: Vec<u32>,
+  hours: Vec<u32>,
+  days: Vec<u32>,
+  months: Vec<u32>,
+  weekdays: Vec<u32>,
+  #[serde(default)]
+  years: Option<Vec<u32>>,
+  #[serde(default)]
+  day_specials: Vec<DaySpecial>,
+  #[serde(default)]
+  both_days: bool,
+}
+
+impl TryFrom<ScheduleFields> for ScheduleComponents {
+  type Error = CrontabError;
+
+  fn try_from(fields: ScheduleFields) -> Result<ScheduleComponents, CrontabError> {
+    let schedule = ScheduleComponents::with_extensions(
+      fields.minutes,
+      fields.hours,
+      fields.days,
+      fields.months,
+      fields.weekdays,
+      fields.years,
+      fields.day_specials)?
+        .with_both_days(fields.both_days);
+    match fields.seconds {
+      // The old `seconds` field was never set, so it's empty.
+      Some(seconds) if !seconds.is_empty() => schedule.with_seconds(seconds),
+      _ => Ok(schedule),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crontab::Crontab;
+  use dialect::Dialect;
+  use expectest::prelude::*;
+  use parsing::ScheduleComponents;
+  use serde::de::{self, value, Deserialize, Deserializer, Visitor};
+  use serde_json;
+
+  /// A string in a format that doesn't describe its own data, like bincode.
+  struct Compact<'a>(&'a str);
+
+  impl<'de, 'a> Deserializer<'de> for Compact<'a> {
+    type Error = value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, value::Error> {
+      Err(de::Error::custom("the format doesn't describe its data"))
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, value::Error> {
+      visitor.visit_str(self.0)
+    }
+
+    fn is_human_readable(&self) -> bool {
+      false
+    }
+
+    serde::forward_to_deserialize_any! {
+      bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char string bytes
+      byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct
+      map struct enum identifier ignored_any
+    }
+  }
+
+  #[test]
+  fn crontab_serializes_as_expression() {
+    let crontab = Crontab::parse("0 0 * * *").unwrap();
+    let json = serde_json::to_string(&crontab).unwrap();
+    expect!(json.as_str()).to(be_equal_to("\"0 0 * * *\""));
+
+    let crontab = Crontab::parse("CRON_TZ=UTC */30 9-17 * * MON-FRI").unwrap();
+    let json = serde_json::to_string(&crontab).unwrap();
+    expect!(json.as_str()).to(be_equal_to("\"CRON_TZ=UTC */30 9-17 * * MON-FRI\""));
+  }
+
+  #[test]
+  fn crontab_deserializes_through_parser() {
+    let crontab : Crontab = serde_json::from_str("\"*/15 0 1,15 * *\"").unwrap();
+    expect!(crontab.schedule().minutes()).to(be_equal_to(&[0, 15, 30, 45][..]));
+    expect!(crontab.schedule().hours()).to(be_equal_to(&[0][..]));
+    expect!(crontab.schedule().days()).to(be_equal_to(&[1, 15][..]));
+  }
+
+  #[test]
+  fn crontab_deserialize_errors() {
+    let result = serde_json::from_str::<Crontab>("\"60 * * * *\"");
+    expect!(result.is_err()).to(be_true());
+    let message = result.err().unwrap().to_string();
+    expect!(message.contains("Value 60 is greater than maximum: 59")).to(be_true());
+
+    expect!(serde_json::from_str::<Crontab>("\"* * *\"").is_err()).to(be_true());
+    expect!(serde_json::from_str::<Crontab>("5").is_err()).to(be_true());
+  }
+
+  #[test]
+  fn dialect_crontabs_round_trip() {
+    let crontab = Crontab::parse_dialect("cron(0 12 ? * MON-FRI *)", Dialect::Aws).unwrap();
+    let json = serde_json::to_string(&crontab).unwrap();
+    expect!(json.as_str()).to(be_equal_to(
+      r#"{"dialect":"aws","expression":"cron(0 12 ? * MON-FRI *)"}"#));
+
+    let deserialized : Crontab = serde_json::from_str(&json).unwrap();
+    expect!(deserialized.dialect()).to(be_equal_to(Dialect::Aws));
+    expect!(deserialized.source()).to(be_equal_to(crontab.source()));
+    expect!(deserialized).to(be_equal_to(crontab));
+
+    let invalid = [
+      r#"{"dialect":"aws","expression":"0 12 * * *"}"#,
+      r#"{"dialect":"jenkins","expression":"H * * * *"}"#,
+      r#"{"expression":"0 12 * * ? *"}"#,
+      r#"{"dialect":"aws","expression":"0 12 * * ? *","zone":"UTC"}"#,
+    ];
+    for json in invalid.iter() {
+      expect!(serde_json::from_str::<Crontab>(json).is_err()).to(be_true());
+    }
+  }
+
+  #[test]
+  fn every_dialect_round_trips() {
+    let crontabs = [
+      ("CRON_TZ=UTC */30 9-17 * * MON-FRI", Dialect::Vixie),
+      ("cron(15 10 L-2,LW * ? 2030)", Dialect::Aws),
+      ("?/20 ? 5/10 ? ?,MON", Dialect::Kubernetes),
+      ("30 0/5 14,18 ? * 6#3 2030", Dialect::Quartz),
+      ("*/30 0 12 13 * FRI", Dialect::Spring),
+    ];
+    for &(source, dialect) in crontabs.iter() {
+      let crontab = Crontab::parse_dialect(source, dialect).unwrap();
+      let json = serde_json::to_string(&crontab).unwrap();
+      let deserialized : Crontab = serde_json::from_str(&json).unwrap();
+
+      expect!(deserialized.dialect()).to(be_equal_to(dialect));
+      expect!(deserialized.source()).to(be_equal_to(source));
+      expect!(deserialized).to(be_equal_to(crontab));
+    }
+  }
+
+  #[test]
+  fn schedule_components_round_trip() {
+    let crontab = Crontab::parse("0 12 * 1,7 1-5").unwrap();
+    let json = serde_json::to_string(crontab.schedule()).unwrap();
+    let components : ScheduleComponents = serde_json::from_str(&json).unwrap();
+
+    expect!(components.minutes()).to(be_equal_to(&[0][..]));
+    expect!(components.hours()).to(be_equal_to(&[12][..]));
+    expect!(components.days().to_vec()).to(be_equal_to((1..32).collect::<Vec<u32>>()));
+    expect!(components.months()).to(be_equal_to(&[1, 7][..]));
+    expect!(components.weekdays()).to(be_equal_to(&[1, 2, 3, 4, 5][..]));
+  }
+
+  #[test]
+  fn schedule_components_deserialize_validated() {
+    let valid = r#"{"minutes":[0],"hours":[12],"days":[1],"months":[1],"weekdays":[0]}"#;
+    expect!(serde_json::from_str::<ScheduleComponents>(valid).is_ok()).to(be_true());
+
+    // Schedules serialized with the old `seconds` field still load.
+    let seconds = r#"{"minutes":[0],"hours":[12],"days":[1],"months":[1],"weekdays":[0],"seconds":[]}"#;
+    expect!(serde_json::from_str::<ScheduleComponents>(seconds).is_ok()).to(be_true());
+
+    let invalid = [
+      r#"{"minutes":[60],"hours":[12],"days":[1],"months":[1],"weekdays":[0]}"#,
+      r#"{"minutes":[],"hours":[12],"days":[1],"months":[1],"weekdays":[0]}"#,
+      r#"{"minutes":[30,0],"hours":[12],"days":[1],"months":[1],"weekdays":[0]}"#,
+    ];
+    for json in invalid.iter() {
+      expect!(serde_json::from_str::<ScheduleComponents>(json).is_err()).to(be_true());
+    }
+  }
+
+  #[test]
+  fn schedule_components_with_years_and_day_specials() {
+    let crontab = Crontab::parse_dialect("0 12 L-2 * ? 2030", Dialect::Aws).unwrap();
+    let json = serde_json::to_string(crontab.schedule()).unwrap();
+    expect!(json.contains(r#""years":[2030]"#)).to(be_true());
+
+    let components : ScheduleComponents = serde_json::from_str(&json).unwrap();
+    expect!(components).to(be_equal_to(crontab.schedule().clone()));
+
+    // Schedules without them leave them out.
+    let json = serde_json::to_string(Crontab::parse("0 12 * * *").unwrap().schedule()).unwrap();
+    expect!(json.contains("years")).to(be_false());
+    expect!(json.contains("day_specials")).to(be_false());
+
+    let invalid = r#"{"minutes":[0],"hours":[12],"days":[1],"months":[1],"weekdays":[0],"years":[1900]}"#;
+    expect!(serde_json::from_str::<ScheduleComponents>(invalid).is_err()).to(be_true());
+  }
+
+  #[test]
+  fn schedule_components_with_seconds_and_both_days() {
+    let crontab = Crontab::parse_dialect("*/30 0 12 13 * FRI", Dialect::Spring).unwrap();
+    let json = serde_json::to_string(crontab.schedule()).unwrap();
+    expect!(json.contains(r#""seconds":[0,30]"#)).to(be_true());
+    expect!(json.contains(r#""both_days":true"#)).to(be_true());
+
+    let components : ScheduleComponents = serde_json::from_str(&json).unwrap();
+    expect!(components).to(be_equal_to(crontab.schedule().clone()));
+
+    let json = serde_json::to_string(Crontab::parse("0 12 * * *").unwrap().schedule()).unwrap();
+    expect!(json.contains("both_days")).to(be_false());
+  }
+
+  #[test]
+  fn crontabs_in_formats_without_self_description() {
+    let crontab = Crontab::deserialize(Compact("*/15 0 1,15 * *")).unwrap();
+    expect!(crontab).to(be_equal_to(Crontab::parse("*/15 0 1,15 * *").unwrap()));
+
+    let crontabs = [
+      ("CRON_TZ=UTC */30 9-17 * * MON-FRI", Dialect::Vixie),
+      ("cron(15 10 L-2,LW * ? 2030)", Dialect::Aws),
+      ("30 0/5 14,18 ? * 6#3 2030", Dialect::Quartz),
+      ("*/30 0 12 13 * FRI", Dialect::Spring),
+    ];
+    for &(source, dialect) in crontabs.iter() {
+      let crontab = Crontab::parse_dialect(source, dialect).unwrap();
+      let compact = super::compact_source(&crontab);
+      let deserialized = Crontab::deserialize(Compact(&compact)).unwrap();
+
+      expect!(deserialized.dialect()).to(be_equal_to(dialect));
+      expect!(deserialized).to(be_equal_to(crontab));
+    }
+    expect!(super::compact_source(&Crontab::parse_dialect("0 12 * * ? *", Dialect::Aws).unwrap()))
+        .to(be_equal_to("aws:0 12 * * ? *".to_string()));
+
+    expect!(Crontab::deserialize(Compact("aws:0 12 * * *"))).to(be_err());
+    expect!(Crontab::deserialize(Compact("jenkins:H * * * *"))).to(be_err());
+  }
+}
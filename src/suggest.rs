@@ -0,0 +1,93 @@
+//! "Did you mean" suggestions for mistyped names.
+
+/// The candidate most similar to a mistyped word, if any is close enough.
+/// Case is ignored, and a word starting with a candidate, such as "MONDAY"
+/// for "MON", also counts as close.
+pub (crate) fn suggest<'a>(word: &str, candidates: &[&'a str]) -> Option<&'a str> {
+  let word = word.to_lowercase();
+
+  if let Some(candidate) = candidates.iter()
+      .find(|candidate| word.starts_with(&candidate.to_lowercase())) {
+    return Some(candidate);
+  }
+
+  candidates.iter()
+      .map(|candidate| (distance(&word, &candidate.to_lowercase()), *candidate))
+      .filter(|&(distance, candidate)| distance <= (candidate.len() / 3).max(1))
+      .min_by_key(|&(distance, _)| distance)
+      .map(|(_, candidate)| candidate)
+}
+
+/// The edit distance between two words: the fewest insertions, deletions,
+/// substitutions and swaps of adjacent characters that turn one into the
+/// other.
+fn distance(a: &str, b: &str) -> usize {
+  let a : Vec<char> = a.chars().collect();
+  let b : Vec<char> = b.chars().collect();
+
+  // distances[i][j] is the distance between a[..i] and b[..j].
+  let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+  distances[0] = (0 .. b.len() + 1).collect();
+  for (i, row) in distances.iter_mut().enumerate() {
+    row[0] = i;
+  }
+
+  for i in 1 .. a.len() + 1 {
+    for j in 1 .. b.len() + 1 {
+      let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+
+      let mut best = (distances[i - 1][j] + 1)
+          .min(distances[i][j - 1] + 1)
+          .min(distances[i - 1][j - 1] + cost);
+
+      if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+        best = best.min(distances[i - 2][j - 2] + 1);
+      }
+
+      distances[i][j] = best;
+    }
+  }
+
+  distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+
+  const WEEKDAYS : [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+  const MONTHS : [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN",
+    "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+  ];
+
+  #[test]
+  fn distances() {
+    expect!(distance("mon", "mon")).to(be_equal_to(0));
+    expect!(distance("mno", "mon")).to(be_equal_to(1));
+    expect!(distance("mun", "mon")).to(be_equal_to(1));
+    expect!(distance("mo", "mon")).to(be_equal_to(1));
+    expect!(distance("@dialy", "@daily")).to(be_equal_to(1));
+    expect!(distance("", "abc")).to(be_equal_to(3));
+    expect!(distance("kitten", "sitting")).to(be_equal_to(3));
+  }
+
+  #[test]
+  fn suggestions() {
+    expect!(suggest("MNO", &WEEKDAYS)).to(be_some().value("MON"));
+    expect!(suggest("Jnu", &MONTHS)).to(be_some().value("JUN"));
+    expect!(suggest("fir", &WEEKDAYS)).to(be_some().value("FRI"));
+    expect!(suggest("Monday", &WEEKDAYS)).to(be_some().value("MON"));
+    expect!(suggest("@dialy", &["@daily", "@hourly"])).to(be_some().value("@daily"));
+    expect!(suggest("@hurly", &["@daily", "@hourly"])).to(be_some().value("@hourly"));
+  }
+
+  #[test]
+  fn no_suggestions() {
+    expect!(suggest("XYZ", &WEEKDAYS)).to(be_none());
+    expect!(suggest("JAN", &WEEKDAYS)).to(be_none());
+    expect!(suggest("@reboot", &["@daily", "@hourly"])).to(be_none());
+  }
+}
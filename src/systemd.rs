@@ -0,0 +1,590 @@
+//! systemd calendar events, as in the `OnCalendar=` setting of timer units,
+//! eg. `Mon..Fri *-*-* 09:00:00`.
+//!
+//! systemd runs a timer when the weekday *and* the date match, where cron
+//! runs a job when either day field does, so a weekday is only combined with
+//! days that pick one weekday of the month, such as `Mon *-*-01..07`.
+
+use ast::FieldKind;
+use calendar::Weekday;
+use crontab::{Crontab, calculate_next_event, day_rule};
+use error::CrontabError;
+use explain::DayRule;
+use formatting::{FieldItem, field_items};
+use parsing::{DaySpecial, ScheduleComponents, check_range};
+use std::collections::BTreeSet;
+use time::Tm;
+
+/// A systemd calendar event, mapped onto schedule components. Seconds must
+/// be 0, since schedules are to the minute.
+///
+/// ```
+/// # extern crate crontab;
+/// # extern crate time;
+/// # fn main() {
+/// use crontab::{CalendarEvent, Crontab};
+///
+/// let event = CalendarEvent::parse("Mon..Fri *-*-* 09:00:00").unwrap();
+/// let crontab = Crontab::parse("0 9 * * MON-FRI").unwrap();
+/// assert_eq!(&event.schedule, crontab.schedule());
+///
+/// // Saturday 2017-10-14 12:00 UTC.
+/// let saturday = time::at_utc(time::Timespec::new(1507982400, 0));
+/// assert_eq!(event.find_event_after(&saturday), crontab.find_event_after(&saturday));
+///
+/// assert_eq!(crontab.to_on_calendar().unwrap(), ["Mon..Fri *-*-* 09:00:00"]);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CalendarEvent {
+  /// The components of the event.
+  pub schedule: ScheduleComponents,
+  /// The time zone the event is in, eg. `Europe/Berlin`, if it names one.
+  pub zone: Option<String>,
+}
+
+/// The shorthands systemd accepts for whole events.
+const SHORTHANDS : [(&str, &str); 9] = [
+  ("minutely", "*-*-* *:*:00"),
+  ("hourly", "*-*-* *:00:00"),
+  ("daily", "*-*-* 00:00:00"),
+  ("monthly", "*-*-01 00:00:00"),
+  ("weekly", "Mon *-*-* 00:00:00"),
+  ("yearly", "*-01-01 00:00:00"),
+  ("annually", "*-01-01 00:00:00"),
+  ("quarterly", "*-01,04,07,10-01 00:00:00"),
+  ("semiannually", "*-01,07-01 00:00:00"),
+];
+
+/// Weekdays in systemd's order, from Monday.
+const WEEK : [Weekday; 7] = [
+  Weekday::Mon,
+  Weekday::Tue,
+  Weekday::Wed,
+  Weekday::Thu,
+  Weekday::Fri,
+  Weekday::Sat,
+  Weekday::Sun,
+];
+
+impl CalendarEvent {
+  /// Parse a calendar event: an optional weekday, an optional date, an
+  /// optional time and an optional time zone, eg. `Sat,Sun 10:00`,
+  /// `*-*-01 00:00` or `*:0/15`, or a shorthand such as `hourly`. Dates
+  /// may count back from the end of the month, as in `*-*~01` for its last
+  /// day.
+  pub fn parse(spec: &str) -> Result<CalendarEvent, CrontabError> {
+    let invalid = || CrontabError::ErrCronFormat(format!("Invalid calendar event: {}", spec));
+
+    let mut words : Vec<&str> = spec.split_whitespace().collect();
+    if let Some(&(_, expansion)) = words.first()
+        .and_then(|word| SHORTHANDS.iter().find(|&&(name, _)| name.eq_ignore_ascii_case(word))) {
+      words.splice(.. 1, expansion.split(' '));
+    }
+
+    let mut words = words.into_iter().peekable();
+
+    let weekdays = match words.peek() {
+      Some(word) if word.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+        parse_weekdays(words.next().unwrap()).ok_or_else(invalid)?
+      },
+      _ => Vec::new(),
+    };
+
+    let date = match words.peek() {
+      Some(word) if !word.contains(':') && word.contains(['-', '~']) => words.next(),
+      _ => None,
+    };
+
+    let time = match words.peek() {
+      Some(word) if word.contains(':') => words.next(),
+      _ => None,
+    };
+
+    let zone = words.next().map(|zone| zone.to_string());
+    if words.next().is_some() || (weekdays.is_empty() && date.is_none() && time.is_none()) {
+      return Err(invalid());
+    }
+
+    let (years, months, days) = match date {
+      Some(date) => parse_date(date).ok_or_else(invalid)?,
+      None => (None, every(FieldKind::Month), Days::Every),
+    };
+
+    let (hours, minutes, seconds) = match time {
+      Some(time) => parse_time(time).ok_or_else(invalid)?,
+      None => (vec![0], vec![0], vec![0]),
+    };
+    if seconds != [0] {
+      return Err(CrontabError::ErrCronFormat(format!(
+        "Schedules are to the minute, so seconds must be 0: {}", spec)));
+    }
+
+    let (days, weekdays, day_specials) = combine_days(days, weekdays)
+        .ok_or_else(|| CrontabError::ErrCronFormat(format!(
+          "systemd runs when both the weekday and the day match, which cron can't \
+           express: {}", spec)))?;
+
+    let schedule = ScheduleComponents::with_extensions(
+      minutes, hours, days, months, weekdays, years, day_specials)?;
+
+    Ok(CalendarEvent { schedule, zone })
+  }
+
+  /// The next time the event happens after `time`, like
+  /// `Crontab::find_event_after`.
+  pub fn find_event_after(&self, time: &Tm) -> Option<Tm> {
+    calculate_next_event(&self.schedule, time)
+  }
+
+  /// The event as `OnCalendar=` values, one per line a timer needs. See
+  /// `Crontab::to_on_calendar`.
+  pub fn to_on_calendar(&self) -> Result<Vec<String>, CrontabError> {
+    on_calendar(&self.schedule, self.zone.as_deref())
+  }
+
+  /// The event as a crontab, if cron can express it: Vixie cron has no
+  /// years or days counted from the end of the month.
+  pub fn to_crontab(&self) -> Result<Crontab, CrontabError> {
+    if self.schedule.years().is_some() || !self.schedule.day_specials().is_empty() {
+      return Err(CrontabError::ErrCronFormat(
+        "Vixie cron can't express years or days counted from the end of the month"
+            .to_string()));
+    }
+
+    let expression = self.schedule.to_expression();
+    match self.zone {
+      Some(ref zone) => Crontab::parse(&format!("CRON_TZ={} {}", zone, expression)),
+      None => Crontab::parse(&expression),
+    }
+  }
+}
+
+impl Crontab {
+  /// The schedule as `OnCalendar=` values for a systemd timer, with the
+  /// crontab's time zone. A timer runs when any of its values match, so
+  /// days picked by either day field, as in `0 0 1 * MON`, become a value
+  /// for each. Days near a weekday, as in `LW` and `15W`, can't be
+  /// expressed.
+  ///
+  /// ```
+  /// let crontab = crontab::Crontab::parse("CRON_TZ=UTC */15 9-17 1 * MON").unwrap();
+  /// assert_eq!(crontab.to_on_calendar().unwrap(), [
+  ///   "*-*-01 09..17:00/15:00 UTC",
+  ///   "Mon *-*-* 09..17:00/15:00 UTC",
+  /// ]);
+  /// ```
+  pub fn to_on_calendar(&self) -> Result<Vec<String>, CrontabError> {
+    on_calendar(self.schedule(), self.zone().map(|zone| zone.name.as_str()))
+  }
+}
+
+/// The days a date picks.
+enum Days {
+  /// Every day of the month.
+  Every,
+  /// Days of the month.
+  Listed(Vec<u32>),
+  /// Days counted back from the end of the month, where 1 is the last day.
+  FromEnd(Vec<u32>),
+}
+
+/// Every value of a field.
+fn every(kind: FieldKind) -> Vec<u32> {
+  (kind.min() ..= kind.max()).collect()
+}
+
+/// Weekdays such as `Mon..Fri` or `Sat,Sun`, as cron numbers.
+fn parse_weekdays(word: &str) -> Option<Vec<u32>> {
+  let day = |name: &str| {
+    WEEK.iter().position(|weekday| {
+      name.eq_ignore_ascii_case(weekday.name()) || name.eq_ignore_ascii_case(weekday.abbreviation())
+    })
+  };
+
+  let mut weekdays = BTreeSet::new();
+  for item in word.split(',') {
+    let (start, end) = match item.find("..") {
+      Some(dots) => (day(&item[.. dots])?, day(&item[dots + 2 ..])?),
+      None => (day(item)?, day(item)?),
+    };
+
+    if start > end {
+      return None;
+    }
+    weekdays.extend(WEEK[start ..= end].iter().map(|weekday| weekday.number()));
+  }
+
+  Some(weekdays.into_iter().collect())
+}
+
+/// A date such as `*-*-01`, `2030-01,07-15` or `*-02~03`, as its years, or
+/// `None` for every year, its months and its days.
+fn parse_date(date: &str) -> Option<(Option<Vec<u32>>, Vec<u32>, Days)> {
+  let (date, from_end) = match date.find('~') {
+    Some(tilde) => (&date[.. tilde], Some(&date[tilde + 1 ..])),
+    None => (date, None),
+  };
+
+  let mut parts : Vec<&str> = date.split('-').collect();
+  if from_end.is_none() {
+    parts.pop();
+  }
+
+  let (year, month) = match parts[..] {
+    [year, month] => (year, month),
+    [month] => ("*", month),
+    _ => return None,
+  };
+
+  let years = match year {
+    "*" => None,
+    year => Some(parse_values(year, FieldKind::Year.min(), FieldKind::Year.max())?),
+  };
+  let months = parse_values(month, FieldKind::Month.min(), FieldKind::Month.max())?;
+
+  let days = match from_end {
+    Some(day) => Days::FromEnd(parse_repeated(day, 1, 31, true)?),
+    None => match date.rsplit('-').next()? {
+      "*" => Days::Every,
+      day => Days::Listed(parse_values(day, 1, 31)?),
+    },
+  };
+
+  Some((years, months, days))
+}
+
+/// A time such as `09:00`, `*:0/15` or `10:30:00`, as its hours, minutes
+/// and seconds.
+fn parse_time(time: &str) -> Option<(Vec<u32>, Vec<u32>, Vec<u32>)> {
+  let parts : Vec<&str> = time.split(':').collect();
+  let (hour, minute, second) = match parts[..] {
+    [hour, minute] => (hour, minute, "00"),
+    [hour, minute, second] => (hour, minute, second),
+    _ => return None,
+  };
+
+  Some((parse_values(hour, 0, 23)?, parse_values(minute, 0, 59)?, parse_values(second, 0, 59)?))
+}
+
+/// The values of a component such as `*`, `1,15`, `9..17` or `0/15`. As in
+/// systemd, a repetition counts from its start to the end of the range.
+fn parse_values(component: &str, field_min: u32, field_max: u32) -> Option<Vec<u32>> {
+  parse_repeated(component, field_min, field_max, false)
+}
+
+/// Like `parse_values`, but a repetition without an end counts down to the
+/// start of the range when `down`, as days from the end of the month do, eg.
+/// `~07/1` for the last seven days.
+fn parse_repeated(component: &str, field_min: u32, field_max: u32, down: bool)
+    -> Option<Vec<u32>> {
+  let number = |text: &str| {
+    let value = text.parse::<u32>().ok()?;
+    check_range(value, value, field_min, field_max).ok().map(|_| value)
+  };
+
+  let mut values = BTreeSet::new();
+  for item in component.split(',') {
+    let (range, step) = match item.find('/') {
+      Some(slash) => (&item[.. slash], Some(item[slash + 1 ..].parse::<usize>().ok()?)),
+      None => (item, None),
+    };
+    if step == Some(0) {
+      return None;
+    }
+
+    let (start, end) = match (range, range.find("..")) {
+      ("*", _) => (field_min, field_max),
+      (_, Some(dots)) => (number(&range[.. dots])?, number(&range[dots + 2 ..])?),
+      (_, None) if step.is_some() && down => {
+        let start = number(range)?;
+        values.extend((field_min ..= start).rev().step_by(step.unwrap_or(1)));
+        continue;
+      },
+      (_, None) if step.is_some() => (number(range)?, field_max),
+      (_, None) => (number(range)?, number(range)?),
+    };
+
+    if start > end {
+      return None;
+    }
+    values.extend((start ..= end).step_by(step.unwrap_or(1)));
+  }
+
+  Some(values.into_iter().collect())
+}
+
+/// The days and weekdays of schedule components for a date and weekdays,
+/// with days picked by their place in the month. `None` when the weekdays
+/// narrow the days in a way cron can't express.
+fn combine_days(days: Days, weekdays: Vec<u32>)
+    -> Option<(Vec<u32>, Vec<u32>, Vec<DaySpecial>)> {
+  let all_weekdays = every(FieldKind::DayOfWeek);
+
+  match (days, weekdays.is_empty()) {
+    (Days::Every, true) => Some((every(FieldKind::DayOfMonth), all_weekdays, Vec::new())),
+    (Days::Every, false) => Some((every(FieldKind::DayOfMonth), weekdays, Vec::new())),
+    (Days::Listed(days), true) => Some((days, all_weekdays, Vec::new())),
+    (Days::FromEnd(days), true) => {
+      let specials = days.iter().map(|day| DaySpecial::LastDay(day - 1)).collect();
+      Some((Vec::new(), all_weekdays, specials))
+    },
+    // The week a weekday falls in, eg. `Mon *-*-08..14` for the second Monday.
+    (Days::Listed(days), false) => {
+      let n = (days[0] - 1) / 7 + 1;
+      let week : Vec<u32> = (n * 7 - 6 ..= (n * 7).min(31)).collect();
+      if days != week {
+        return None;
+      }
+      let specials = weekdays.iter().map(|&weekday| DaySpecial::Nth(weekday, n)).collect();
+      Some((every(FieldKind::DayOfMonth), Vec::new(), specials))
+    },
+    // The last week of the month, eg. `Fri *-*~07/1` for the last Friday.
+    (Days::FromEnd(days), false) => {
+      if days != [1, 2, 3, 4, 5, 6, 7] {
+        return None;
+      }
+      let specials = weekdays.iter().map(|&weekday| DaySpecial::Last(weekday)).collect();
+      Some((every(FieldKind::DayOfMonth), Vec::new(), specials))
+    },
+  }
+}
+
+/// The `OnCalendar=` values for schedule components: one for the days of
+/// the month, one for the weekdays and one per day special, as the day
+/// rule needs.
+fn on_calendar(schedule: &ScheduleComponents, zone: Option<&str>)
+    -> Result<Vec<String>, CrontabError> {
+  let years = match schedule.years() {
+    Some(years) => component(years, FieldKind::Year.min(), FieldKind::Year.max(), 4),
+    None => "*".to_string(),
+  };
+  let months = component(schedule.months(), 1, 12, 2);
+  let seconds = schedule.seconds().map_or("00".to_string(), |seconds| component(seconds, 0, 59, 2));
+  let time = format!("{}:{}:{}",
+                     component(schedule.hours(), 0, 23, 2),
+                     component(schedule.minutes(), 0, 59, 2),
+                     seconds);
+
+  let event = |weekdays: Option<String>, date: String| {
+    let mut event = weekdays.map_or(String::new(), |weekdays| format!("{} ", weekdays));
+    event.push_str(&format!("{}-{}{} {}", years, months, date, time));
+    if let Some(zone) = zone {
+      event.push_str(&format!(" {}", zone));
+    }
+    event
+  };
+
+  let rule = day_rule(schedule);
+  let mut events = Vec::new();
+
+  // systemd matches a day when both its weekday and its date do.
+  if rule == DayRule::Both {
+    if !schedule.day_specials().is_empty() {
+      return Err(CrontabError::ErrCronFormat(
+        "systemd can't express days picked by both their place and day fields".to_string()));
+    }
+    let date = format!("-{}", component(schedule.days(), 1, 31, 2));
+    return Ok(vec![event(Some(weekday_names(schedule.weekdays())), date)]);
+  }
+
+  if rule == DayRule::Any {
+    events.push(event(None, "-*".to_string()));
+  }
+  if (rule == DayRule::DayOfMonth || rule == DayRule::Either) && !schedule.days().is_empty() {
+    events.push(event(None, format!("-{}", component(schedule.days(), 1, 31, 2))));
+  }
+  if (rule == DayRule::DayOfWeek || rule == DayRule::Either) && !schedule.weekdays().is_empty() {
+    events.push(event(Some(weekday_names(schedule.weekdays())), "-*".to_string()));
+  }
+
+  for &special in schedule.day_specials() {
+    let name = |weekday: u32| weekday_names(&[weekday]);
+    events.push(match special {
+      DaySpecial::LastDay(offset) => event(None, format!("~{:02}", offset + 1)),
+      DaySpecial::Last(weekday) => event(Some(name(weekday)), "~07/1".to_string()),
+      DaySpecial::Nth(weekday, n) => {
+        event(Some(name(weekday)), format!("-{:02}..{:02}", n * 7 - 6, (n * 7).min(31)))
+      },
+      DaySpecial::LastBusinessDay | DaySpecial::NearestBusinessDay(_) => {
+        return Err(CrontabError::ErrCronFormat(
+          "systemd can't express the weekday nearest a day".to_string()));
+      },
+    });
+  }
+
+  Ok(events)
+}
+
+/// A component of a date or time, eg. `09..17` or `00/15`.
+fn component(values: &[u32], field_min: u32, field_max: u32, width: usize) -> String {
+  let number = |value: u32| format!("{:0width$}", value, width = width);
+
+  let items : Vec<String> = field_items(values, field_min, field_max).iter()
+      .map(|item| match *item {
+        FieldItem::All => "*".to_string(),
+        FieldItem::Step(step) => format!("{}/{}", number(field_min), step),
+        // Steps run to the end of the field in systemd.
+        FieldItem::SteppedRange(start, end, step) if end + step > field_max => {
+          format!("{}/{}", number(start), step)
+        },
+        // systemd has no steps within a range, so the values are listed.
+        FieldItem::SteppedRange(start, end, step) => {
+          (start ..= end).step_by(step as usize)
+              .map(number)
+              .collect::<Vec<String>>()
+              .join(",")
+        },
+        FieldItem::Range(start, end) => format!("{}..{}", number(start), number(end)),
+        FieldItem::Value(value) => number(value),
+      })
+      .collect();
+
+  items.join(",")
+}
+
+/// Weekdays as systemd writes them, from Monday, with runs of three or more
+/// as ranges, eg. `Mon..Fri` or `Sat,Sun`.
+fn weekday_names(weekdays: &[u32]) -> String {
+  let mut names = Vec::new();
+  let mut run : Vec<Weekday> = Vec::new();
+  let mut flush = |run: &mut Vec<Weekday>| {
+    let title = |weekday: &Weekday| weekday.name()[.. 3].to_string();
+    match run.len() {
+      0 => {},
+      1 | 2 => names.extend(run.iter().map(title)),
+      _ => names.push(format!("{}..{}", title(&run[0]), title(&run[run.len() - 1]))),
+    }
+    run.clear();
+  };
+
+  for weekday in WEEK.iter() {
+    if weekdays.contains(&weekday.number()) {
+      run.push(*weekday);
+    } else {
+      flush(&mut run);
+    }
+  }
+  flush(&mut run);
+
+  names.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+  use test_helpers::{get_tm, normal};
+
+  fn cron(spec: &str) -> String {
+    CalendarEvent::parse(spec).unwrap().schedule.to_expression()
+  }
+
+  fn systemd(schedule: &str) -> Vec<String> {
+    Crontab::parse(schedule).unwrap().to_on_calendar().unwrap()
+  }
+
+  #[test]
+  fn parse() {
+    expect!(cron("Mon..Fri *-*-* 09:00:00")).to(be_equal_to("0 9 * * 1-5".to_string()));
+    expect!(cron("*-*-01 00:00")).to(be_equal_to("0 0 1 * *".to_string()));
+    expect!(cron("Sat,Sun 10:00")).to(be_equal_to("0 10 * * 0,6".to_string()));
+    expect!(cron("*:0/15")).to(be_equal_to("*/15 * * * *".to_string()));
+    expect!(cron("*:5/20")).to(be_equal_to("5,25,45 * * * *".to_string()));
+    expect!(cron("monday,WEDNESDAY 12:30")).to(be_equal_to("30 12 * * 1,3".to_string()));
+    expect!(cron("Mon")).to(be_equal_to("0 0 * * 1".to_string()));
+    expect!(cron("01,07-15 8..17:00")).to(be_equal_to("0 8-17 15 1,7 *".to_string()));
+  }
+
+  #[test]
+  fn shorthands() {
+    expect!(cron("minutely")).to(be_equal_to("* * * * *".to_string()));
+    expect!(cron("hourly")).to(be_equal_to("0 * * * *".to_string()));
+    expect!(cron("daily")).to(be_equal_to("0 0 * * *".to_string()));
+    expect!(cron("weekly")).to(be_equal_to("0 0 * * 1".to_string()));
+    expect!(cron("monthly")).to(be_equal_to("0 0 1 * *".to_string()));
+    expect!(cron("Yearly")).to(be_equal_to("0 0 1 1 *".to_string()));
+    expect!(cron("quarterly")).to(be_equal_to("0 0 1 */3 *".to_string()));
+    expect!(cron("semiannually")).to(be_equal_to("0 0 1 1,7 *".to_string()));
+  }
+
+  #[test]
+  fn years_zones_and_days_from_the_end() {
+    let event = CalendarEvent::parse("2030..2031-*-* 06:00 Europe/Berlin").unwrap();
+    expect!(event.schedule.years()).to(be_some().value(&[2030, 2031][..]));
+    expect!(event.zone.clone()).to(be_some().value("Europe/Berlin".to_string()));
+
+    let event = CalendarEvent::parse("*-*~01 23:00").unwrap();
+    expect!(event.schedule.day_specials()).to(be_equal_to(&[DaySpecial::LastDay(0)][..]));
+
+    let event = CalendarEvent::parse("Fri *-*~07/1 23:00").unwrap();
+    expect!(event.schedule.day_specials()).to(be_equal_to(&[DaySpecial::Last(5)][..]));
+
+    let event = CalendarEvent::parse("Mon,Tue *-*-08..14").unwrap();
+    expect!(event.schedule.day_specials()).to(be_equal_to(
+      &[DaySpecial::Nth(1, 2), DaySpecial::Nth(2, 2)][..]));
+  }
+
+  #[test]
+  fn errors() {
+    for spec in &["", "Mon..Fri *-*-* 09:00:30", "Fri..Mon", "*-*-32", "25:00", "*:0/0",
+                  "Mon *-*-01", "Mon *-*~02", "daily UTC extra", "*-13-01", "1969-*-*"] {
+      expect!(CalendarEvent::parse(spec)).to(be_err());
+    }
+  }
+
+  #[test]
+  fn find_event_after() {
+    // Saturday 2017-10-14.
+    let start = get_tm(2017, 10, 14, 12, 0, 0);
+    let next = |spec: &str| {
+      CalendarEvent::parse(spec).unwrap().find_event_after(&start).map(|next| normal(&next))
+    };
+
+    expect!(next("Mon..Fri *-*-* 09:00:00")).to(be_some().value(get_tm(2017, 10, 16, 9, 0, 0)));
+    expect!(next("*:0/15")).to(be_some().value(get_tm(2017, 10, 14, 12, 15, 0)));
+    expect!(next("*-*~01")).to(be_some().value(get_tm(2017, 10, 31, 0, 0, 0)));
+    expect!(next("Mon *-*-08..14")).to(be_some().value(get_tm(2017, 11, 13, 0, 0, 0)));
+    expect!(next("2016-*-*")).to(be_none());
+  }
+
+  #[test]
+  fn to_on_calendar() {
+    expect!(systemd("0 9 * * MON-FRI")).to(be_equal_to(vec!["Mon..Fri *-*-* 09:00:00".to_string()]));
+    expect!(systemd("*/15 * * * *")).to(be_equal_to(vec!["*-*-* *:00/15:00".to_string()]));
+    expect!(systemd("0 0 1 */3 *")).to(be_equal_to(vec!["*-01/3-01 00:00:00".to_string()]));
+    expect!(systemd("0 8-18/2 * * 0,6")).to(be_equal_to(
+      vec!["Sat,Sun *-*-* 08,10,12,14,16,18:00:00".to_string()]));
+    expect!(systemd("0 0 1 * 1")).to(be_equal_to(vec![
+      "*-*-01 00:00:00".to_string(),
+      "Mon *-*-* 00:00:00".to_string(),
+    ]));
+
+    let aws = |schedule: &str| {
+      Crontab::parse_dialect(schedule, ::dialect::Dialect::Aws).unwrap().to_on_calendar()
+    };
+    expect!(aws("0 12 L * ? 2030").unwrap()).to(be_equal_to(vec!["2030-*~01 12:00:00".to_string()]));
+    expect!(aws("0 12 ? * 2#1,6L *").unwrap()).to(be_equal_to(vec![
+      "Fri *-*~07/1 12:00:00".to_string(),
+      "Mon *-*-01..07 12:00:00".to_string(),
+    ]));
+    expect!(aws("0 12 LW * ? *")).to(be_err());
+  }
+
+  #[test]
+  fn round_trip() {
+    for schedule in &["0 9 * * 1-5", "*/15 * * * *", "0 0 1 1,7 *", "30 2 * * 0", "5 4 * * 0,6",
+                      "0 */3 * * *", "0 0 1,15 * *", "0 22 * * 1-3,5"] {
+      let crontab = Crontab::parse(schedule).unwrap();
+      let events = crontab.to_on_calendar().unwrap();
+      expect!(events.len()).to(be_equal_to(1));
+
+      let event = CalendarEvent::parse(&events[0]).unwrap();
+      expect!(event.to_crontab().unwrap()).to(be_equal_to(crontab));
+    }
+
+    let event = CalendarEvent::parse("*-*-* 09:00 UTC").unwrap();
+    expect!(event.to_crontab().unwrap().to_string()).to(be_equal_to("CRON_TZ=UTC 0 9 * * *".to_string()));
+    expect!(CalendarEvent::parse("*-*~01").unwrap().to_crontab()).to(be_err());
+  }
+}
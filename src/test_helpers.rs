@@ -43,7 +43,7 @@ pub (crate) fn get_tm(year: i32,
 
 /// Normalize a Tm to drop certain fields entirely.
 pub (crate) fn normal(time: &Tm) -> Tm {
-  let mut tm = time.clone();
+  let mut tm = *time;
   tm.tm_wday = 0;
   tm.tm_yday = 0;
   tm.tm_isdst = 0;
@@ -16,37 +16,69 @@ pub (crate) fn adv_month(time: &mut Tm) {
   }
 }
 
+/// Whether the year of the Tm is a leap year.
+fn is_leap_year(time: &Tm) -> bool {
+  let year = time.tm_year + 1900;
+  year % 400 == 0 || (year % 4 == 0 && year % 100 != 0)
+}
+
+/// The number of days in the Tm's month.
+pub (crate) fn days_in_month(time: &Tm) -> i32 {
+  match time.tm_mon {
+    1 if is_leap_year(time) => 29,
+    1 => 28,
+    3 | 5 | 8 | 10 => 30,
+    _ => 31,
+  }
+}
+
+/// The day of the week [0,6] of the Tm's date, with 0 as Sunday. Unlike
+/// `tm_wday`, this is always consistent with the year, month and day.
+pub (crate) fn weekday(time: &Tm) -> u32 {
+  // Sakamoto's method.
+  let offsets = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+  let month = time.tm_mon as usize;
+  let mut year = time.tm_year as i64 + 1900;
+  if month < 2 {
+    year -= 1;
+  }
+
+  let day = year + year / 4 - year / 100 + year / 400
+      + offsets[month] + time.tm_mday as i64;
+  day.rem_euclid(7) as u32
+}
+
+/// The zero-indexed day of the year of the Tm's date.
+pub (crate) fn day_of_year(time: &Tm) -> i32 {
+  let mut days_before = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+  if is_leap_year(time) {
+    for days in days_before.iter_mut().skip(2) {
+      *days += 1;
+    }
+  }
+
+  days_before[time.tm_mon as usize] + time.tm_mday - 1
+}
+
 /// Advance the day, but leave the hour, minute, and second untouched.
 pub (crate) fn adv_day(time: &mut Tm) {
   time.tm_wday = (time.tm_wday + 1) % 7; // day of week
   time.tm_mday += 1; // day of month
 
-  let is_leap_year = {
-    let year = time.tm_year + 1900;
-    if year % 400 == 0
-        || (year % 4 == 0 && year % 100 != 0) {
-      true
-    } else {
-      false
-    }
-  };
+  let is_leap_year = is_leap_year(time);
 
   let days_in_year = if is_leap_year { 366 } else { 365 };
 
   time.tm_yday = (time.tm_yday + 1) % days_in_year; // day of year
 
   match time.tm_mon {
-    0 | 2 | 4 | 6 | 7 | 9 | 11 => {
-      if time.tm_mday > 31 {
-        time.tm_mday = 1;
-        adv_month(time);
-      }
+    0 | 2 | 4 | 6 | 7 | 9 | 11 if time.tm_mday > 31 => {
+      time.tm_mday = 1;
+      adv_month(time);
     },
-    3 | 5 | 8 | 10 => {
-      if time.tm_mday > 30 {
-        time.tm_mday = 1;
-        adv_month(time);
-      }
+    3 | 5 | 8 | 10 if time.tm_mday > 30 => {
+      time.tm_mday = 1;
+      adv_month(time);
     },
     1 => {
       let mdays = if is_leap_year { 29 } else { 28 };
@@ -86,6 +118,25 @@ mod tests {
   use test_helpers::normal;
   use time::{Timespec, at_utc};
 
+  #[test]
+  pub fn test_weekday_and_day_of_year() {
+    // 2017-01-01 00:00 UTC, a non-leap year starting on a Sunday (tm_wday=0).
+    let mut tm = at_utc(Timespec::new(1483228800, 0));
+
+    // Through 2020, a leap year.
+    for _ in 0 .. (365 * 4 + 1) {
+      expect!(weekday(&tm)).to(be_equal_to(tm.tm_wday as u32));
+      expect!(day_of_year(&tm)).to(be_equal_to(tm.tm_yday));
+      adv_day(&mut tm);
+    }
+
+    // Centuries are only leap years every 400 years.
+    expect!(weekday(&get_tm(2000, 3, 1, 0, 0, 0))).to(be_equal_to(3));
+    expect!(day_of_year(&get_tm(2000, 3, 1, 0, 0, 0))).to(be_equal_to(60));
+    expect!(weekday(&get_tm(2100, 3, 1, 0, 0, 0))).to(be_equal_to(1));
+    expect!(day_of_year(&get_tm(2100, 3, 1, 0, 0, 0))).to(be_equal_to(59));
+  }
+
   #[test]
   pub fn test_adv_year() {
     let mut tm = get_tm(2017, 10, 6, 12, 24, 0);
@@ -0,0 +1,151 @@
+//! Conversion of schedules between dialects, reporting what doesn't carry
+//! over as it is.
+
+use crontab::{Crontab, day_rule, in_dialect, unexpressible};
+use dialect::Dialect;
+use error::CrontabError;
+use explain::DayRule;
+
+/// A schedule written in another dialect. See `Crontab::transpile`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transpiled {
+  /// The schedule in the dialect. There is one crontab, or one for each
+  /// day field when the dialect can't fire on days matching either of them.
+  pub crontabs: Vec<Crontab>,
+  /// What has to be done outside the expressions to keep the schedule, eg.
+  /// setting a time zone that the dialect has no prefix for.
+  pub warnings: Vec<String>,
+}
+
+impl Crontab {
+  /// The schedule written in another dialect, as far as it can be without
+  /// changing when it fires. Unlike `Crontab::to_dialect`, this keeps the
+  /// schedule where it has to be moved around, with a warning for each
+  /// change:
+  ///
+  /// * A time zone prefix the dialect lacks is left out, with a warning to
+  ///   set the zone where the schedule is defined.
+  /// * Days matching either day field, as in `0 0 1 * MON`, become a
+  ///   crontab for each field, in dialects that need one of them to be `?`
+  ///   or that fire only on days matching both.
+  ///
+  /// Fails for what can't be moved: seconds other than 0, years, days such
+  /// as `L` or `MON#2`, and days matching both day fields, in dialects that
+  /// lack them. Jenkins-style hashed `H` values aren't accepted by any
+  /// dialect, so there are none to lose.
+  ///
+  /// ```
+  /// use crontab::{Crontab, Dialect};
+  ///
+  /// let crontab = Crontab::parse("CRON_TZ=Europe/Paris 0 6 1 * MON").unwrap();
+  /// let quartz = crontab.transpile(Dialect::Quartz).unwrap();
+  /// let expressions : Vec<String> = quartz.crontabs.iter().map(|c| c.to_string()).collect();
+  /// assert_eq!(expressions, ["0 0 6 1 * ?", "0 0 6 ? * 2"]);
+  /// assert_eq!(quartz.warnings.len(), 2);
+  ///
+  /// let crontab = Crontab::parse_dialect("0 0 6 L * ?", Dialect::Quartz).unwrap();
+  /// assert!(crontab.transpile(Dialect::Kubernetes).is_err());
+  /// ```
+  pub fn transpile(&self, dialect: Dialect) -> Result<Transpiled, CrontabError> {
+    let mut warnings = Vec::new();
+
+    let zone = match self.zone() {
+      Some(zone) if !dialect.zones() => {
+        warnings.push(zone_warning(dialect, &zone.name));
+        None
+      },
+      zone => zone,
+    };
+
+    let schedules = match day_rule(self.schedule()) {
+      DayRule::Either if dialect.both_days() || dialect.requires_no_specific_day() => {
+        warnings.push(format!(
+          "The {} dialect can't match days of either day field, so each has its own \
+           expression: run them all to keep the schedule", dialect));
+        let (by_day_of_month, by_day_of_week) = self.schedule().split_days();
+        vec![by_day_of_month, by_day_of_week]
+      },
+      _ => vec![self.schedule().clone()],
+    };
+
+    let mut crontabs = Vec::new();
+    for schedule in &schedules {
+      if let Some(unsupported) = unexpressible(schedule, dialect) {
+        return Err(self.unexpressible_error(dialect, unsupported));
+      }
+      crontabs.push(in_dialect(schedule, zone, dialect)?);
+    }
+
+    Ok(Transpiled { crontabs, warnings })
+  }
+}
+
+/// Where to set a time zone instead of a prefix, in a dialect without one.
+fn zone_warning(dialect: Dialect, zone: &str) -> String {
+  let place = match dialect {
+    Dialect::Kubernetes => "the CronJob's `timeZone` field",
+    Dialect::Aws => "the EventBridge schedule's time zone, which is otherwise UTC",
+    Dialect::Quartz => "the trigger's time zone",
+    Dialect::Spring => "the `zone` of `@Scheduled`",
+    Dialect::Vixie => "a `CRON_TZ` variable",
+  };
+  format!("The {} dialect has no time zone prefix: set {} to {}", dialect, place, zone)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expectest::prelude::*;
+
+  fn transpile(schedule: &str, from: Dialect, to: Dialect)
+      -> Result<(Vec<String>, usize), CrontabError> {
+    let transpiled = Crontab::parse_dialect(schedule, from).unwrap().transpile(to)?;
+    let crontabs = transpiled.crontabs.iter().map(|crontab| crontab.to_string()).collect();
+    Ok((crontabs, transpiled.warnings.len()))
+  }
+
+  #[test]
+  fn exact() {
+    expect!(transpile("*/15 9-17 * * 1-5", Dialect::Vixie, Dialect::Aws))
+        .to(be_ok().value((vec!["cron(*/15 9-17 ? * 2-6 *)".to_string()], 0)));
+    expect!(transpile("0 0 12 ? * 6#3", Dialect::Quartz, Dialect::Aws))
+        .to(be_ok().value((vec!["cron(0 12 ? * 6#3 *)".to_string()], 0)));
+    expect!(transpile("0 0/5 14,18 * * ?", Dialect::Quartz, Dialect::Kubernetes))
+        .to(be_ok().value((vec!["*/5 14,18 * * *".to_string()], 0)));
+    expect!(transpile("CRON_TZ=UTC @daily", Dialect::Vixie, Dialect::Vixie))
+        .to(be_ok().value((vec!["CRON_TZ=UTC 0 0 * * *".to_string()], 0)));
+  }
+
+  #[test]
+  fn warnings() {
+    let transpiled = Crontab::parse("TZ=Asia/Tokyo 0 9 * * *").unwrap()
+        .transpile(Dialect::Kubernetes).unwrap();
+    expect!(transpiled.crontabs[0].to_string()).to(be_equal_to("0 9 * * *".to_string()));
+    expect!(transpiled.warnings).to(be_equal_to(vec![
+      "The kubernetes dialect has no time zone prefix: set the CronJob's `timeZone` field \
+       to Asia/Tokyo".to_string(),
+    ]));
+
+    expect!(transpile("30 4 1,15 * 5", Dialect::Vixie, Dialect::Quartz))
+        .to(be_ok().value((vec!["0 30 4 1,15 * ?".to_string(),
+                                "0 30 4 ? * 6".to_string()], 1)));
+    expect!(transpile("0 0 1 * MON", Dialect::Kubernetes, Dialect::Spring))
+        .to(be_ok().value((vec!["0 0 0 1 * *".to_string(),
+                                "0 0 0 * * 1".to_string()], 1)));
+  }
+
+  #[test]
+  fn lossy() {
+    // Seconds, years, days from the end of the month and days matching
+    // both fields change when the schedule fires, so they fail.
+    expect!(transpile("30 0 9 * * ?", Dialect::Quartz, Dialect::Vixie)).to(be_err());
+    expect!(transpile("0 0 9 * * ? 2030", Dialect::Quartz, Dialect::Kubernetes)).to(be_err());
+    expect!(transpile("cron(0 9 * * ? 2030)", Dialect::Aws, Dialect::Spring)).to(be_err());
+    expect!(transpile("0 0 9 L * ?", Dialect::Quartz, Dialect::Vixie)).to(be_err());
+    expect!(transpile("0 0 0 13 * FRI", Dialect::Spring, Dialect::Vixie)).to(be_err());
+    expect!(transpile("0 0 0 13 * FRI", Dialect::Spring, Dialect::Quartz)).to(be_err());
+
+    expect!(transpile("*/30 * * * * ?", Dialect::Quartz, Dialect::Spring))
+        .to(be_ok().value((vec!["0,30 * * * * *".to_string()], 0)));
+  }
+}
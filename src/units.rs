@@ -0,0 +1,201 @@
+//! systemd timer and service units generated from crontab files, for moving
+//! jobs off cron.
+
+use file::{CrontabFile, Entry, LineKind, Variable};
+
+/// A systemd timer and the service it starts, for an entry of a crontab
+/// file. See `CrontabFile::to_systemd_units`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SystemdUnits {
+  /// The name of both units, without `.timer` or `.service`, eg. `cron-4`.
+  pub name: String,
+  /// The number of the line the entry is on.
+  pub line: usize,
+  /// The contents of the `.timer` unit.
+  pub timer: String,
+  /// The contents of the `.service` unit.
+  pub service: String,
+  /// What the units do differently from cron, eg. not mailing output to
+  /// `MAILTO`.
+  pub warnings: Vec<String>,
+}
+
+impl CrontabFile {
+  /// A timer and service for each entry, named after `prefix` and the line
+  /// the entry is on.
+  ///
+  /// The timer has an `OnCalendar=` for each `Crontab::to_on_calendar`
+  /// value, since cron runs a job on the days of either day field, or
+  /// `OnBootSec=0` for `@reboot`. The service runs the command with `SHELL`,
+  /// or `/bin/sh`, with an `Environment=` for each variable assigned before
+  /// the entry, and any input after a `%` as its standard input. A system
+  /// crontab's user becomes `User=`; a user's crontab has none, so install
+  /// its units as user units to run them as its owner.
+  ///
+  /// ```
+  /// let file = crontab::CrontabFile::parse(
+  ///   "PATH=/usr/bin:/bin\n30 2 * * * backup --all\n").unwrap();
+  /// let units = &file.to_systemd_units("cron")[0];
+  ///
+  /// assert_eq!(units.name, "cron-2");
+  /// assert!(units.timer.contains("\nOnCalendar=*-*-* 02:30:00\n"));
+  /// assert!(units.service.contains("\nEnvironment=\"PATH=/usr/bin:/bin\"\n"));
+  /// assert!(units.service.contains("\nExecStart=/bin/sh -c \"backup --all\"\n"));
+  /// ```
+  pub fn to_systemd_units(&self, prefix: &str) -> Vec<SystemdUnits> {
+    let mut environment : Vec<&Variable> = Vec::new();
+    let mut units = Vec::new();
+
+    for line in &self.lines {
+      match line.kind {
+        LineKind::Variable(ref variable) => {
+          environment.retain(|assigned| assigned.name != variable.name);
+          environment.push(variable);
+        },
+        LineKind::Entry(ref entry) => {
+          let name = format!("{}-{}", prefix, line.number);
+          units.push(entry_units(name, line.number, entry, &environment));
+        },
+        _ => {},
+      }
+    }
+
+    units
+  }
+}
+
+fn entry_units(name: String, line: usize, entry: &Entry, environment: &[&Variable])
+    -> SystemdUnits {
+  let mut warnings = Vec::new();
+  let command = entry.command_line();
+
+  let mut timer = format!("[Unit]\nDescription=Timer for {}\n\n[Timer]\n", escape(&command));
+  match entry.crontab {
+    Some(ref crontab) => match crontab.to_on_calendar() {
+      Ok(events) => {
+        for event in events {
+          timer.push_str(&format!("OnCalendar={}\n", event));
+        }
+      },
+      Err(error) => warnings.push(format!("The schedule has no OnCalendar= value: {}", error)),
+    },
+    None => timer.push_str("OnBootSec=0\n"),
+  }
+  timer.push_str("\n[Install]\nWantedBy=timers.target\n");
+
+  let mut service = format!("[Unit]\nDescription={}\n\n[Service]\nType=oneshot\n",
+                            escape(&command));
+  if let Some(ref user) = entry.user {
+    service.push_str(&format!("User={}\n", user));
+  }
+
+  let mut shell = "/bin/sh";
+  for variable in environment {
+    match variable.name.as_str() {
+      "SHELL" => shell = &variable.value,
+      "MAILTO" if !variable.value.is_empty() => {
+        warnings.push(format!("Output goes to the journal rather than being mailed to {}",
+                              variable.value));
+      },
+      _ => {},
+    }
+    service.push_str(&format!("Environment=\"{}={}\"\n",
+                              quote(&variable.name), quote(&variable.value)));
+  }
+
+  service.push_str(&format!("ExecStart={} -c \"{}\"\n", escape(shell), quote(&command)));
+  if let Some(input) = entry.input() {
+    for text in input.split('\n') {
+      service.push_str(&format!("StandardInputText={}\n", escape(text)));
+    }
+  }
+
+  SystemdUnits {
+    name,
+    line,
+    timer,
+    service,
+    warnings,
+  }
+}
+
+/// Text with systemd's `%` specifiers and `$` variables escaped, so it is
+/// used as written.
+fn escape(text: &str) -> String {
+  text.replace('%', "%%").replace('$', "$$")
+}
+
+/// Text escaped for inside double quotes.
+fn quote(text: &str) -> String {
+  escape(&text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use file::CrontabFile;
+
+  const FILE : &str = "\
+SHELL=/bin/bash
+MAILTO=ops@example.com
+30 2 * * * backup --all \"$HOME\"
+MAILTO=
+0 0 1 * MON report%first line%second 100\\%
+@reboot warm-cache
+";
+
+  #[test]
+  fn units() {
+    let file = CrontabFile::parse(FILE).unwrap();
+    let units = file.to_systemd_units("cron");
+    expect!(units.iter().map(|u| u.name.as_str()).collect::<Vec<&str>>())
+        .to(be_equal_to(vec!["cron-3", "cron-5", "cron-6"]));
+
+    expect!(units[0].timer.as_str()).to(be_equal_to(
+      "[Unit]\n\
+       Description=Timer for backup --all \"$$HOME\"\n\
+       \n\
+       [Timer]\n\
+       OnCalendar=*-*-* 02:30:00\n\
+       \n\
+       [Install]\n\
+       WantedBy=timers.target\n"));
+    expect!(units[0].service.as_str()).to(be_equal_to(
+      "[Unit]\n\
+       Description=backup --all \"$$HOME\"\n\
+       \n\
+       [Service]\n\
+       Type=oneshot\n\
+       Environment=\"SHELL=/bin/bash\"\n\
+       Environment=\"MAILTO=ops@example.com\"\n\
+       ExecStart=/bin/bash -c \"backup --all \\\"$$HOME\\\"\"\n"));
+    expect!(units[0].warnings.len()).to(be_equal_to(1));
+  }
+
+  #[test]
+  fn either_day_and_input() {
+    let file = CrontabFile::parse(FILE).unwrap();
+    let units = &file.to_systemd_units("cron")[1];
+
+    expect!(units.timer.contains("OnCalendar=*-*-01 00:00:00\nOnCalendar=Mon *-*-* 00:00:00\n"))
+        .to(be_true());
+    expect!(units.service.contains("Environment=\"MAILTO=\"\n")).to(be_true());
+    expect!(units.service.ends_with(
+      "ExecStart=/bin/bash -c \"report\"\n\
+       StandardInputText=first line\n\
+       StandardInputText=second 100%%\n")).to(be_true());
+    expect!(units.warnings.is_empty()).to(be_true());
+  }
+
+  #[test]
+  fn reboot_and_users() {
+    let file = CrontabFile::parse(FILE).unwrap();
+    expect!(file.to_systemd_units("cron")[2].timer.contains("\nOnBootSec=0\n")).to(be_true());
+
+    let file = CrontabFile::parse_system("17 * * * * root run-parts /etc/cron.hourly").unwrap();
+    let units = &file.to_systemd_units("cron-system")[0];
+    expect!(units.name.as_str()).to(be_equal_to("cron-system-1"));
+    expect!(units.service.contains("\nUser=root\n")).to(be_true());
+    expect!(units.timer.contains("\nOnCalendar=*-*-* *:17:00\n")).to(be_true());
+  }
+}